@@ -0,0 +1,121 @@
+use std::fmt;
+
+/// Which way the population has to cross a threshold for an alert to go off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Threshold {
+    /// Goes off when the population grows past this many cells.
+    Above(usize),
+    /// Goes off when the population shrinks below this many cells.
+    Below(usize),
+}
+
+/// What an alert does when it goes off, on top of saying so in the notice line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AlertAction {
+    /// Only the notice.
+    #[default]
+    Notice,
+    /// Rings the terminal bell.
+    Bell,
+    /// Pauses the simulation, if it's running.
+    Pause,
+}
+
+/// A population threshold to watch for while the board runs, given with `--alert`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alert {
+    pub threshold: Threshold,
+    pub action: AlertAction,
+}
+
+impl Alert {
+    /// Parses `above:N` or `below:N`, optionally followed by `:notice`, `:bell` or `:pause`.
+    pub fn parse(input: &str) -> Result<Alert, String> {
+        let mut parts = input.trim().split(':');
+        let direction = parts.next().unwrap_or_default().to_lowercase();
+        let cells = parts
+            .next()
+            .ok_or_else(|| format!("\"{input}\" has no population, expected e.g. above:5000"))?;
+        let cells = cells
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| format!("\"{}\" isn't a number of cells", cells.trim()))?;
+        let threshold = match direction.as_str() {
+            "above" => Threshold::Above(cells),
+            "below" => Threshold::Below(cells),
+            _ => {
+                return Err(format!(
+                    "unknown threshold \"{direction}\", expected above or below"
+                ))
+            }
+        };
+        let action = match parts.next().map(|action| action.trim().to_lowercase()) {
+            None => AlertAction::Notice,
+            Some(action) => match action.as_str() {
+                "notice" => AlertAction::Notice,
+                "bell" => AlertAction::Bell,
+                "pause" => AlertAction::Pause,
+                _ => {
+                    return Err(format!(
+                        "unknown alert action \"{action}\", expected notice, bell or pause"
+                    ))
+                }
+            },
+        };
+        if parts.next().is_some() {
+            return Err(format!(
+                "\"{input}\" has too many parts, expected e.g. below:10:pause"
+            ));
+        }
+        Ok(Alert { threshold, action })
+    }
+
+    /// Whether a generation taking the population from `before` to `after` crosses the
+    /// threshold. Staying past it doesn't go off again until it has come back.
+    pub fn crossed(&self, before: usize, after: usize) -> bool {
+        match self.threshold {
+            Threshold::Above(cells) => before <= cells && after > cells,
+            Threshold::Below(cells) => before >= cells && after < cells,
+        }
+    }
+}
+
+impl fmt::Display for Threshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Threshold::Above(cells) => write!(f, "above {cells}"),
+            Threshold::Below(cells) => write!(f, "below {cells}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_crosses() {
+        let alert = Alert::parse("above:5000").unwrap();
+        assert_eq!(alert.threshold, Threshold::Above(5000));
+        assert_eq!(alert.action, AlertAction::Notice);
+        assert!(alert.crossed(5000, 5001));
+        assert!(!alert.crossed(5001, 5002));
+        assert!(!alert.crossed(4000, 5000));
+
+        let alert = Alert::parse("Below:10:pause").unwrap();
+        assert_eq!(alert.threshold, Threshold::Below(10));
+        assert_eq!(alert.action, AlertAction::Pause);
+        assert!(alert.crossed(10, 9));
+        assert!(!alert.crossed(9, 8));
+        assert_eq!(
+            Alert::parse("below:0:bell").unwrap().action,
+            AlertAction::Bell
+        );
+
+        assert!(Alert::parse("above").is_err());
+        assert!(Alert::parse("over:5").is_err());
+        assert!(Alert::parse("above:many").is_err());
+        assert!(Alert::parse("above:5:shout").is_err());
+        assert!(Alert::parse("above:5:bell:pause").is_err());
+    }
+}
@@ -1,64 +1,1253 @@
-use clap::Parser;
-use rand::{thread_rng, Rng};
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt,
+    hash::{Hash, Hasher},
+    io, mem,
+    ops::{Add, ControlFlow, Sub},
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+#[cfg(feature = "tui")]
+use std::{net::SocketAddr, num::NonZeroUsize, path::PathBuf};
+
+#[cfg(feature = "tui")]
+use clap::{ArgAction, Parser, Subcommand};
+use rand::Rng;
+#[cfg(feature = "tui")]
+use ratatui::{crossterm::event::KeyCode, layout::Rect};
+use tracing::{debug, trace, warn};
+
+use crate::{
+    alert::{Alert, AlertAction},
+    boundary::Boundary,
+    checkpoints::{self, Checkpoint, Checkpoints},
+    continuous::{self, Continuous, Field},
+    describe::describe,
+    errors::ErrorReport,
+    explorer::Explorer,
+    headless::{self, Outcome},
+    history::{History, Recent},
+    journal::{Journal, Run},
+    kernel::Kernel,
+    layout::LayoutSpec,
+    metrics::{self, Metrics},
+    mnca::Mnca,
+    neighborhood::{Moore, Neighborhood},
+    overlay::Corner,
+    pattern::{normalize_name, Library, Pattern, Rotation, Transform},
+    picker::Picker,
+    replay::Recorder,
+    rng::RngSource,
+    rulebook::{self, KnownRule},
+    scenario::{Answer, Scenario, Step, Waiting},
+    screensaver::Entry,
+    session::SLOTS,
+    soup::{soup, Symmetry},
+    stability::{StabilityDetector, Stabilization},
+    stats::{Sample, Stats},
+    table::TransitionTable,
+    theme::Theme,
+    tutorial::{Lesson, Tutorial},
+    workspace::Workspace,
+};
+#[cfg(feature = "tui")]
+use crate::{
+    color::ColorDepth,
+    continuous::{parse_interval, parse_positive, EngineKind, Lenia, SmoothLife, Totalistic},
+    evolve::Fitness,
+    input::{parse_binding, parse_jump, Action, Keymap, DEFAULT_JUMP},
+    neighborhood,
+    pattern::default_presets_dir,
+    theme::{parse_intensity, CellStyle, Shading},
+    wallpaper::Resolution,
+};
 
 #[derive(Debug)]
 pub struct Model {
     cells: Vec<Vec<Cell>>,
+    /// The board as it was before the generation being stepped, kept between steps so its
+    /// allocations are reused rather than made again every generation.
+    scratch: Vec<Vec<Cell>>,
+    /// The cells the last generation changed, reused between steps.
+    diff: Diff,
+    observers: Observers,
     rule: Rule,
     state: State,
     current_coords: Coords,
     max_coords: Coords,
-    tickrate: u16,
+    tickrate: Duration,
+    generation: u64,
+    config_errors: Vec<ConfigError>,
+    prompt: Option<Prompt>,
+    picker: Option<Picker>,
+    /// What the open picker lists.
+    picker_kind: PickerKind,
+    theme: Theme,
+    layout: LayoutSpec,
+    rng: RngSource,
+    density: Option<f64>,
+    symmetry: Symmetry,
+    library: Library,
+    stability: StabilityDetector,
+    /// When the loaded preset is known to stabilize, counted from when it was loaded.
+    expected_stabilization: Option<u64>,
+    /// A copy of the board run under another rule, stepped in lockstep with this one.
+    comparison: Option<Box<Model>>,
+    /// Whether this board changed since the comparison board last copied it.
+    comparison_stale: bool,
+    /// Whether the previous generation is drawn beneath the current one.
+    ghost: bool,
+    /// Which cells were really alive in the previous generation, kept while `ghost` or `fade`
+    /// is on.
+    previous: Option<Vec<Vec<bool>>>,
+    /// Whether births fade in and deaths fade out between generations.
+    fade: bool,
+    /// When the latest generation was stepped to, for fading.
+    last_tick: Option<Instant>,
+    /// Whether F6 exports the board as an HTML page as well as ANSI art.
+    export_html: bool,
+    /// An automaton with continuous states, stepped in place of `rule` if one was chosen.
+    continuous: Option<Continuous>,
+    /// A weighted neighborhood whose sums decide births and deaths in place of `rule`'s
+    /// neighbor counts, if one was loaded.
+    kernel: Option<Kernel>,
+    /// An explicit transition table that decides every cell's next state in place of `rule`,
+    /// if one was loaded.
+    table: Option<TransitionTable>,
+    /// A multiple-neighborhood automaton that decides every cell's next state in place of
+    /// `rule`, if one was loaded.
+    mnca: Option<Mnca>,
+    /// What cells along the edges see beyond them.
+    boundary: Boundary,
+    /// The cells whose live count `rule` looks up.
+    neighborhood: Arc<dyn Neighborhood>,
+    /// Generations stepped each tick, from `--time-lapse`.
+    time_lapse: u64,
+    /// Population thresholds to watch for, from `--alert`.
+    alerts: Vec<Alert>,
+    /// Whether an alert has asked for the terminal bell since it was last rung.
+    bell: bool,
+    /// The continuous automaton's state of every cell, which `cells` follows. Empty until the
+    /// board is first stepped.
+    field: Field,
+    /// Which cells were alive a generation before this one, which a Reversible rule steps
+    /// from. Empty, meaning all dead, until the board has been stepped.
+    earlier: Vec<Vec<bool>>,
+    /// Whether the header and footer are hidden, leaving only the board.
+    zen: bool,
+    stats: Stats,
+    screen: Screen,
+    /// Whether the magnified inset is shown.
+    inset: bool,
+    /// The cell the inset stays centered on, instead of following the cursor.
+    inset_pin: Option<Coords>,
+    pause_on_focus_loss: bool,
+    /// Whether the simulation was paused because the terminal lost focus, and so should
+    /// resume when it gets it back.
+    paused_for_focus: bool,
+    /// How many terminal rows each cell is drawn over; cells are drawn this many cell widths
+    /// wide.
+    zoom: u16,
+    /// The top-left cell drawn, which moves to keep the cursor on screen while zoomed in.
+    viewport: Coords,
+    /// Where on screen the board was last drawn, used to find the cell under the mouse.
+    board_area: Area,
+    history: History,
+    /// The quick-save slot F5 and F9 use.
+    slot: u8,
+    /// A short message about the last thing that happened, such as a save, until the next key.
+    notice: Option<String>,
+    /// Whether the debug overlay is shown.
+    debug_overlay: bool,
+    /// Whether the recording overlay is shown, and the corner it goes in.
+    overlay: bool,
+    overlay_corner: Corner,
+    /// Whether the key cheat sheet is shown over the board.
+    help: bool,
+    /// Whether the cell under the cursor is explained while editing.
+    inspector: bool,
+    /// How far fast-forwarding has to go, while it's under way.
+    fast_forward: Option<FastForward>,
+    /// Whether decoration is left out and changes are announced, for screen readers.
+    accessible: bool,
+    /// The latest change announced in the screen-reader friendly mode's status line.
+    announcement: Option<String>,
+    /// The keys, for the footer and the cheat sheet.
+    #[cfg(feature = "tui")]
+    keymap: Keymap,
+    metrics: Metrics,
+    /// Recoverable errors not yet dismissed, oldest first.
+    errors: Vec<ErrorReport>,
+    /// The name of the last preset loaded, remembered with the rule and seed in the history.
+    last_preset: String,
+    /// Writes every message to a replay file, if the session is being recorded.
+    recorder: Option<Recorder>,
+    checkpoints: Checkpoints,
+    /// The boards from `--workspace`.
+    workspace: Workspace,
+    /// Where each run is logged, with `--journal`.
+    journal: Option<Journal>,
+    /// When the current run started, for the journal.
+    run_started: SystemTime,
+    /// Generations stepped in the current run.
+    run_generations: u64,
+    /// The rules mutated away from, and how often to mutate while running.
+    explorer: Explorer,
+    /// The tutorial being followed, if running with `tutorial`.
+    tutorial: Option<Tutorial>,
+    /// The lesson being played through, if running with `lesson`.
+    scenario: Option<Scenario>,
 }
 
-#[derive(Debug, PartialEq)]
+/// What the picker lists: patterns to load at the cursor, recent runs to start over from,
+/// checkpoints to roll back to, or the workspace's boards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PickerKind {
+    #[default]
+    Patterns,
+    Recent,
+    Checkpoints,
+    Boards,
+}
+
+/// The screens the main area can show, switched between with Tab.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Screen {
+    #[default]
+    Board,
+    Stats,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Rule {
     pub birth_list: Vec<u8>,
     pub survival_list: Vec<u8>,
+    /// Whether this is the rule's History variant, written with a `History` suffix as in
+    /// `B3/S23History`: dead cells remember having been alive, and cells can be marked.
+    pub history: bool,
+    /// Whether this is the rule's second-order variant, written with a `Reversible` suffix as
+    /// in `B3/S23Reversible`: a cell's next state is the rule's verdict on the current board
+    /// XORed with the cell's state a generation earlier, so the board can be stepped back
+    /// exactly, as far as generation 0.
+    pub second_order: bool,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// The mode the application is in. Changes between modes only happen through
+/// [`State::transition`]; see its documentation for the allowed transitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum State {
     Editing,
     Running,
+    /// A text prompt is open. `resume` is the state to return to once it closes.
+    Prompting {
+        resume: Box<State>,
+    },
+    /// The pattern picker is open. `resume` is the state to return to once it closes.
+    Picking {
+        resume: Box<State>,
+    },
+    /// Stepping [`FAST_FORWARD`] generations a batch per redraw, with a progress bar, or until
+    /// the board settles down. `resume` is the state to return to once done or stopped.
+    FastForwarding {
+        resume: Box<State>,
+    },
     Done,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// How the board is being looked at, as opposed to what's on it, so a saved session can be
+/// reopened looking the same.
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    pub theme: Theme,
+    pub zoom: u16,
+    pub viewport: Coords,
+    pub cursor: Coords,
+    pub ghost: bool,
+    pub inset: bool,
+    pub inset_pin: Option<Coords>,
+    pub screen: Screen,
+    pub zen: bool,
+}
+
+/// A rectangle of terminal cells or board cells, `width` across and `height` down from its
+/// top-left corner at `(x, y)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Area {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Area {
+    pub const fn new(x: u16, y: u16, width: u16, height: u16) -> Area {
+        Area {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// One past the last column.
+    pub fn right(self) -> u16 {
+        self.x.saturating_add(self.width)
+    }
+
+    /// One past the last row.
+    pub fn bottom(self) -> u16 {
+        self.y.saturating_add(self.height)
+    }
+
+    pub fn contains(self, x: u16, y: u16) -> bool {
+        (self.x..self.right()).contains(&x) && (self.y..self.bottom()).contains(&y)
+    }
+
+    /// The part of this area inside `other`, empty if they don't overlap.
+    pub fn intersection(self, other: Area) -> Area {
+        let (x, y) = (self.x.max(other.x), self.y.max(other.y));
+        Area {
+            x,
+            y,
+            width: self.right().min(other.right()).saturating_sub(x),
+            height: self.bottom().min(other.bottom()).saturating_sub(y),
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+impl From<Rect> for Area {
+    fn from(rect: Rect) -> Area {
+        Area::new(rect.x, rect.y, rect.width, rect.height)
+    }
+}
+
+#[cfg(feature = "tui")]
+impl From<Area> for Rect {
+    fn from(area: Area) -> Rect {
+        Rect::new(area.x, area.y, area.width, area.height)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Coords {
     pub x: i16,
     pub y: i16,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+impl Coords {
+    /// These coordinates moved `dx` columns right and `dy` rows down, stopping at the ends of
+    /// `i16` rather than wrapping around.
+    pub fn offset(self, dx: i16, dy: i16) -> Coords {
+        Coords {
+            x: self.x.saturating_add(dx),
+            y: self.y.saturating_add(dy),
+        }
+    }
+
+    /// These coordinates pulled back onto a board whose last cell is at `bounds`.
+    pub fn within(self, bounds: Coords) -> Coords {
+        Coords {
+            x: self.x.clamp(0, bounds.x.max(0)),
+            y: self.y.clamp(0, bounds.y.max(0)),
+        }
+    }
+
+    /// The cells of `neighborhood` around these coordinates, leaving out those off a board
+    /// whose last cell is at `bounds`.
+    pub fn neighbors(
+        self,
+        neighborhood: &dyn Neighborhood,
+        bounds: Coords,
+    ) -> impl Iterator<Item = Coords> + '_ {
+        neighborhood
+            .offsets()
+            .iter()
+            .map(move |&(dx, dy)| self.offset(dx, dy))
+            .filter(move |coords| {
+                (0..=bounds.x).contains(&coords.x) && (0..=bounds.y).contains(&coords.y)
+            })
+    }
+}
+
+impl Add for Coords {
+    type Output = Coords;
+
+    fn add(self, other: Coords) -> Coords {
+        self.offset(other.x, other.y)
+    }
+}
+
+impl Sub for Coords {
+    type Output = Coords;
+
+    fn sub(self, other: Coords) -> Coords {
+        Coords {
+            x: self.x.saturating_sub(other.x),
+            y: self.y.saturating_sub(other.y),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Message {
-    Move(Direction),
+    /// Moves the cursor by a number of cells, stopping at the board's edge.
+    Move(Direction, i16),
     ToggleCellState,
+    Randomize,
+    /// Fills the cells in view with a soup while editing, leaving the rest of the board as it
+    /// is.
+    RandomizeView,
+    ToggleGhost,
+    /// Marks or unmarks the cell under the cursor, under a History rule.
+    ToggleMark,
+    /// Steps the board back a generation, under a Reversible rule. Only works while editing.
+    StepBack,
+    /// Replaces the rule with a mutation of it, one birth or survival digit different.
+    Mutate,
+    ToggleZen,
+    /// Halves the tickrate, down to [`MIN_TICKRATE`].
+    Faster,
+    /// Doubles the tickrate, up to [`MAX_TICKRATE`].
+    Slower,
+    /// Changes the zoom level by a number of steps, between 1 and [`MAX_ZOOM`].
+    Zoom(i16),
+    /// Makes the cell at the given coordinates alive or dead. Only works while editing.
+    SetCell(Coords, bool),
+    /// Scrolls the viewport by a number of columns and rows. While editing, the cursor moves
+    /// along so it stays in the same place on screen.
+    Pan(i16, i16),
+    SwitchScreen,
+    ToggleInset,
+    ToggleInsetPin,
+    FocusLost,
+    FocusGained,
     ToggleEditing,
+    OpenPrompt,
+    PromptInput(char),
+    PromptBackspace,
+    PromptSubmit,
+    PromptCancel,
+    OpenPicker,
+    /// Opens the picker on the recent runs.
+    OpenRecent,
+    /// Closes the newest error in the error panel.
+    DismissError,
+    /// Shows or hides the debug overlay.
+    ToggleDebug,
+    /// Shows or hides the recording overlay.
+    ToggleOverlay,
+    /// Steps ten times as many generations each tick, back to one after [`MAX_TIME_LAPSE`].
+    CycleTimeLapse,
+    /// Opens a prompt for the board's size.
+    OpenResize,
+    /// Grows or crops the board to a width and height in cells around an anchor.
+    Resize(u16, u16, Anchor),
+    /// Shows or hides the key cheat sheet.
+    ToggleHelp,
+    /// Shows or hides the neighborhood inspector while editing.
+    ToggleInspector,
+    /// Announces a summary of the board: its population, where it is and the patterns in it.
+    Describe,
+    /// Opens the picker on the checkpoints.
+    OpenCheckpoints,
+    /// Opens the picker on the workspace's boards.
+    OpenWorkspace,
+    /// Chooses the quick-save slot, from 1 to [`SLOTS`].
+    SelectSlot(u8),
+    /// Steps the board [`FAST_FORWARD`] generations ahead without drawing each one.
+    FastForward,
+    /// Steps the board without drawing each generation until it becomes periodic or dies
+    /// out, or [`RUN_UNTIL_STABLE`] generations have gone by, then pauses and reports how it
+    /// ended.
+    RunUntilStable,
+    /// Stops fast-forwarding at the generation it got to.
+    StopFastForward,
+    /// Goes on to the lesson's next step.
+    Continue,
+    /// Answers the lesson's question of whether the cell under the cursor is alive next
+    /// generation.
+    Predict(bool),
+    PickerInput(char),
+    PickerBackspace,
+    PickerMove(Direction),
+    PickerSubmit,
+    PickerCancel,
     Idle,
     Quit,
 }
 
+/// The fastest the simulation may be asked to run.
+pub const MIN_TICKRATE: Duration = Duration::from_micros(100);
+
+pub const DEFAULT_TICKRATE: Duration = Duration::from_millis(100);
+
+/// The slowest the simulation can be slowed down to while it runs.
+pub const MAX_TICKRATE: Duration = Duration::from_secs(10);
+
+/// The shortest time between redraws under `--low-bandwidth`, unless `--fps` is given.
+pub const LOW_BANDWIDTH_FRAME: Duration = Duration::from_millis(200);
+
+/// The fastest tickrate at which `--fade` fades cells between generations. Faster ticks
+/// don't leave enough frames to see a fade.
+pub const FADE_TICKRATE: Duration = Duration::from_millis(100);
+
+/// The most generations time-lapse steps each tick.
+pub const MAX_TIME_LAPSE: u64 = 1000;
+
+/// Generations skipped at once by fast-forwarding.
+pub const FAST_FORWARD: u64 = 1000;
+
+/// The most generations running until stable steps before giving up on the board settling.
+pub const RUN_UNTIL_STABLE: u64 = 100_000;
+
+/// Generations fast-forwarding steps between redraws, small enough for the progress bar to move
+/// and Esc to be noticed on large boards.
+const FAST_FORWARD_BATCH: u64 = 20;
+
+/// The largest zoom level, at which each cell is drawn over this many rows.
+pub const MAX_ZOOM: u16 = 4;
+
+/// The fewest cells across or down a board can have, as [`Model::new`] requires.
+pub const MIN_BOARD_SIZE: usize = 2;
+
+/// The most cells across or down the board can be resized to.
+pub const MAX_BOARD_SIZE: usize = 4096;
+
+#[cfg(feature = "tui")]
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     #[arg(short, long)]
     pub rulestring: Option<String>,
 
     #[arg(short, long)]
     pub preset_string: Option<String>,
 
-    #[arg(short, long)]
-    pub tickrate: Option<u16>,
+    /// Time between generations: 50ms, 250us, 2s, or a rate such as 10/s (bare numbers are
+    /// milliseconds), from 100us to 10s; slower tickrates are clamped to 10s
+    #[arg(short, long, value_parser = parse_tickrate)]
+    pub tickrate: Option<Duration>,
+
+    /// Redraw the screen at most this many times a second, however fast the simulation runs
+    #[arg(long, value_name = "FPS", value_parser = parse_fps)]
+    pub fps: Option<Duration>,
+
+    /// Send less to the terminal, for slow SSH connections and nested tmux: redraw at most 5
+    /// times a second unless --fps says otherwise, use at most 256 colors unless --color-depth
+    /// says otherwise, and don't fade
+    #[arg(long, conflicts_with = "fade")]
+    pub low_bandwidth: bool,
+
+    /// Step this many generations each tick and draw only the last, to watch slow patterns
+    /// grow; up to 1000
+    #[arg(long, value_name = "GENERATIONS",
+          value_parser = clap::value_parser!(u64).range(1..=MAX_TIME_LAPSE))]
+    pub time_lapse: Option<u64>,
+
+    /// Say so when the population crosses a threshold, given as above:CELLS or below:CELLS,
+    /// e.g. above:5000; add :bell to ring the terminal bell as well or :pause to pause. May be
+    /// repeated
+    #[arg(long, value_name = "THRESHOLD", value_parser = Alert::parse)]
+    pub alert: Vec<Alert>,
+
+    /// Start by running until the board becomes periodic or dies out, up to 100000
+    /// generations, then pause and report how many generations it took
+    #[arg(long)]
+    pub run_until_stable: bool,
+
+    /// Fade cells in as they're born and out as they die over each tick, at tickrates of
+    /// 100ms and slower
+    #[arg(long)]
+    pub fade: bool,
+
+    /// Export the board with F6 as an HTML page as well as ANSI art
+    #[arg(long)]
+    pub export_html: bool,
+
+    /// Show a line with the rule, generation, seed and time in a corner of the board, for
+    /// recordings and streams: top-left, top-right, bottom-left or bottom-right (the default)
+    #[arg(long, value_name = "CORNER", num_args = 0..=1, default_missing_value = "bottom-right",
+          value_parser = Corner::parse)]
+    pub overlay: Option<Corner>,
+
+    /// Watch random soups full screen, with a new one whenever the board dies out or stops
+    /// changing, until a key is pressed
+    #[arg(long, conflicts_with_all = ["record", "replay"])]
+    pub screensaver: bool,
+
+    /// Rules for the screensaver to take turns with, each RULE or RULE@THEME, such as
+    /// B36/S23@dots; themes must have cells as wide as --theme's
+    #[arg(long, value_name = "ENTRY", num_args = 1.., requires = "screensaver",
+          value_parser = Entry::parse)]
+    pub playlist: Vec<Entry>,
+
+    /// How long each rule of the playlist plays for, as with --tickrate
+    #[arg(long, value_name = "DURATION", default_value = "60s", value_parser = parse_tickrate)]
+    pub playlist_turn: Duration,
+
+    /// Built-in theme: classic, square, dots, emoji, cividis and okabe-ito, whose colors
+    /// stay distinct with red-green color blindness (deuteranopia and protanopia), or mono,
+    /// which uses no color at all and shows age with glyphs
+    #[arg(long)]
+    pub theme: Option<String>,
+
+    /// Custom glyph for live cells, one or two columns wide (overrides the theme's)
+    #[arg(long)]
+    pub glyph: Option<String>,
+
+    /// How cell age is shown: color, glyphs (a ·∙•●█ ramp) or both
+    #[arg(long, value_parser = CellStyle::parse)]
+    pub cell_style: Option<CellStyle>,
+
+    /// Colors the terminal supports: truecolor, 256 or 16 (detected from $COLORTERM and $TERM
+    /// if not given)
+    #[arg(long, value_parser = ColorDepth::parse)]
+    pub color_depth: Option<ColorDepth>,
+
+    /// Where the header, board, stats and footer go: rows separated by commas, panes in a row
+    /// by |, leaving out any but the board. "header,board|stats" shows the stats on the
+    /// right and no footer [default: header,board,footer]
+    #[arg(long, value_parser = LayoutSpec::parse)]
+    pub layout: Option<LayoutSpec>,
+
+    /// Faint background for dead cells so the board's extent shows: none, checkerboard or grid
+    #[arg(long, value_parser = Shading::parse)]
+    pub shading: Option<Shading>,
+
+    /// Brightness of the dead cell shading, from 0 to 100 percent
+    #[arg(long, value_name = "PERCENT", value_parser = parse_intensity)]
+    pub shading_intensity: Option<u8>,
+
+    /// Seed for the random number generator, to reproduce a previous run
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Place the preset's top-left corner at X,Y instead of the board's corner
+    #[arg(long, value_name = "X,Y", value_parser = parse_coords, conflicts_with = "center")]
+    pub at: Option<Coords>,
+
+    /// Center the preset on the board
+    #[arg(long)]
+    pub center: bool,
+
+    /// Fraction of cells alive in a random soup, overriding the rule's suggested density
+    #[arg(long, value_parser = parse_density)]
+    pub density: Option<f64>,
+
+    /// Run a copy of the board under this rule side by side, e.g. --compare B36/S23
+    #[arg(long, value_name = "RULESTRING")]
+    pub compare: Option<Rule>,
+
+    /// What steps the board: life for the rulestring's rule, lenia or smoothlife, whose cells
+    /// fade smoothly between dead and alive, or totalistic, whose cells have K states
+    #[arg(long, value_name = "ENGINE", value_parser = EngineKind::parse)]
+    pub engine: Option<EngineKind>,
+
+    /// Radius of Lenia's neighborhood ring, in cells
+    #[arg(long, value_name = "R", default_value_t = Lenia::DEFAULT_RADIUS,
+          value_parser = clap::value_parser!(u16).range(1..=64))]
+    pub lenia_radius: u16,
+
+    /// Neighborhood average at which Lenia's cells grow fastest
+    #[arg(long, value_name = "MU", default_value_t = Lenia::DEFAULT_MU, value_parser = parse_positive)]
+    pub lenia_mu: f32,
+
+    /// How far from --lenia-mu the average may be before Lenia's cells shrink
+    #[arg(long, value_name = "SIGMA", default_value_t = Lenia::DEFAULT_SIGMA,
+          value_parser = parse_positive)]
+    pub lenia_sigma: f32,
+
+    /// Fraction of the growth applied to Lenia's cells each generation
+    #[arg(long, value_name = "DT", default_value_t = Lenia::DEFAULT_DT, value_parser = parse_positive)]
+    pub lenia_dt: f32,
+
+    /// Radius of the disk that decides whether a SmoothLife cell is alive, in cells
+    #[arg(long, value_name = "R", default_value_t = SmoothLife::DEFAULT_INNER_RADIUS,
+          value_parser = clap::value_parser!(u16).range(1..=64))]
+    pub smoothlife_inner: u16,
+
+    /// Radius of the ring around the disk that SmoothLife cells are born and die by
+    #[arg(long, value_name = "R", default_value_t = SmoothLife::DEFAULT_OUTER_RADIUS,
+          value_parser = clap::value_parser!(u16).range(2..=64))]
+    pub smoothlife_outer: u16,
+
+    /// How full the ring must be for a dead SmoothLife cell to be born, as LOW,HIGH
+    #[arg(long, value_name = "LOW,HIGH", default_value = "0.278,0.365",
+          value_parser = parse_interval)]
+    pub smoothlife_birth: (f32, f32),
+
+    /// How full the ring may be for a live SmoothLife cell to survive, as LOW,HIGH; it dies
+    /// outside this interval
+    #[arg(long, value_name = "LOW,HIGH", default_value = "0.267,0.445",
+          value_parser = parse_interval)]
+    pub smoothlife_death: (f32, f32),
+
+    /// Number of states of the totalistic engine's cells, dead included
+    #[arg(long, value_name = "K", default_value_t = Totalistic::DEFAULT_STATES,
+          value_parser = clap::value_parser!(u8).range(2..=Totalistic::MAX_STATES as i64))]
+    pub totalistic_states: u8,
+
+    /// Wolfram code of the totalistic engine's rule: written in base K, its last digit is the
+    /// next state of a cell whose own and neighbors' states sum to 0, the one before for a sum
+    /// of 1 and so on
+    #[arg(long, value_name = "CODE", default_value = Totalistic::DEFAULT_CODE)]
+    pub totalistic_code: String,
+
+    /// Step the rulestring's rule with a FILE of integer neighbor weights and the sums cells
+    /// are born and survive on, in place of plain neighbor counts
+    #[arg(long, value_name = "FILE")]
+    pub kernel: Option<PathBuf>,
+
+    /// Step the board with a FILE of explicit transitions from a cell and its eight neighbors
+    /// to the cell's next state, in place of the rulestring's rule
+    #[arg(long, value_name = "FILE", conflicts_with = "kernel")]
+    pub table: Option<PathBuf>,
+
+    /// Step the board with a FILE of neighborhood rings and the fractions of each that turn
+    /// cells on or off, a multiple-neighborhood automaton, in place of the rulestring's rule
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["kernel", "table"])]
+    pub mnca: Option<PathBuf>,
+
+    /// What cells along the edges see beyond them: dead (the default), alive, mirror for a
+    /// reflection of the cells inside the edge, or wrap for the cells along the opposite edge
+    #[arg(long, value_parser = Boundary::parse)]
+    pub boundary: Option<Boundary>,
+
+    /// The cells counted as each cell's neighbors: moore (the default), von-neumann, hex, or
+    /// offsets such as 1,0;0,1;-1,0;0,-1
+    #[arg(long, value_parser = neighborhood::parse)]
+    pub neighborhood: Option<Arc<dyn Neighborhood>>,
+
+    /// Symmetry enforced on random soups: none, C2, C4, D2 or D8
+    #[arg(long, value_parser = Symmetry::parse)]
+    pub symmetry: Option<Symmetry>,
+
+    /// Directory of .rle files to offer as presets, named after their file names
+    #[arg(long, value_name = "DIR")]
+    pub presets_dir: Option<PathBuf>,
+
+    /// Rotate the preset clockwise by 90, 180 or 270 degrees
+    #[arg(long, value_name = "DEGREES", value_parser = Rotation::parse)]
+    pub rotate: Option<Rotation>,
+
+    /// Mirror the preset left to right (applied before rotating)
+    #[arg(long)]
+    pub flip_h: bool,
+
+    /// Mirror the preset top to bottom (applied before rotating)
+    #[arg(long)]
+    pub flip_v: bool,
+
+    /// Place a pattern before starting, e.g. glider@10,5 or GosperGliderGun@0,0,180. May be
+    /// repeated; overlapping or clipped patterns are reported
+    #[arg(long, value_name = "NAME@X,Y[,ROT]", value_parser = parse_place)]
+    pub place: Vec<PlaceSpec>,
+
+    /// Cells the cursor moves with Shift+WASD
+    #[cfg(feature = "tui")]
+    #[arg(long, value_name = "CELLS", value_parser = parse_jump)]
+    pub jump: Option<i16>,
+
+    /// Move an action to another key, e.g. mutate=x or help=F1. May be repeated; see
+    /// --print-keys for the actions
+    #[cfg(feature = "tui")]
+    #[arg(long, value_name = "ACTION=KEY", value_parser = parse_binding)]
+    pub bind: Vec<(Action, KeyCode)>,
+
+    /// Print the keys and what they do, with any --bind applied, and exit
+    #[arg(long)]
+    pub print_keys: bool,
+
+    /// Keep running while the terminal doesn't have focus, instead of pausing until it's back
+    #[arg(long)]
+    pub run_unfocused: bool,
+
+    /// Screen-reader friendly mode: leave out ghosts, shading and other decoration, and keep a
+    /// status line announcing what changed and where the cursor is
+    #[arg(long)]
+    pub accessible: bool,
+
+    /// Save the board to autosave.rle in the data directory when quitting, including when
+    /// interrupted or terminated by a signal
+    #[arg(long)]
+    pub autosave_on_exit: bool,
+
+    /// Start the most recent run over: its pattern, rule and seed, unless given
+    #[arg(long)]
+    pub resume_last: bool,
+
+    /// Open a workspace FILE of several boards, switched between with o; the first is loaded
+    /// unless a preset or rule is given
+    #[arg(long, value_name = "FILE")]
+    pub workspace: Option<PathBuf>,
+
+    /// Copy the board every N generations, to roll back to with b (0 turns checkpoints off)
+    #[arg(long, value_name = "N")]
+    pub checkpoint_every: Option<u64>,
+
+    /// Mutate the rule every N generations, adding or removing a birth or survival digit as
+    /// u does
+    #[arg(long, value_name = "N")]
+    pub mutate_every: Option<u64>,
+
+    /// Keep the pattern library, saves and history in DIR instead of the usual per-user
+    /// directory, for running from a portable drive
+    #[arg(long, value_name = "DIR")]
+    pub portable: Option<PathBuf>,
+
+    /// Log every run (rule, seed, start time, generations, final population, stabilization)
+    /// to journal.tsv in the data directory
+    #[arg(long)]
+    pub journal: bool,
+
+    /// If the app crashes, save a report with the backtrace, the end of the log and the board
+    /// to the data directory
+    #[arg(long)]
+    pub crash_report: bool,
+
+    /// Write a log to FILE, for debugging
+    #[arg(long, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
+    /// Log in more detail: -v for debugging, -vv for every generation; logs to the log
+    /// directory unless --log-file is given
+    #[arg(short, long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Run the experiment a manifest FILE describes without drawing, then write the
+    /// manifest completed with its seed and results, so it can be run again
+    ///
+    /// Manifests are written in a subset of TOML: `rule`, `preset`, `seed`, `density`,
+    /// `width`, `height`, `boundary` and `generations` describe the run, `output` names the
+    /// completed manifest, by default FILE with a `.results.toml` extension, and `final_board`
+    /// saves the last board as RLE. They override the same options on the command line, and
+    /// the exit status is as with `headless`.
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub manifest: Option<PathBuf>,
+
+    /// Serve the board read-only over HTTP on ADDR, such as 127.0.0.1:8080: /state sums it
+    /// up as JSON, /stats gives the recent generations as JSON and /frame.png is a picture
+    #[arg(long, value_name = "ADDR")]
+    pub status_addr: Option<SocketAddr>,
+
+    /// Serve Prometheus metrics at /metrics on ADDR, such as 0.0.0.0:9898, or on 127.0.0.1 if
+    /// only a port is given: the generation, population, tick duration and births and deaths
+    #[cfg(feature = "prometheus")]
+    #[arg(long, value_name = "ADDR", value_parser = crate::prometheus::parse_addr)]
+    pub metrics_addr: Option<SocketAddr>,
+
+    /// Draw every frame to PATH as well, as ANSI text: a file to capture them in, a named pipe
+    /// or another terminal's TTY, such as /dev/pts/3, to watch there
+    #[arg(long, value_name = "PATH")]
+    pub mirror: Option<PathBuf>,
+
+    /// Record the session to FILE, to be played back with --replay
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    /// Play back a session recorded with --record, then carry on from where it ended
+    #[arg(long, value_name = "FILE", conflicts_with = "resume_last")]
+    pub replay: Option<PathBuf>,
+
+    /// Print the names of all available presets and exit
+    #[arg(long)]
+    pub list_presets: bool,
+
+    /// Exit with status 2 on problems that would otherwise be shown as a warning, such as an
+    /// unknown preset or an invalid rulestring, for scripts and batch runs
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[cfg(feature = "tui")]
+#[derive(Subcommand)]
+pub enum Command {
+    /// Check the terminal's size, colors, mouse, glyph widths and alternate screen, to find
+    /// out why the board looks wrong
+    Doctor,
+    /// Run without drawing until the board becomes periodic, dies out or reaches
+    /// --max-generations, then print how it ended
+    ///
+    /// The exit status tells the outcome apart: 0 when the board became periodic (including
+    /// still lifes), 3 when every cell died, 4 when --max-generations was reached first and 2
+    /// for problems with the command line, such as an unknown preset. Warnings that would
+    /// otherwise be shown in the app are treated as problems, as with --strict.
+    Headless {
+        /// Give up after this many generations
+        #[arg(long, value_name = "N", default_value_t = 10_000)]
+        max_generations: u64,
+
+        /// Width of the board in cells, at least 2
+        #[arg(long, default_value_t = 80, value_parser = clap::value_parser!(u16).range(2..=4096))]
+        width: u16,
+
+        /// Height of the board in cells, at least 2
+        #[arg(long, default_value_t = 24, value_parser = clap::value_parser!(u16).range(2..=4096))]
+        height: u16,
+    },
+    /// Learn the basics: editing, running, changing the rule and saving, with a panel beside
+    /// the board that moves on as each step is done
+    ///
+    /// Every other option works as usual, so a tutorial can start from a pattern with -p.
+    Tutorial,
+    /// Play through a lesson written as a scenario file, with a panel beside the board that
+    /// shows its text and asks what happens next
+    ///
+    /// A scenario has one step per line: `load NAME [X,Y]` loads a pattern, `rule RULESTRING`
+    /// changes the rule, `say TEXT` shows text until Enter, `predict X,Y` asks whether that
+    /// cell is alive next generation, answered with y or n, and `advance N` steps the board.
+    /// `title TEXT` names the lesson and `#` starts a comment.
+    Lesson {
+        /// The scenario file
+        file: PathBuf,
+    },
+    /// Run in the background without drawing, replacing a PNG image with the board as it
+    /// evolves, for a wallpaper setter to show
+    ///
+    /// The board steps at --tickrate and fills the image, a square of --cell-size pixels per
+    /// cell. Every other option works as usual, so the board can start from a pattern with
+    /// -p. Stop it with SIGINT or SIGTERM.
+    Daemon {
+        /// The image to write
+        output: PathBuf,
+
+        /// Size of the image in pixels
+        #[arg(long, value_name = "WxH", default_value = "1920x1080", value_parser = Resolution::parse)]
+        resolution: Resolution,
+
+        /// Pixels across and down each cell
+        #[arg(long, value_name = "PIXELS", default_value_t = 8,
+              value_parser = clap::value_parser!(u16).range(1..=256))]
+        cell_size: u16,
+
+        /// The shortest time between images, as with --tickrate
+        #[arg(long, value_name = "DURATION", default_value = "1s", value_parser = parse_tickrate)]
+        interval: Duration,
+
+        /// Start over from a random soup once the board dies out or repeats
+        #[arg(long)]
+        reseed: bool,
+    },
+    /// Run every rule in a set on the same random soups without drawing, in parallel, and
+    /// write a CSV comparing how they went
+    ///
+    /// Rules are rulestrings where digits in square brackets may each be there or not, so
+    /// `B3[678]/S23` covers the 8 rules from B3/S23 to B3678/S23. Each rule gets --seeds soups
+    /// seeded from --seed on, and --density sets how full they are. The CSV has a row per
+    /// rule: how many soups died out, became periodic or were still changing after
+    /// --generations, and the mean generations run, period, growth and final density.
+    Sweep {
+        /// Rules or rule templates to run
+        #[arg(required = true, value_name = "RULE")]
+        rules: Vec<String>,
+
+        /// Soups to run each rule on
+        #[arg(long, value_name = "M", default_value_t = 8)]
+        seeds: u64,
+
+        /// Generations each soup runs for at most
+        #[arg(long, value_name = "G", default_value_t = 1000)]
+        generations: u64,
+
+        /// Width of the soups in cells
+        #[arg(long, default_value_t = 64, value_parser = clap::value_parser!(u16).range(2..=4096))]
+        width: u16,
+
+        /// Height of the soups in cells
+        #[arg(long, default_value_t = 64, value_parser = clap::value_parser!(u16).range(2..=4096))]
+        height: u16,
+
+        /// Rules run at once, by default one per CPU
+        #[arg(long, value_name = "N")]
+        jobs: Option<NonZeroUsize>,
+
+        /// Write the CSV here instead of to standard output
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Search for interesting rules with a genetic algorithm, trying each on a random soup
+    /// without drawing, then print the best rules found and the seeds of their soups
+    ///
+    /// The search is reproducible with --seed, and --density sets how full the soups are.
+    Evolve {
+        /// What makes a rule good: longevity (generations before the soup dies out or
+        /// repeats), growth (how many times over the soup grows) or oscillation (the period
+        /// the soup settles into)
+        #[arg(long, default_value = "longevity", value_parser = Fitness::parse)]
+        fitness: Fitness,
+
+        /// Rules tried in each round
+        #[arg(long, value_name = "N", default_value_t = 24,
+              value_parser = clap::value_parser!(u16).range(2..=1000))]
+        population: u16,
+
+        /// Rounds of keeping the better half of the rules and breeding the rest from them
+        #[arg(long, value_name = "N", default_value_t = 20)]
+        rounds: u32,
+
+        /// Generations each soup runs for at most
+        #[arg(long, value_name = "N", default_value_t = 1000)]
+        max_generations: u64,
+
+        /// Width of the soups in cells
+        #[arg(long, default_value_t = 64, value_parser = clap::value_parser!(u16).range(2..=4096))]
+        width: u16,
+
+        /// Height of the soups in cells
+        #[arg(long, default_value_t = 32, value_parser = clap::value_parser!(u16).range(2..=4096))]
+        height: u16,
+    },
 }
 
 pub struct Config {
     pub rule: Rule,
     pub preset: Preset,
-    pub tickrate: u16,
+    pub tickrate: Duration,
+    pub theme: Theme,
+    pub layout: LayoutSpec,
+    pub seed: Option<u64>,
+    pub placement: Placement,
+    pub transform: Transform,
+    pub places: Vec<(PlaceSpec, Preset)>,
+    pub density: Option<f64>,
+    pub symmetry: Symmetry,
+    pub compare: Option<Rule>,
+    /// The continuous automaton chosen with `--engine`, if any.
+    pub continuous: Option<Continuous>,
+    /// The weighted neighborhood loaded with `--kernel`, if any.
+    pub kernel: Option<Kernel>,
+    /// The transition table loaded with `--table`, if any.
+    pub table: Option<TransitionTable>,
+    /// The multiple-neighborhood automaton loaded with `--mnca`, if any.
+    pub mnca: Option<Mnca>,
+    pub boundary: Boundary,
+    /// The neighborhood chosen with `--neighborhood`, if any.
+    pub neighborhood: Option<Arc<dyn Neighborhood>>,
+    /// Cursor movement and the keys for each action, from `--jump` and `--bind`.
+    #[cfg(feature = "tui")]
+    pub keymap: Keymap,
+    /// The shortest time between redraws, from `--fps`.
+    pub frame_interval: Option<Duration>,
+    /// Generations stepped each tick, from `--time-lapse`.
+    pub time_lapse: u64,
+    /// Whether to start by running until stable, from `--run-until-stable`.
+    pub run_until_stable: bool,
+    /// Population thresholds to watch for, from `--alert`.
+    pub alerts: Vec<Alert>,
+    pub fade: bool,
+    pub export_html: bool,
+    /// The corner of the recording overlay, from `--overlay`, if it's shown from the start.
+    pub overlay: Option<Corner>,
+    pub screensaver: bool,
+    /// The screensaver's rules, from `--playlist`, and how long each plays for.
+    pub playlist: Vec<Entry>,
+    pub playlist_turn: Duration,
+    pub pause_on_focus_loss: bool,
+    pub accessible: bool,
+    pub autosave_on_exit: bool,
+    pub journal: bool,
+    pub checkpoint_interval: u64,
+    /// Generations between automatic rule mutations, from `--mutate-every`.
+    pub mutate_every: Option<u64>,
+    pub workspace: Workspace,
+    pub library: Library,
+    pub history: History,
+    pub errors: Vec<ConfigError>,
+}
+
+/// Where a preset goes on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    TopLeft,
+    At(Coords),
+    Center,
+}
+
+/// A startup input that couldn't be used as given, along with what was substituted for it.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    Rulestring {
+        input: String,
+        reason: String,
+    },
+    Preset {
+        input: String,
+    },
+    Theme {
+        input: String,
+        reason: String,
+    },
+    Placement {
+        preset: String,
+        reason: String,
+    },
+    Library {
+        path: String,
+        reason: String,
+    },
+    Place {
+        spec: String,
+        reason: String,
+    },
+    History {
+        path: String,
+        reason: String,
+    },
+    Workspace {
+        path: String,
+        reason: String,
+    },
+    /// A tickrate slower than [`MAX_TICKRATE`], which has been clamped.
+    Tickrate {
+        input: String,
+    },
+    /// An engine whose parameters don't make sense together.
+    Engine {
+        input: String,
+        reason: String,
+    },
+    Kernel {
+        path: String,
+        reason: String,
+    },
+    Table {
+        path: String,
+        reason: String,
+    },
+    Mnca {
+        path: String,
+        reason: String,
+    },
+    Neighborhood {
+        input: String,
+        reason: String,
+    },
+}
+
+/// One `--place NAME@X,Y[,ROT]` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaceSpec {
+    pub name: String,
+    pub at: Coords,
+    pub rotation: Rotation,
+}
+
+/// What [`Model::place`] does where a pattern lands on the board.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CollisionPolicy {
+    /// The pattern's dead cells clear the cells under them, as well as its live cells being
+    /// added.
+    #[default]
+    Overwrite,
+    /// Only the pattern's live cells are added, leaving the cells under its dead ones as they
+    /// are.
+    Merge,
+    /// As [`CollisionPolicy::Merge`], unless a live cell would land on a live cell or fall off
+    /// the board, in which case nothing is placed.
+    Error,
+}
+
+/// What happened to a pattern placed on the board with [`Model::place`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PlaceReport {
+    /// Live cells that fell off the board.
+    pub clipped: usize,
+    /// Live cells that landed on cells which were already alive.
+    pub collisions: usize,
+}
+
+/// The cells a generation changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diff {
+    pub born: Vec<Coords>,
+    pub died: Vec<Coords>,
+}
+
+/// A callback run after every generation, given its number and the cells it changed.
+type Observer = Box<dyn FnMut(u64, &Diff) + Send>;
+
+/// The callbacks registered with [`Model::on_generation`], in the order they were.
+#[derive(Default)]
+struct Observers(Vec<Observer>);
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
+
+/// A fast-forward under way, from [`Model::fast_forward`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FastForward {
+    /// The generation it started at.
+    pub from: u64,
+    /// The generation it stops at.
+    pub to: u64,
+    /// Whether it stops early once the board becomes periodic or dies out.
+    pub until_stable: bool,
+}
+
+/// What the neighborhood inspector shows about a cell, from [`Model::inspect`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inspection {
+    pub at: Coords,
+    pub alive: bool,
+    /// Generations the cell has survived, if it's alive.
+    pub age: u32,
+    /// Live cells among its neighbors, past the edges as the boundary has it.
+    pub neighbors: u8,
+    /// Whether the cell is alive next generation, unless a continuous rule decides.
+    pub next: Option<bool>,
+    /// Why, in a line.
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptField {
+    Rulestring,
+    Preset,
+    Theme,
+    /// The board's size, as `WxH`, optionally followed by the anchor to resize it around.
+    Size,
 }
 
+/// A single-line text input used to correct a rejected startup value from inside the TUI.
 #[derive(Debug, PartialEq, Eq)]
+pub struct Prompt {
+    pub field: PromptField,
+    pub buffer: String,
+}
+
+/// What stays put when the board is resized: its middle, or its top-left corner.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Anchor {
+    #[default]
+    Center,
+    TopLeft,
+}
+
+impl Anchor {
+    pub fn parse(input: &str) -> Result<Anchor, String> {
+        match input.trim().to_lowercase().as_str() {
+            "center" | "centre" => Ok(Anchor::Center),
+            "top-left" => Ok(Anchor::TopLeft),
+            _ => Err(format!(
+                "unknown anchor \"{input}\", expected center or top-left"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
@@ -66,19 +1255,41 @@ pub enum Direction {
     Right,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Preset {
     Blinker,
     Mold,
+    Glider,
+    Lwss,
+    Mwss,
+    Hwss,
+    GosperGliderGun,
+    Pulsar,
+    Pentadecathlon,
+    RPentomino,
+    Acorn,
+    Diehard,
+    Bunnies,
     Random,
     HorizontalLine,
     Empty,
+    /// A pattern loaded from the user's presets directory.
+    User {
+        name: String,
+        pattern: Pattern,
+    },
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Cell {
     pub is_alive: bool,
     pub age: u32,
+    /// Whether the cell has been alive since the board was loaded, kept under History rules
+    /// to show the envelope a pattern sweeps out.
+    pub ever_alive: bool,
+    /// Whether the cell was marked by hand under a History rule, to highlight it whether
+    /// it's alive or dead.
+    pub marked: bool,
 }
 
 impl Model {
@@ -87,7 +1298,7 @@ impl Model {
         max_x: i16,
         birth_list: Vec<u8>,
         survival_list: Vec<u8>,
-        tickrate: u16,
+        tickrate: Duration,
     ) -> Model {
         for birth in &birth_list {
             if *birth > 8 {
@@ -116,575 +1327,5072 @@ impl Model {
 
         Model {
             cells: outer,
+            scratch: vec![],
+            diff: Diff::default(),
+            observers: Observers::default(),
             rule: Rule {
                 birth_list,
                 survival_list,
+                history: false,
+                second_order: false,
             },
             state: State::Editing,
             current_coords: Coords { x: 0, y: 0 },
             max_coords: Coords { x: max_x, y: max_y },
             tickrate,
+            generation: 0,
+            config_errors: vec![],
+            prompt: None,
+            picker: None,
+            picker_kind: PickerKind::default(),
+            theme: Theme::default(),
+            layout: LayoutSpec::default(),
+            rng: RngSource::default(),
+            density: None,
+            symmetry: Symmetry::default(),
+            library: Library::default(),
+            stability: StabilityDetector::default(),
+            expected_stabilization: None,
+            comparison: None,
+            comparison_stale: false,
+            ghost: false,
+            previous: None,
+            fade: false,
+            last_tick: None,
+            export_html: false,
+            continuous: None,
+            kernel: None,
+            table: None,
+            mnca: None,
+            boundary: Boundary::default(),
+            neighborhood: Arc::new(Moore),
+            time_lapse: 1,
+            alerts: vec![],
+            bell: false,
+            field: vec![],
+            earlier: vec![],
+            zen: false,
+            stats: Stats::default(),
+            screen: Screen::default(),
+            inset: false,
+            inset_pin: None,
+            pause_on_focus_loss: false,
+            paused_for_focus: false,
+            zoom: 1,
+            viewport: Coords { x: 0, y: 0 },
+            board_area: Area::default(),
+            history: History::default(),
+            slot: 1,
+            notice: None,
+            errors: vec![],
+            debug_overlay: false,
+            overlay: false,
+            overlay_corner: Corner::default(),
+            help: false,
+            inspector: false,
+            fast_forward: None,
+            accessible: false,
+            announcement: None,
+            #[cfg(feature = "tui")]
+            keymap: Keymap::default(),
+            metrics: Metrics::default(),
+            last_preset: String::from(Preset::Empty.name()),
+            recorder: None,
+            checkpoints: Checkpoints::default(),
+            workspace: Workspace::default(),
+            journal: None,
+            run_started: SystemTime::now(),
+            run_generations: 0,
+            explorer: Explorer::default(),
+            tutorial: None,
+            scenario: None,
         }
     }
 
     pub fn load_preset(&mut self, preset: Preset) {
-        let cells = match preset {
-            Preset::Mold => vec![
-                vec![false, false, false, true, true, false],
-                vec![false, false, true, false, false, true],
-                vec![true, false, false, true, false, true],
-                vec![false, false, false, false, true, false],
-                vec![true, false, true, true, false, false],
-                vec![false, true, false, false, false, false],
-            ],
+        self.load_preset_at(preset, Coords { x: 0, y: 0 }, Transform::default());
+    }
 
-            Preset::Blinker => vec![
-                vec![false, false, false],
-                vec![true, true, true],
-                vec![false, false, false],
-            ],
+    /// Loads `preset`, oriented by `transform`, with its top-left corner at `origin`, clipping
+    /// whatever falls off the board. Presets generated to fill the board ignore both.
+    pub fn load_preset_at(&mut self, preset: Preset, origin: Coords, transform: Transform) {
+        self.end_run();
+        self.stability.reset(self.generation);
+        self.comparison_stale = true;
+        self.previous = None;
+        self.stats.clear();
+        self.checkpoints.clear();
+        self.last_preset = String::from(preset.name());
+        self.field.clear();
 
-            Preset::Random => {
-                let mut rng = thread_rng();
-                let mut outer = Vec::with_capacity((self.max_coords.y + 1) as usize);
-                for _ in 0..=self.max_coords.y {
-                    let mut inner: Vec<bool> = Vec::with_capacity((self.max_coords.x + 1) as usize);
-                    for _ in 0..=self.max_coords.x {
-                        inner.push(rng.gen_bool(0.3));
-                    }
-                    outer.push(inner);
-                }
-                outer
+        if let Some(pattern) = preset.pattern() {
+            // overwriting never refuses, and whatever falls off the board is clipped
+            let _ = self.place(&pattern, origin, transform, CollisionPolicy::Overwrite);
+            self.expect_stabilization(&preset);
+            return;
+        }
+
+        let density = self.density();
+        let (width, height) = (
+            (self.max_coords.x + 1) as usize,
+            (self.max_coords.y + 1) as usize,
+        );
+        let cells = match preset {
+            Preset::Random if self.continuous.is_some() => {
+                let continuous = self.continuous.as_ref().unwrap();
+                self.field = continuous.soup(&mut self.rng, width, height, density);
+                self.field
+                    .iter()
+                    .map(|line| {
+                        line.iter()
+                            .map(|state| *state >= continuous::ALIVE)
+                            .collect()
+                    })
+                    .collect()
             }
+            Preset::Random => soup(
+                &mut self.rng,
+                (self.max_coords.x + 1) as usize,
+                (self.max_coords.y + 1) as usize,
+                density,
+                self.symmetry,
+            ),
 
             Preset::HorizontalLine => {
                 let mut outer = Vec::with_capacity((self.max_coords.y + 1) as usize);
                 for y in 0..=self.max_coords.y {
                     let mut inner = Vec::with_capacity((self.max_coords.x + 1) as usize);
                     for _ in 0..=self.max_coords.x {
-                        inner.push(if y == self.max_coords.y / 2 {
-                            true
-                        } else {
-                            false
-                        });
+                        inner.push(y == self.max_coords.y / 2);
                     }
                     outer.push(inner);
                 }
                 outer
             }
 
-            Preset::Empty => vec![vec![false]],
+            _ => vec![vec![false]],
         };
 
-        self.insert_cells(Cell::vec_from(cells));
-    }
-
-    pub fn update(&mut self, msg: Message) {
-        match msg {
-            Message::Move(dir) => self.move_cursor_in_direction(dir),
-            Message::ToggleCellState => self.toggle_current_cell(),
-            Message::ToggleEditing => self.toggle_editing_state(),
-            Message::Idle => self.pass_tick(),
-            Message::Quit => self.quit(),
-        }
-    }
-
-    pub fn current_coords(&self) -> &Coords {
-        &self.current_coords
-    }
-
-    pub fn update_cell(&mut self, y: usize, x: usize, val: bool) {
-        if (y as i16 <= self.max_coords.y) && (x as i16 <= self.max_coords.x) {
-            self.cells[y][x].is_alive = val;
-            self.cells[y][x].age = 0;
-        }
-    }
-    pub fn increment_cell_age(&mut self, y: usize, x: usize) {
-        if (y as i16 <= self.max_coords.y) && (x as i16 <= self.max_coords.x) {
-            self.cells[y][x].age += 1;
-        }
-    }
-
-    pub fn cells(&self) -> &Vec<Vec<Cell>> {
-        &self.cells
-    }
-
-    pub fn state(&self) -> &State {
-        &self.state
+        let pattern = Pattern {
+            cells,
+            rule: None,
+            metadata: Default::default(),
+        };
+        let origin = Coords { x: 0, y: 0 };
+        let _ = self.place(
+            &pattern,
+            origin,
+            Transform::default(),
+            CollisionPolicy::Overwrite,
+        );
+        self.expect_stabilization(&preset);
     }
 
-    pub fn tickrate(&self) -> u16 {
-        self.tickrate
+    /// Remembers when `preset` is known to stabilize, if it is under the rule in force.
+    fn expect_stabilization(&mut self, preset: &Preset) {
+        self.expected_stabilization = preset
+            .expected_stabilization()
+            .filter(|_| self.rulestring() == "B3/S23");
     }
 
-    pub fn rulestring(&self) -> String {
-        let mut result = String::from("B");
-        for birth_rule in &self.rule.birth_list {
-            result.push_str(&birth_rule.to_string());
-        }
-
-        result.push_str("/S");
-
-        for survival_rule in &self.rule.survival_list {
-            result.push_str(&survival_rule.to_string());
+    pub fn update(&mut self, msg: Message) {
+        if msg != Message::Idle {
+            debug!(?msg, state = ?self.state, "update");
+            self.notice = None;
         }
-        result
-    }
-
-    pub fn pass_tick(&mut self) {
-        if *self.state() != State::Running {
-            return;
+        let before = self
+            .accessible
+            .then(|| (self.state.clone(), self.stabilization().is_some()));
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(error) = recorder.record(&msg) {
+                self.recorder = None;
+                self.report(ErrorReport::new("Recording stopped", &error));
+            }
         }
-
-        let cells_prev = (*self.cells()).clone();
-        for (y, line) in cells_prev.iter().enumerate() {
-            for (x, cell) in line.iter().enumerate() {
-                let mut active_neighbors = 0;
-
-                // find total active neighbors
-                let can_go_up = if y > 0 { true } else { false };
-
-                let can_go_down = if (y as i16) < self.max_coords.y {
-                    true
-                } else {
-                    false
-                };
-
-                let can_go_left = if x > 0 { true } else { false };
-
-                let can_go_right = if (x as i16) < self.max_coords.x {
-                    true
-                } else {
-                    false
-                };
-
-                // take care of upper, upper-left, and upper-right neighbors
-                if can_go_up {
-                    if cells_prev[y - 1][x].is_alive {
-                        active_neighbors += 1
-                    }
-
-                    if can_go_left {
-                        if cells_prev[y - 1][x - 1].is_alive {
-                            active_neighbors += 1
-                        }
+        match msg {
+            Message::Move(dir, cells) => {
+                self.move_cursor_in_direction(dir, cells);
+                self.learn(Lesson::Move);
+            }
+            Message::ToggleCellState => {
+                self.toggle_current_cell();
+                self.learn(Lesson::Draw);
+            }
+            Message::Randomize => self.load_preset(Preset::Random),
+            Message::RandomizeView => {
+                if self.state == State::Editing {
+                    self.randomize_view();
+                }
+            }
+            Message::ToggleGhost => self.set_ghost(!self.ghost),
+            Message::ToggleMark => {
+                if self.state == State::Editing && self.rule.history {
+                    let Coords { x, y } = self.current_coords;
+                    if let Some(cell) = self.cell_mut(x as usize, y as usize) {
+                        cell.marked = !cell.marked;
                     }
-
-                    if can_go_right {
-                        if cells_prev[y - 1][x + 1].is_alive {
-                            active_neighbors += 1
-                        }
+                }
+            }
+            Message::StepBack => {
+                if self.state == State::Editing {
+                    self.step_back();
+                }
+            }
+            Message::Mutate => self.mutate_rule(),
+            Message::SwitchScreen => {
+                self.screen = match self.screen {
+                    Screen::Board => Screen::Stats,
+                    Screen::Stats => Screen::Board,
+                };
+            }
+            Message::ToggleInset => self.inset = !self.inset,
+            Message::ToggleInsetPin => {
+                self.inset_pin = match self.inset_pin {
+                    Some(_) => None,
+                    None => Some(self.current_coords),
+                };
+            }
+            Message::Faster => self.tickrate = (self.tickrate / 2).max(MIN_TICKRATE),
+            Message::Slower => self.tickrate = (self.tickrate * 2).min(MAX_TICKRATE),
+            Message::Zoom(steps) => {
+                let zoom = (self.zoom as i16).saturating_add(steps);
+                self.zoom = zoom.clamp(1, MAX_ZOOM as i16) as u16;
+            }
+            Message::SetCell(coords, alive) => {
+                if self.state == State::Editing {
+                    self.set_cell(coords, alive);
+                    self.learn(Lesson::Draw);
+                }
+            }
+            Message::Pan(columns, rows) => {
+                self.viewport.x = self.viewport.x.saturating_add(columns);
+                self.viewport.y = self.viewport.y.saturating_add(rows);
+                if self.state == State::Editing {
+                    self.move_cursor_in_direction(Direction::Right, columns);
+                    self.move_cursor_in_direction(Direction::Down, rows);
+                }
+            }
+            Message::ToggleZen => {
+                // zen mode is for watching, so it's only entered while running
+                self.zen = !self.zen && self.state == State::Running;
+            }
+            Message::FocusLost => {
+                if self.pause_on_focus_loss
+                    && self.state == State::Running
+                    && self.transition(&Message::ToggleEditing)
+                {
+                    self.paused_for_focus = true;
+                    self.zen = false;
+                }
+            }
+            Message::FocusGained => {
+                if self.paused_for_focus && self.state == State::Editing {
+                    self.transition(&Message::ToggleEditing);
+                }
+                self.paused_for_focus = false;
+            }
+            Message::ToggleEditing | Message::Quit => {
+                // pausing or resuming by hand overrides an automatic pause
+                self.paused_for_focus = false;
+                if self.transition(&msg) && msg == Message::ToggleEditing {
+                    self.learn(match self.state {
+                        State::Running => Lesson::Run,
+                        _ => Lesson::Pause,
+                    });
+                }
+            }
+            Message::OpenPrompt => {
+                if self.transition(&msg) {
+                    self.open_prompt();
+                }
+            }
+            Message::PromptInput(ch) => {
+                if let Some(prompt) = &mut self.prompt {
+                    prompt.buffer.push(ch);
+                }
+            }
+            Message::PromptBackspace => {
+                if let Some(prompt) = &mut self.prompt {
+                    prompt.buffer.pop();
+                }
+            }
+            Message::PromptSubmit => {
+                if self.transition(&msg) {
+                    self.submit_prompt();
+                }
+            }
+            Message::PromptCancel => {
+                if self.transition(&msg) {
+                    self.prompt = None;
+                }
+            }
+            Message::OpenPicker => {
+                if self.transition(&msg) {
+                    self.open_picker();
+                }
+            }
+            Message::OpenRecent => {
+                if self.transition(&msg) {
+                    self.open_recent();
+                }
+            }
+            Message::OpenCheckpoints => {
+                if self.transition(&msg) {
+                    self.open_checkpoints();
+                }
+            }
+            Message::DismissError => {
+                self.errors.pop();
+            }
+            Message::ToggleDebug => self.debug_overlay = !self.debug_overlay,
+            Message::ToggleOverlay => self.overlay = !self.overlay,
+            Message::CycleTimeLapse => {
+                let next = if self.time_lapse >= MAX_TIME_LAPSE {
+                    1
+                } else {
+                    (self.time_lapse * 10).min(MAX_TIME_LAPSE)
+                };
+                self.set_time_lapse(next);
+                self.set_notice(format!("{next} generations a tick"));
+            }
+            Message::OpenResize => {
+                if self.transition(&msg) {
+                    let (width, height) = self.size();
+                    self.prompt = Some(Prompt {
+                        field: PromptField::Size,
+                        buffer: format!("{width}x{height}"),
+                    });
+                }
+            }
+            Message::Resize(width, height, anchor) => {
+                self.resize(width as usize, height as usize, anchor);
+            }
+            Message::ToggleHelp => self.help = !self.help,
+            Message::ToggleInspector => self.inspector = !self.inspector,
+            Message::Describe => {
+                let description = describe(&self.alive_board());
+                self.announce(format!("Generation {}: {description}", self.generation));
+            }
+            Message::OpenWorkspace => {
+                if self.transition(&msg) {
+                    self.open_workspace();
+                }
+            }
+            Message::SelectSlot(slot) => {
+                if (1..=SLOTS).contains(&slot) {
+                    self.slot = slot;
+                }
+            }
+            Message::PickerInput(ch) => {
+                if let Some(picker) = &mut self.picker {
+                    picker.push(ch);
+                }
+            }
+            Message::PickerBackspace => {
+                if let Some(picker) = &mut self.picker {
+                    picker.pop();
+                }
+            }
+            Message::PickerMove(dir) => {
+                if let Some(picker) = &mut self.picker {
+                    match dir {
+                        Direction::Up => picker.select_relative(-1),
+                        Direction::Down => picker.select_relative(1),
+                        Direction::Left | Direction::Right => {}
                     }
                 }
-
-                // take care of lower, lower-left, and lower-right neighbors
-                if can_go_down {
-                    if cells_prev[y + 1][x].is_alive {
-                        active_neighbors += 1
+            }
+            Message::PickerSubmit => {
+                if self.transition(&msg) {
+                    self.submit_picker();
+                }
+            }
+            Message::PickerCancel => {
+                if self.transition(&msg) {
+                    self.picker = None;
+                }
+            }
+            Message::FastForward => {
+                if self.transition(&msg) {
+                    self.fast_forward = Some(FastForward {
+                        from: self.generation,
+                        to: self.generation + FAST_FORWARD,
+                        until_stable: false,
+                    });
+                }
+            }
+            Message::RunUntilStable => {
+                if self.transition(&msg) {
+                    self.fast_forward = Some(FastForward {
+                        from: self.generation,
+                        to: self.generation + RUN_UNTIL_STABLE,
+                        until_stable: true,
+                    });
+                    // a board that has already settled is reported without stepping
+                    self.fast_forward_batch();
+                }
+            }
+            Message::StopFastForward => {
+                if self.transition(&msg) {
+                    self.fast_forward = None;
+                    self.set_notice(format!(
+                        "Fast-forward stopped at generation {}",
+                        self.generation
+                    ));
+                }
+            }
+            Message::Continue => {
+                if let Some(scenario) = &mut self.scenario {
+                    if scenario.waiting() == Some(Waiting::Continue) {
+                        scenario.next();
+                        self.play_scenario();
                     }
+                }
+            }
+            Message::Predict(predicted) => {
+                let inspection = self.inspect();
+                if let (Some(scenario), Some(inspection)) = (&mut self.scenario, inspection) {
+                    scenario.answer_with(Answer {
+                        predicted,
+                        actual: inspection.next,
+                        reason: inspection.reason,
+                    });
+                }
+            }
+            Message::Idle => match self.state {
+                State::FastForwarding { .. } => self.fast_forward_batch(),
+                _ => self.pass_tick(),
+            },
+        }
+        if let Some((state, stable)) = before {
+            self.announce_changes(&state, stable);
+        }
 
-                    if can_go_left {
-                        if cells_prev[y + 1][x - 1].is_alive {
-                            active_neighbors += 1
-                        }
-                    }
+        if self.comparison_stale {
+            self.sync_comparison();
+        }
+    }
 
-                    if can_go_right {
-                        if cells_prev[y + 1][x + 1].is_alive {
-                            active_neighbors += 1
-                        }
-                    }
-                }
+    /// Announces entering a new state since it was `state`, and the board settling down if it
+    /// wasn't `stable` before.
+    fn announce_changes(&mut self, state: &State, stable: bool) {
+        if self.state != *state {
+            let announcement = match &self.state {
+                State::Editing => format!("Editing at generation {}", self.generation),
+                State::Running => String::from("Running"),
+                State::Prompting { .. } => String::from("Prompt open, type and press Enter"),
+                State::Picking { .. } => String::from("Picker open, type to search"),
+                State::FastForwarding { .. } => match self.fast_forward {
+                    Some(FastForward {
+                        until_stable: true, ..
+                    }) => String::from("Running until stable, Escape to stop"),
+                    _ => format!("Fast-forwarding {FAST_FORWARD} generations, Escape to stop"),
+                },
+                State::Done => return,
+            };
+            self.announce(announcement);
+        }
+        if let (false, Some(stabilization)) = (stable, self.stabilization()) {
+            let population = self
+                .alive_board()
+                .iter()
+                .flatten()
+                .filter(|&&alive| alive)
+                .count();
+            self.announce(if population == 0 {
+                format!("Every cell died by generation {}", stabilization.generation)
+            } else {
+                format!(
+                    "Settled at generation {} with period {}",
+                    stabilization.generation, stabilization.period
+                )
+            });
+        }
+    }
 
-                // take care of left neighbor
-                if can_go_left {
-                    if cells_prev[y][x - 1].is_alive {
-                        active_neighbors += 1
-                    }
-                }
+    /// Moves to the state [`State::transition`] allows for `msg`, provided the model-level
+    /// guards also pass. Returns whether the state changed.
+    fn transition(&mut self, msg: &Message) -> bool {
+        let Some(next) = self.state.transition(msg) else {
+            return false;
+        };
 
-                // take care of right neighbor
-                if can_go_right {
-                    if cells_prev[y][x + 1].is_alive {
-                        active_neighbors += 1
-                    }
-                }
+        // there's nothing to correct unless a correctable configuration error is outstanding
+        if *msg == Message::OpenPrompt && !self.config_errors.iter().any(|e| e.field().is_some()) {
+            return false;
+        }
 
-                if (*cell).is_alive {
-                    // check if living cell survives
-                    let mut kill_cell = true;
-                    for criterion in &self.rule.survival_list.clone() {
-                        if active_neighbors == *criterion {
-                            kill_cell = false;
-                        }
-                    }
-                    if kill_cell {
-                        self.update_cell(y, x, false);
-                    } else {
-                        self.increment_cell_age(y, x);
-                    }
-                } else {
-                    // check if cell is born
-                    for criterion in &self.rule.birth_list.clone() {
-                        if active_neighbors == *criterion {
-                            self.update_cell(y, x, true);
-                        }
+        self.state = next;
+        true
+    }
+
+    /// Width and height of the board in cells.
+    pub fn size(&self) -> (usize, usize) {
+        (
+            self.max_coords.x as usize + 1,
+            self.max_coords.y as usize + 1,
+        )
+    }
+
+    /// Grows or crops the board to `width` by `height` cells, between [`MIN_BOARD_SIZE`] and
+    /// [`MAX_BOARD_SIZE`] across and down, keeping what's on it around `anchor`. Cells cropped
+    /// off are lost, and the cells added are dead.
+    pub fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let (width, height) = (
+            width.clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE),
+            height.clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE),
+        );
+        let (old_width, old_height) = self.size();
+        if (width, height) == (old_width, old_height) {
+            return;
+        }
+        // where the old board's top-left cell ends up on the new one
+        let offset = match anchor {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Center => (
+                (width as isize - old_width as isize) / 2,
+                (height as isize - old_height as isize) / 2,
+            ),
+        };
+        debug!(
+            old_width,
+            old_height,
+            width,
+            height,
+            ?anchor,
+            "resizing the board"
+        );
+
+        let dead = self.is_inverted();
+        let cells = mem::take(&mut self.cells);
+        self.cells = reframe(cells, (width, height), offset, || Cell::new(dead));
+        if !self.field.is_empty() {
+            let field = mem::take(&mut self.field);
+            self.field = reframe(field, (width, height), offset, || 0.0);
+        }
+        if !self.earlier.is_empty() {
+            let earlier = mem::take(&mut self.earlier);
+            self.earlier = reframe(earlier, (width, height), offset, || false);
+        }
+        self.max_coords = Coords {
+            x: width as i16 - 1,
+            y: height as i16 - 1,
+        };
+        let max_coords = self.max_coords;
+        let moved = |coords: Coords| {
+            let (dx, dy) = (offset.0 as i16, offset.1 as i16);
+            coords.offset(dx, dy).within(max_coords)
+        };
+        self.current_coords = moved(self.current_coords);
+        self.inset_pin = self.inset_pin.map(moved);
+        self.viewport = moved(self.viewport);
+        // checkpoints and the last diff are of the old board, and wouldn't line up with this one
+        self.checkpoints.clear();
+        self.diff = Diff::default();
+        self.board_edited();
+        if let Some(comparison) = &mut self.comparison {
+            comparison.resize(width, height, anchor);
+        }
+    }
+
+    pub fn current_coords(&self) -> &Coords {
+        &self.current_coords
+    }
+
+    /// Returns the cell at `(x, y)`, or `None` if the coordinates fall outside the board.
+    pub fn cell(&self, x: usize, y: usize) -> Option<&Cell> {
+        self.cells.get(y)?.get(x)
+    }
+
+    /// Mutable counterpart of [`Model::cell`].
+    pub fn cell_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        self.cells.get_mut(y)?.get_mut(x)
+    }
+
+    /// Sets the liveness of the cell at `(x, y)` and resets its age. Returns `None` without
+    /// touching the board if the coordinates are out of range.
+    pub fn try_set(&mut self, x: usize, y: usize, val: bool) -> Option<()> {
+        let cell = self.cell_mut(x, y)?;
+        cell.is_alive = val;
+        cell.age = 0;
+        Some(())
+    }
+
+    pub fn update_cell(&mut self, y: usize, x: usize, val: bool) {
+        self.try_set(x, y, val);
+    }
+
+    /// Counts another generation survived by the cell at `y`, `x`, which stops counting
+    /// rather than overflowing on a board left running for good.
+    pub fn increment_cell_age(&mut self, y: usize, x: usize) {
+        if let Some(cell) = self.cell_mut(x, y) {
+            cell.age = cell.age.saturating_add(1);
+        }
+    }
+
+    pub fn cells(&self) -> &Vec<Vec<Cell>> {
+        &self.cells
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn tickrate(&self) -> Duration {
+        self.tickrate
+    }
+
+    /// The time generations actually take while running, which is longer than the tickrate
+    /// when stepping the board can't keep up.
+    pub fn effective_tickrate(&self) -> Duration {
+        self.tickrate.max(self.metrics.tick)
+    }
+
+    pub fn rulestring(&self) -> String {
+        self.rule.to_string()
+    }
+
+    /// The well-known rule the board runs under, if it's one. Boards stepped by a continuous
+    /// automaton, MNCA, table or kernel have none.
+    pub fn known_rule(&self) -> Option<&'static KnownRule> {
+        let replaced = self.continuous.is_some()
+            || self.mnca.is_some()
+            || self.table.is_some()
+            || self.kernel.is_some();
+        (!replaced).then(|| rulebook::lookup(&self.rule)).flatten()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether the stored board is currently the complement of the real one, which is how
+    /// rules containing B0 are emulated. See [`Rule::is_inverted`].
+    pub fn is_inverted(&self) -> bool {
+        self.is_inverted_at(self.generation)
+    }
+
+    /// Whether the stored board is the complement of the real one at `generation`. Kernels,
+    /// tables, MNCAs and continuous automata never give birth on an empty neighborhood, so only
+    /// the rule can.
+    fn is_inverted_at(&self, generation: u64) -> bool {
+        self.continuous.is_none()
+            && self.kernel.is_none()
+            && self.table.is_none()
+            && self.mnca.is_none()
+            && self.rule.is_inverted(generation)
+    }
+
+    /// When the board became periodic, if it has since loading the preset or the last edit.
+    pub fn stabilization(&self) -> Option<Stabilization> {
+        self.stability.stabilization()
+    }
+
+    /// The generation the loaded preset is known to stabilize at under Life, if it's one of the
+    /// bundled methuselahs and the board hasn't been edited since.
+    pub fn expected_stabilization(&self) -> Option<u64> {
+        self.expected_stabilization
+    }
+
+    /// Whether the cell at `(x, y)` is really alive, taking B0 phase inversion into account.
+    pub fn is_alive(&self, x: usize, y: usize) -> Option<bool> {
+        self.cell(x, y)
+            .map(|cell| cell.is_alive != self.is_inverted())
+    }
+
+    pub fn config_errors(&self) -> &[ConfigError] {
+        &self.config_errors
+    }
+
+    pub fn set_config_errors(&mut self, errors: Vec<ConfigError>) {
+        self.config_errors = errors;
+    }
+
+    pub fn prompt(&self) -> Option<&Prompt> {
+        self.prompt.as_ref()
+    }
+
+    pub fn picker(&self) -> Option<&Picker> {
+        self.picker.as_ref()
+    }
+
+    pub fn picker_kind(&self) -> PickerKind {
+        self.picker_kind
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn set_history(&mut self, history: History) {
+        self.history = history;
+    }
+
+    /// The recent run listed under `label` in the recent menu.
+    pub fn recent(&self, label: &str) -> Option<&Recent> {
+        self.history
+            .entries()
+            .iter()
+            .find(|entry| entry.label() == label)
+    }
+
+    pub fn workspace(&self) -> &Workspace {
+        &self.workspace
+    }
+
+    pub fn set_workspace(&mut self, workspace: Workspace) {
+        self.workspace = workspace;
+    }
+
+    /// Copies the board every `interval` generations from now on, or never if it's 0.
+    pub fn set_checkpoint_interval(&mut self, interval: u64) {
+        self.checkpoints = Checkpoints::new(interval);
+    }
+
+    pub fn explorer(&self) -> &Explorer {
+        &self.explorer
+    }
+
+    /// Mutates the rule every `every` generations of a run, or only on demand if `None`.
+    pub fn set_mutation_interval(&mut self, every: Option<u64>) {
+        self.explorer = Explorer::new(every);
+    }
+
+    pub fn tutorial(&self) -> Option<&Tutorial> {
+        self.tutorial.as_ref()
+    }
+
+    pub fn set_tutorial(&mut self, tutorial: Option<Tutorial>) {
+        self.tutorial = tutorial;
+    }
+
+    pub fn scenario(&self) -> Option<&Scenario> {
+        self.scenario.as_ref()
+    }
+
+    /// Starts playing through `scenario`, up to its first step that waits for the user.
+    pub fn set_scenario(&mut self, scenario: Option<Scenario>) {
+        self.scenario = scenario;
+        self.play_scenario();
+    }
+
+    /// Carries out the lesson's steps from the current one until one waits for the user:
+    /// text to read or a prediction to make, which puts the cursor on the cell in question.
+    fn play_scenario(&mut self) {
+        while let Some(step) = self.scenario.as_ref().and_then(Scenario::current).cloned() {
+            match step {
+                Step::Load { name, at } => {
+                    if let Some(preset) = self.library.resolve(&name) {
+                        self.load_preset_at(preset, at, Transform::default());
                     }
                 }
+                Step::Rule(rule) => {
+                    self.rule = rule;
+                    self.board_edited();
+                }
+                Step::Advance(generations) => {
+                    self.step_n(generations);
+                }
+                Step::Say(_) => return,
+                Step::Predict(at) => {
+                    self.current_coords = at.within(self.max_coords);
+                    return;
+                }
+            }
+            if let Some(scenario) = &mut self.scenario {
+                scenario.next();
             }
         }
     }
 
-    fn insert_cells(&mut self, cells: Vec<Vec<Cell>>) {
-        for (y, line) in cells.iter().enumerate() {
-            for (x, cell) in line.iter().enumerate() {
-                self.cells[y][x].is_alive = cell.is_alive;
+    /// Tells the tutorial, if there is one, that `lesson` was just done.
+    pub fn learn(&mut self, lesson: Lesson) {
+        if let Some(tutorial) = &mut self.tutorial {
+            tutorial.learn(lesson);
+        }
+    }
+
+    /// The checkpoint listed under `label` in the rollback menu.
+    pub fn checkpoint(&self, label: &str) -> Option<&Checkpoint> {
+        self.checkpoints
+            .newest_first()
+            .find(|checkpoint| checkpoint.label() == label)
+    }
+
+    /// Logs every run to `journal` from now on.
+    pub fn set_journal(&mut self, journal: Journal) {
+        self.journal = Some(journal);
+    }
+
+    /// Logs the current run to the journal, if there is one and the run got anywhere, and
+    /// starts timing a new one.
+    pub fn log_run(&mut self) -> io::Result<()> {
+        let run = Run {
+            started: self.run_started,
+            preset: self.last_preset.clone(),
+            rule: self.rulestring(),
+            seed: self.rng.seed(),
+            generations: self.run_generations,
+            population: self.stats.latest().map_or(0, |sample| sample.population),
+            stabilization: self.stability.stabilization(),
+        };
+        self.run_started = SystemTime::now();
+        self.run_generations = 0;
+        match &self.journal {
+            Some(journal) if run.generations > 0 => {
+                debug!(?run, "logging run to the journal");
+                journal.append(&run)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records every message from now on with `recorder`.
+    pub fn set_recorder(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
+    }
+
+    pub fn slot(&self) -> u8 {
+        self.slot
+    }
+
+    pub fn notice(&self) -> Option<&str> {
+        self.notice.as_deref()
+    }
+
+    pub fn set_notice(&mut self, notice: String) {
+        self.notice = Some(notice);
+    }
+
+    pub fn is_debug_overlay(&self) -> bool {
+        self.debug_overlay
+    }
+
+    /// The corner the recording overlay is shown in, if it's shown.
+    pub fn overlay(&self) -> Option<Corner> {
+        self.overlay.then_some(self.overlay_corner)
+    }
+
+    /// Shows the recording overlay in `corner`, or hides it. It stays in the corner it was
+    /// last given when toggled.
+    pub fn set_overlay(&mut self, corner: Option<Corner>) {
+        self.overlay = corner.is_some();
+        self.overlay_corner = corner.unwrap_or(self.overlay_corner);
+    }
+
+    pub fn is_help(&self) -> bool {
+        self.help
+    }
+
+    /// Whether the neighborhood inspector is shown, which it only is while editing.
+    pub fn is_inspector(&self) -> bool {
+        self.inspector && self.state == State::Editing
+    }
+
+    /// What the neighborhood inspector says about the cell under the cursor, counting its
+    /// neighbors the way the next step will.
+    pub fn inspect(&self) -> Option<Inspection> {
+        let Coords { x, y } = self.current_coords;
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        let age = self.cell(x, y)?.age;
+        let alive = self.is_alive(x, y)?;
+
+        let margin = self.reach();
+        let board = self.boundary.pad(&alive_grid_of(&self.cells), margin);
+        let (y_in, x_in) = (y + margin, x + margin);
+        let neighborhood = self.neighborhood.as_ref();
+        let mut neighbors = live_neighbors(&board, y_in, x_in, neighborhood);
+        // under B0 emulation the stored board may be the complement of the real one
+        if self.is_inverted() {
+            neighbors = neighborhood.size() as u8 - neighbors;
+        }
+
+        let (next, mut reason) = if self.continuous.is_some() {
+            let reason = String::from("The continuous rule decides from the smooth field");
+            (None, reason)
+        } else if let Some(next) = self.replaced_verdict(&board, y_in, x_in) {
+            let by = match (&self.mnca, &self.table) {
+                (Some(_), _) => "MNCA",
+                (None, Some(_)) => "table",
+                (None, None) => "kernel",
+            };
+            let what = match (alive, next) {
+                (true, true) => "keeps it alive",
+                (true, false) => "kills it",
+                (false, true) => "brings it to life",
+                (false, false) => "leaves it dead",
+            };
+            (Some(next), format!("The {by} {what}"))
+        } else {
+            let (letter, list) = match alive {
+                true => ('S', &self.rule.survival_list),
+                false => ('B', &self.rule.birth_list),
+            };
+            let next = list.contains(&neighbors);
+            let digits: String = list.iter().map(u8::to_string).collect();
+            let what = match (alive, next) {
+                (true, true) => "Survives",
+                (true, false) => "Dies",
+                (false, true) => "Is born",
+                (false, false) => "Stays dead",
+            };
+            let has = if next { "has" } else { "lacks" };
+            (
+                Some(next),
+                format!("{what}: {letter}{digits} {has} {neighbors}"),
+            )
+        };
+        let next = next.map(|next| {
+            let flipped = self.rule.second_order && self.was_alive_earlier(y, x);
+            if flipped {
+                reason.push_str(", flipped by Reversible since it was alive a generation ago");
             }
+            next != flipped
+        });
+
+        Some(Inspection {
+            at: self.current_coords,
+            alive,
+            age,
+            neighbors,
+            next,
+            reason,
+        })
+    }
+
+    pub fn is_accessible(&self) -> bool {
+        self.accessible
+    }
+
+    pub fn set_accessible(&mut self, accessible: bool) {
+        self.accessible = accessible;
+    }
+
+    /// Tells the user about a change: in the status line in the screen-reader friendly mode,
+    /// where it stays until the next announcement, or as a notice otherwise.
+    pub fn announce(&mut self, announcement: String) {
+        if self.accessible {
+            self.announcement = Some(announcement);
+        } else {
+            self.set_notice(announcement);
+        }
+    }
+
+    /// The screen-reader friendly mode's status line: the latest announcement, then where the
+    /// cursor is while editing and how many cells are alive.
+    pub fn status_line(&self) -> String {
+        let mut status = self.announcement.clone().unwrap_or_default();
+        if !status.is_empty() {
+            status.push_str(" | ");
+        }
+        if self.state == State::Editing {
+            let Coords { x, y } = self.current_coords;
+            let cell = match self.is_alive(x as usize, y as usize) {
+                Some(true) => "alive",
+                _ => "dead",
+            };
+            status.push_str(&format!("Cursor at column {x}, row {y}, {cell}. "));
+        }
+        let population = self
+            .alive_board()
+            .iter()
+            .flatten()
+            .filter(|&&alive| alive)
+            .count();
+        status.push_str(&format!(
+            "Generation {}, {population} alive.",
+            self.generation
+        ));
+        status
+    }
+
+    /// Which cells are alive, with the B0 emulation's inversion undone.
+    pub fn alive_board(&self) -> Vec<Vec<bool>> {
+        let inverted = self.is_inverted();
+        self.cells
+            .iter()
+            .map(|line| line.iter().map(|cell| cell.is_alive != inverted).collect())
+            .collect()
+    }
+
+    #[cfg(feature = "tui")]
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    #[cfg(feature = "tui")]
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Records how many events the last batch of input held, for the debug overlay.
+    pub fn set_queue_depth(&mut self, depth: usize) {
+        self.metrics.queue_depth = depth;
+    }
+
+    /// The name of the engine stepping the board, for the debug overlay.
+    pub fn engine(&self) -> &'static str {
+        match (&self.continuous, &self.mnca, &self.table, &self.kernel) {
+            (Some(continuous), ..) => continuous.name(),
+            (None, Some(_), ..) => "mnca",
+            (None, None, Some(_), _) => "table",
+            (None, None, None, Some(_)) => "weighted",
+            (None, None, None, None) => "naive",
+        }
+    }
+
+    pub fn kernel(&self) -> Option<&Kernel> {
+        self.kernel.as_ref()
+    }
+
+    pub fn set_kernel(&mut self, kernel: Option<Kernel>) {
+        self.kernel = kernel;
+        self.board_edited();
+    }
+
+    pub fn table(&self) -> Option<&TransitionTable> {
+        self.table.as_ref()
+    }
+
+    pub fn set_table(&mut self, table: Option<TransitionTable>) {
+        self.table = table;
+        self.board_edited();
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+        self.board_edited();
+    }
+
+    pub fn neighborhood(&self) -> &dyn Neighborhood {
+        self.neighborhood.as_ref()
+    }
+
+    /// Counts `neighborhood` around each cell from now on, here and on the comparison board.
+    pub fn set_neighborhood(&mut self, neighborhood: Arc<dyn Neighborhood>) {
+        if let Some(comparison) = &mut self.comparison {
+            comparison.set_neighborhood(Arc::clone(&neighborhood));
+        }
+        self.neighborhood = neighborhood;
+        self.board_edited();
+    }
+
+    pub fn mnca(&self) -> Option<&Mnca> {
+        self.mnca.as_ref()
+    }
+
+    pub fn set_mnca(&mut self, mnca: Option<Mnca>) {
+        self.mnca = mnca;
+        self.board_edited();
+    }
+
+    pub fn continuous(&self) -> Option<&Continuous> {
+        self.continuous.as_ref()
+    }
+
+    pub fn set_continuous(&mut self, continuous: Option<Continuous>) {
+        self.continuous = continuous;
+        self.field.clear();
+        self.board_edited();
+    }
+
+    /// The continuous state of the cell at `x`, `y`, if a continuous automaton is running.
+    pub fn intensity(&self, x: usize, y: usize) -> Option<f32> {
+        self.continuous.as_ref()?;
+        match self.field.get(y).and_then(|line| line.get(x)) {
+            Some(state) => Some(*state),
+            None => self
+                .cell(x, y)
+                .map(|cell| f32::from(u8::from(cell.is_alive))),
+        }
+    }
+
+    /// Recoverable errors waiting to be dismissed, oldest first.
+    pub fn errors(&self) -> &[ErrorReport] {
+        &self.errors
+    }
+
+    /// Shows `error` in the error panel until it's dismissed.
+    pub fn report(&mut self, error: ErrorReport) {
+        warn!(context = error.context, chain = ?error.chain, "reported error");
+        self.errors.push(error);
+    }
+
+    /// Replaces the board with a saved one: the pattern's cells from the top-left corner, its
+    /// rule if it names a valid one, and `generation`.
+    pub fn restore(&mut self, pattern: &Pattern, generation: u64) {
+        self.end_run();
+        if let Some(rule) = pattern.rule.as_deref().and_then(|rule| rule.parse().ok()) {
+            self.rule = rule;
+        }
+        self.clear_board();
+        self.generation = generation;
+        // dead cells are stored alive while the phase is inverted
+        let inverted = self.is_inverted();
+        for cell in self.cells.iter_mut().flatten() {
+            cell.is_alive = inverted;
+        }
+        let origin = Placement::TopLeft
+            .for_pattern(pattern, Transform::default(), self.size())
+            .origin(pattern.size(), self.size());
+        let _ = self.place(
+            pattern,
+            origin,
+            Transform::default(),
+            CollisionPolicy::Overwrite,
+        );
+        self.stats.clear();
+    }
+
+    /// Records the last preset loaded, the rule and the seed as the most recent run.
+    pub fn remember(&mut self) {
+        self.history.record(Recent {
+            preset: self.last_preset.clone(),
+            rule: self.rulestring(),
+            seed: self.rng.seed(),
+        });
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn layout(&self) -> &LayoutSpec {
+        &self.layout
+    }
+
+    pub fn set_layout(&mut self, layout: LayoutSpec) {
+        self.layout = layout;
+    }
+
+    /// Runs the board under `rule` from now on, ending the run under the old one.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.end_run();
+        self.rule = rule;
+        self.board_edited();
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.comparison_stale = true;
+    }
+
+    /// Runs a copy of the board under `rule` alongside this one from now on.
+    pub fn set_comparison(&mut self, rule: Rule) {
+        let mut comparison = Model::new(
+            self.max_coords.y,
+            self.max_coords.x,
+            rule.birth_list,
+            rule.survival_list,
+            self.tickrate,
+        );
+        comparison.state = State::Running;
+        comparison.neighborhood = Arc::clone(&self.neighborhood);
+        self.comparison = Some(Box::new(comparison));
+        self.sync_comparison();
+    }
+
+    /// Whether the cell at `(x, y)` was alive in the previous generation, as shown by the ghost
+    /// view. Always `false` while the ghost view is off or before the first step.
+    pub fn was_alive(&self, x: usize, y: usize) -> bool {
+        self.ghost && self.lived_before(x, y)
+    }
+
+    fn lived_before(&self, x: usize, y: usize) -> bool {
+        self.previous
+            .as_ref()
+            .and_then(|previous| previous.get(y)?.get(x).copied())
+            .unwrap_or(false)
+    }
+
+    pub fn set_ghost(&mut self, ghost: bool) {
+        self.ghost = ghost;
+        if !ghost && !self.fade {
+            self.previous = None;
+        }
+        if let Some(comparison) = &mut self.comparison {
+            comparison.set_ghost(ghost);
+        }
+    }
+
+    pub fn set_fade(&mut self, fade: bool) {
+        self.fade = fade;
+        if !fade && !self.ghost {
+            self.previous = None;
+        }
+        if let Some(comparison) = &mut self.comparison {
+            comparison.set_fade(fade);
+        }
+    }
+
+    pub fn export_html(&self) -> bool {
+        self.export_html
+    }
+
+    pub fn set_export_html(&mut self, export_html: bool) {
+        self.export_html = export_html;
+    }
+
+    /// How far through the current tick the board is, from 0 right after stepping to 1 when
+    /// the next step is due, while cells are fading. `None` unless `fade` is on, the board is
+    /// running at [`FADE_TICKRATE`] or slower and there's a previous generation to fade from.
+    pub fn fade_progress(&self) -> Option<f32> {
+        if !self.fade || self.state != State::Running || self.tickrate < FADE_TICKRATE {
+            return None;
+        }
+        self.previous.as_ref()?;
+        let elapsed = self.last_tick?.elapsed();
+        Some((elapsed.as_secs_f32() / self.tickrate.as_secs_f32()).min(1.0))
+    }
+
+    /// How much of the current fade is left before the next step is due.
+    pub fn until_next_tick(&self) -> Duration {
+        self.last_tick.map_or(Duration::ZERO, |last_tick| {
+            self.tickrate.saturating_sub(last_tick.elapsed())
+        })
+    }
+
+    /// How far the cell at `(x, y)` has faded in, if it was just born, or out, if it just
+    /// died, as [`Model::fade_progress`]. `None` for cells that didn't change or when nothing
+    /// is fading.
+    pub fn fading(&self, x: usize, y: usize) -> Option<f32> {
+        let progress = self.fade_progress()?;
+        (self.is_alive(x, y)? != self.lived_before(x, y)).then_some(progress)
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    pub fn screen(&self) -> Screen {
+        self.screen
+    }
+
+    /// The cell the magnified inset is centered on, if the inset is shown: the pinned cell, or
+    /// else the cursor.
+    pub fn inset_center(&self) -> Option<Coords> {
+        self.inset
+            .then(|| self.inset_pin.unwrap_or(self.current_coords))
+    }
+
+    pub fn is_inset_pinned(&self) -> bool {
+        self.inset_pin.is_some()
+    }
+
+    /// Whether the simulation pauses while the terminal doesn't have focus.
+    pub fn set_pause_on_focus_loss(&mut self, pause: bool) {
+        self.pause_on_focus_loss = pause;
+    }
+
+    pub fn zoom(&self) -> u16 {
+        self.zoom
+    }
+
+    /// The top-left cell drawn. See [`Model::fit_viewport`].
+    pub fn viewport(&self) -> &Coords {
+        &self.viewport
+    }
+
+    /// The terminal columns and rows each cell is drawn over at the current zoom level.
+    pub fn block_size(&self) -> (u16, u16) {
+        (self.theme.cell_width() * self.zoom, self.zoom)
+    }
+
+    /// The cell drawn at the terminal position `(column, row)`, if there is one.
+    pub fn cell_at(&self, column: u16, row: u16) -> Option<Coords> {
+        if !self.board_area.contains(column, row) {
+            return None;
+        }
+        let (block_width, block_height) = self.block_size();
+        let coords = self.viewport
+            + Coords {
+                x: ((column - self.board_area.x) / block_width) as i16,
+                y: ((row - self.board_area.y) / block_height) as i16,
+            };
+        (coords.x <= self.max_coords.x && coords.y <= self.max_coords.y).then_some(coords)
+    }
+
+    /// Where the board was last drawn.
+    pub fn board_area(&self) -> Area {
+        self.board_area
+    }
+
+    /// Records that the board is drawn in `area` and moves the viewport so that the cells
+    /// fitting in it stay on the board and, while editing, include the cursor. The comparison
+    /// board follows along.
+    pub fn fit_viewport(&mut self, area: Area) {
+        self.board_area = area;
+        let (block_width, block_height) = self.block_size();
+        let (columns, rows) = (
+            (area.width / block_width) as usize,
+            (area.height / block_height) as usize,
+        );
+        let fit = |offset: i16, cursor: i16, max: i16, visible: usize| {
+            let visible = visible.min(i16::MAX as usize) as i16;
+            let mut offset = offset;
+            if self.state == State::Editing && visible > 0 {
+                offset = offset.clamp(cursor - visible + 1, cursor);
+            }
+            offset.clamp(0, (max + 1 - visible).max(0))
+        };
+        self.viewport = Coords {
+            x: fit(
+                self.viewport.x,
+                self.current_coords.x,
+                self.max_coords.x,
+                columns,
+            ),
+            y: fit(
+                self.viewport.y,
+                self.current_coords.y,
+                self.max_coords.y,
+                rows,
+            ),
+        };
+        if let Some(comparison) = &mut self.comparison {
+            comparison.zoom = self.zoom;
+            comparison.viewport = self.viewport;
+        }
+    }
+
+    pub fn is_zen(&self) -> bool {
+        self.zen
+    }
+
+    pub fn view(&self) -> View {
+        View {
+            theme: self.theme.clone(),
+            zoom: self.zoom,
+            viewport: self.viewport,
+            cursor: self.current_coords,
+            ghost: self.ghost,
+            inset: self.inset,
+            inset_pin: self.inset_pin,
+            screen: self.screen,
+            zen: self.zen,
+        }
+    }
+
+    /// Looks at the board as in `view`. Out of range positions are pulled back onto the board,
+    /// and zen mode is still only entered while running.
+    pub fn set_view(&mut self, view: View) {
+        let max = self.max_coords;
+        let clamp = |coords: Coords| coords.within(max);
+        self.set_theme(view.theme);
+        self.zoom = view.zoom.clamp(1, MAX_ZOOM);
+        self.viewport = clamp(view.viewport);
+        self.current_coords = clamp(view.cursor);
+        self.set_ghost(view.ghost);
+        self.inset = view.inset;
+        self.inset_pin = view.inset_pin.map(clamp);
+        self.screen = view.screen;
+        self.zen = view.zen && self.state == State::Running;
+    }
+
+    pub fn comparison(&self) -> Option<&Model> {
+        self.comparison.as_deref()
+    }
+
+    pub fn rng(&self) -> &RngSource {
+        &self.rng
+    }
+
+    pub fn set_rng(&mut self, rng: RngSource) {
+        self.rng = rng;
+    }
+
+    /// Fraction of cells alive in random soups: the override if one is set, otherwise the
+    /// rule's [`Rule::soup_density`].
+    pub fn density(&self) -> f64 {
+        self.density.unwrap_or_else(|| match &self.continuous {
+            Some(continuous) => continuous.soup_density(),
+            None => self.rule.soup_density(),
+        })
+    }
+
+    pub fn set_density(&mut self, density: Option<f64>) {
+        self.density = density;
+    }
+
+    pub fn set_symmetry(&mut self, symmetry: Symmetry) {
+        self.symmetry = symmetry;
+    }
+
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    pub fn set_library(&mut self, library: Library) {
+        self.library = library;
+    }
+
+    pub fn pass_tick(&mut self) {
+        if *self.state() == State::Running {
+            if self.time_lapse > 1 {
+                // an alert may pause partway through the tick
+                self.step_n_with(self.time_lapse, |model| match model.state {
+                    State::Running => ControlFlow::Continue(()),
+                    _ => ControlFlow::Break(()),
+                });
+            } else {
+                self.step();
+            }
+        }
+    }
+
+    /// Generations stepped each tick while running, only the last of which is drawn.
+    pub fn time_lapse(&self) -> u64 {
+        self.time_lapse
+    }
+
+    /// Steps `generations` each tick from now on, between 1 and [`MAX_TIME_LAPSE`].
+    pub fn set_time_lapse(&mut self, generations: u64) {
+        self.time_lapse = generations.clamp(1, MAX_TIME_LAPSE);
+    }
+
+    /// Population thresholds watched for while stepping.
+    pub fn alerts(&self) -> &[Alert] {
+        &self.alerts
+    }
+
+    pub fn set_alerts(&mut self, alerts: Vec<Alert>) {
+        self.alerts = alerts;
+    }
+
+    /// Whether an alert has asked for the terminal bell since this was last called.
+    pub fn take_bell(&mut self) -> bool {
+        mem::take(&mut self.bell)
+    }
+
+    /// Goes off for every alert whose threshold a generation taking the population from
+    /// `before` to `after` crossed.
+    fn check_alerts(&mut self, before: usize, after: usize) {
+        let crossed: Vec<Alert> = self
+            .alerts
+            .iter()
+            .filter(|alert| alert.crossed(before, after))
+            .copied()
+            .collect();
+        for alert in crossed {
+            warn!(threshold = %alert.threshold, population = after, "population alert");
+            self.set_notice(format!(
+                "Population {} at generation {} ({after} cells)",
+                alert.threshold, self.generation
+            ));
+            match alert.action {
+                AlertAction::Notice => {}
+                AlertAction::Bell => self.bell = true,
+                AlertAction::Pause => {
+                    if self.state == State::Running {
+                        self.transition(&Message::ToggleEditing);
+                        self.zen = false;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Steps the next batch of a fast-forward, going back to the state it started from once
+    /// it's done.
+    fn fast_forward_batch(&mut self) {
+        let Some(FastForward {
+            from,
+            to,
+            until_stable,
+        }) = self.fast_forward
+        else {
+            return;
+        };
+        if !until_stable {
+            self.step_n(FAST_FORWARD_BATCH.min(to - self.generation));
+            if self.generation >= to && self.transition(&Message::StopFastForward) {
+                self.fast_forward = None;
+                self.set_notice(format!("Fast-forwarded to generation {to}"));
+            }
+            return;
+        }
+        let mut outcome = headless::ended(self);
+        if outcome.is_none() {
+            self.step_n_with(FAST_FORWARD_BATCH.min(to - self.generation), |model| {
+                outcome = headless::ended(model);
+                match outcome {
+                    Some(_) => ControlFlow::Break(()),
+                    None => ControlFlow::Continue(()),
+                }
+            });
+        }
+        if self.generation >= to {
+            outcome = Some(Outcome::MaxGenerations(self.generation));
+        }
+        if let Some(outcome) = outcome {
+            if self.transition(&Message::StopFastForward) {
+                self.fast_forward = None;
+                debug!(%outcome, generations = self.generation - from, "ran until stable");
+                self.set_notice(format!(
+                    "Ran {} generations: {outcome}",
+                    self.generation - from
+                ));
+            }
+        }
+    }
+
+    /// How far fast-forwarding has got, while it's under way.
+    pub fn fast_forward(&self) -> Option<&FastForward> {
+        self.fast_forward.as_ref()
+    }
+
+    /// Steps the board `n` generations whatever state it's in, as quickly as it can, returning
+    /// the generations stepped.
+    pub fn step_n(&mut self, n: u64) -> u64 {
+        self.step_n_with(n, |_| ControlFlow::Continue(()))
+    }
+
+    /// Steps the board up to `n` generations whatever state it's in, handing it to `after`
+    /// after each one, which can stop it early by breaking, and returning the generations
+    /// stepped. Only the last generation is kept for ghosts and fading.
+    pub fn step_n_with(&mut self, n: u64, mut after: impl FnMut(&Model) -> ControlFlow<()>) -> u64 {
+        for stepped in 1..=n {
+            self.step_generation(stepped == n);
+            // a rule mutated along the way starts the comparison board over
+            if self.comparison_stale {
+                self.sync_comparison();
+            }
+            if after(self).is_break() {
+                return stepped;
+            }
+        }
+        n
+    }
+
+    /// The cells born and the cells that died in the last generation stepped, empty before
+    /// the first and after the board is resized.
+    pub fn last_diff(&self) -> &Diff {
+        &self.diff
+    }
+
+    /// Runs `observer` after every generation stepped from now on, with the generation and
+    /// the cells it changed. Steps of a comparison board aren't included.
+    pub fn on_generation(&mut self, observer: impl FnMut(u64, &Diff) + Send + 'static) {
+        self.observers.0.push(Box::new(observer));
+    }
+
+    /// Steps the board a generation.
+    fn step(&mut self) {
+        self.step_generation(true);
+    }
+
+    /// Steps the board a generation, keeping the one before for ghosts and fading if `shown`,
+    /// as it's about to be drawn.
+    fn step_generation(&mut self, shown: bool) {
+        if self.stability.is_tracking() {
+            let hash = self.board_hash();
+            self.stability.observe(self.generation, hash);
+        }
+
+        let started = Instant::now();
+        let allocations = metrics::allocations();
+        let invert_in = self.is_inverted_at(self.generation);
+        let invert_out = self.is_inverted_at(self.generation + 1);
+
+        self.previous = (shown && (self.ghost || self.fade)).then(|| {
+            self.cells
+                .iter()
+                .map(|line| line.iter().map(|cell| cell.is_alive != invert_in).collect())
+                .collect()
+        });
+
+        let (mut population, mut births, mut deaths) = (0, 0, 0);
+        let mut cells_prev = mem::take(&mut self.scratch);
+        cells_prev.clone_from(&self.cells);
+        let replaced = self.kernel.is_some() || self.table.is_some() || self.mnca.is_some();
+        let margin = self.reach();
+        let board = (replaced || self.boundary != Boundary::Dead)
+            .then(|| self.boundary.pad(&alive_grid_of(&cells_prev), margin));
+        let neighborhood = Arc::clone(&self.neighborhood);
+        let reach = neighborhood.reach() as i16;
+        if self.continuous.is_some() {
+            (population, births, deaths) = self.step_continuous();
+        } else {
+            for (y, line) in cells_prev.iter().enumerate() {
+                for (x, cell) in line.iter().enumerate() {
+                    let here = Coords {
+                        x: x as i16,
+                        y: y as i16,
+                    };
+                    let mut active_neighbors = here
+                        .neighbors(neighborhood.as_ref(), self.max_coords)
+                        .filter(|n| cells_prev[n.y as usize][n.x as usize].is_alive)
+                        .count() as u8;
+
+                    // cells along the edges see past them, where the boundary decides what's there
+                    let on_edge = here.offset(-reach, -reach).within(self.max_coords)
+                        != here.offset(-reach, -reach)
+                        || here.offset(reach, reach).within(self.max_coords)
+                            != here.offset(reach, reach);
+                    if let (true, Some(board)) = (on_edge, &board) {
+                        active_neighbors =
+                            live_neighbors(board, y + margin, x + margin, neighborhood.as_ref());
+                    }
+
+                    // under B0 emulation the stored board may be the complement of the real one
+                    let was_alive = cell.is_alive != invert_in;
+                    if invert_in {
+                        active_neighbors = neighborhood.size() as u8 - active_neighbors;
+                    }
+
+                    let replaced = board
+                        .as_ref()
+                        .and_then(|board| self.replaced_verdict(board, y + margin, x + margin));
+                    let alive = if let Some(alive) = replaced {
+                        alive
+                    } else if was_alive {
+                        // check if living cell survives
+                        self.rule.survival_list.contains(&active_neighbors)
+                    } else {
+                        // check if cell is born
+                        self.rule.birth_list.contains(&active_neighbors)
+                    };
+                    let alive = alive != (self.rule.second_order && self.was_alive_earlier(y, x));
+
+                    if was_alive && self.rule.history {
+                        self.cells[y][x].ever_alive = true;
+                    }
+                    match (was_alive, alive) {
+                        (false, true) => births += 1,
+                        (true, false) => deaths += 1,
+                        _ => {}
+                    }
+                    if alive {
+                        population += 1;
+                    }
+
+                    if was_alive && alive {
+                        self.increment_cell_age(y, x);
+                    } else if (alive != invert_out) != cell.is_alive {
+                        self.update_cell(y, x, alive != invert_out);
+                    }
+                }
+            }
+        }
+
+        if self.rule.second_order {
+            self.earlier = alive_grid_of(&cells_prev);
+        }
+
+        self.metrics.tick = started.elapsed();
+        self.metrics.cells_evaluated = cells_prev.iter().map(Vec::len).sum();
+        self.diff.born.clear();
+        self.diff.died.clear();
+        for (y, (line, line_prev)) in self.cells.iter().zip(&cells_prev).enumerate() {
+            for (x, (cell, cell_prev)) in line.iter().zip(line_prev).enumerate() {
+                let coords = Coords {
+                    x: x as i16,
+                    y: y as i16,
+                };
+                match (cell_prev.is_alive != invert_in, cell.is_alive != invert_out) {
+                    (false, true) => self.diff.born.push(coords),
+                    (true, false) => self.diff.died.push(coords),
+                    _ => {}
+                }
+            }
+        }
+        self.scratch = cells_prev;
+        self.metrics.allocations = metrics::allocations() - allocations;
+        self.generation += 1;
+        self.run_generations += 1;
+        self.last_tick = Some(Instant::now());
+        if self.explorer.is_due(self.run_generations) {
+            self.mutate_rule();
+        }
+        trace!(
+            generation = self.generation,
+            population,
+            births,
+            deaths,
+            "step"
+        );
+        self.stats.record(Sample {
+            generation: self.generation,
+            population,
+            births,
+            deaths,
+        });
+        if !self.alerts.is_empty() {
+            self.check_alerts(population + deaths - births, population);
+        }
+        if self.checkpoints.is_due(self.generation) {
+            let cells = self
+                .cells
+                .iter()
+                .map(|line| {
+                    line.iter()
+                        .map(|cell| cell.is_alive != invert_out)
+                        .collect()
+                })
+                .collect();
+            self.checkpoints.record(Checkpoint {
+                generation: self.generation,
+                population,
+                cells,
+            });
+        }
+
+        for observer in &mut self.observers.0 {
+            observer(self.generation, &self.diff);
+        }
+
+        if let Some(comparison) = &mut self.comparison {
+            comparison.step_generation(shown);
+        }
+    }
+
+    /// Fills the cells of `area`, measured in cells, with a soup of `density` drawn from `rng`
+    /// under the board's symmetry, leaving the rest of the board as it is. The part of `area`
+    /// off the board is ignored.
+    pub fn randomize_region(&mut self, area: Area, density: f64, rng: &mut impl Rng) {
+        let board = Area::new(
+            0,
+            0,
+            self.max_coords.x as u16 + 1,
+            self.max_coords.y as u16 + 1,
+        );
+        let area = area.intersection(board);
+        if area.is_empty() {
+            return;
+        }
+        self.board_edited();
+        let inverted = self.is_inverted();
+        let cells = soup(
+            rng,
+            area.width as usize,
+            area.height as usize,
+            density.clamp(0.0, 1.0),
+            self.symmetry,
+        );
+        for (y, line) in cells.into_iter().enumerate() {
+            for (x, alive) in line.into_iter().enumerate() {
+                let (x, y) = (area.x as usize + x, area.y as usize + y);
+                self.try_set(x, y, alive != inverted);
+            }
+        }
+    }
+
+    /// Fills the cells last drawn with a soup of the board's [`density`](Model::density), or the
+    /// whole board if it hasn't been drawn yet.
+    fn randomize_view(&mut self) {
+        let (block_width, block_height) = self.block_size();
+        let view = match self.board_area.is_empty() {
+            true => Area::new(0, 0, u16::MAX, u16::MAX),
+            false => Area::new(
+                self.viewport.x as u16,
+                self.viewport.y as u16,
+                self.board_area.width / block_width,
+                self.board_area.height / block_height,
+            ),
+        };
+        let density = self.density();
+        let mut rng = mem::take(&mut self.rng);
+        self.randomize_region(view, density, &mut rng);
+        self.rng = rng;
+        self.set_notice(String::from("Filled the cells in view with a soup"));
+    }
+
+    /// Places `pattern`, oriented by `transform`, with its top-left corner at `at`, dealing
+    /// with the cells already there as `policy` says. Live cells that fall off the board or
+    /// land on live cells are counted in the report, which is the error if `policy` refused
+    /// to place the pattern.
+    pub fn place(
+        &mut self,
+        pattern: &Pattern,
+        at: Coords,
+        transform: Transform,
+        policy: CollisionPolicy,
+    ) -> Result<PlaceReport, PlaceReport> {
+        let pattern = pattern.transformed(transform);
+        let inverted = self.is_inverted();
+        let max_coords = self.max_coords;
+        let target = |x: usize, y: usize| {
+            let coords = at.offset(x as i16, y as i16);
+            (coords.within(max_coords) == coords).then_some((coords.x as usize, coords.y as usize))
+        };
+
+        let mut report = PlaceReport::default();
+        for (y, line) in pattern.cells.iter().enumerate() {
+            for (x, _) in line.iter().enumerate().filter(|(_, alive)| **alive) {
+                match target(x, y) {
+                    None => report.clipped += 1,
+                    Some((x, y)) if self.is_alive(x, y) == Some(true) => report.collisions += 1,
+                    Some(_) => {}
+                }
+            }
+        }
+        if policy == CollisionPolicy::Error && report != PlaceReport::default() {
+            return Err(report);
+        }
+
+        self.board_edited();
+        for (y, line) in pattern.cells.iter().enumerate() {
+            for (x, &alive) in line.iter().enumerate() {
+                if !alive && policy != CollisionPolicy::Overwrite {
+                    continue;
+                }
+                if let Some((x, y)) = target(x, y) {
+                    self.try_set(x, y, alive != inverted);
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// A hash of the real board, used to recognise a board seen before.
+    fn board_hash(&self) -> u64 {
+        let inverted = self.is_inverted();
+        let mut hasher = DefaultHasher::new();
+        for line in &self.cells {
+            for cell in line {
+                (cell.is_alive != inverted).hash(&mut hasher);
+            }
+        }
+        // a Reversible rule's next board depends on the earlier one too
+        if self.rule.second_order {
+            self.earlier.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Restarts stabilization tracking after the board was changed by hand, which also voids
+    /// any expectation that came with the preset.
+    fn board_edited(&mut self) {
+        self.stability.reset(self.generation);
+        self.expected_stabilization = None;
+        self.comparison_stale = true;
+        self.previous = None;
+    }
+
+    /// Restarts the comparison board from a copy of this one.
+    fn sync_comparison(&mut self) {
+        self.comparison_stale = false;
+        let Some(comparison) = &mut self.comparison else {
+            return;
+        };
+
+        comparison.generation = self.generation;
+        comparison.theme = self.theme.clone();
+        // copy the real board, since the two rules may disagree on B0 phase inversion
+        let inverted = self.rule.is_inverted(self.generation);
+        let comparison_inverted = comparison.is_inverted();
+        comparison.cells = self
+            .cells
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|cell| Cell {
+                        is_alive: (cell.is_alive != inverted) != comparison_inverted,
+                        ..cell.clone()
+                    })
+                    .collect()
+            })
+            .collect();
+        comparison.stability.reset(self.generation);
+        comparison.previous = None;
+    }
+
+    fn set_cell(&mut self, Coords { x, y }: Coords, alive: bool) {
+        self.board_edited();
+        let inverted = self.is_inverted();
+        if let Some(cell) = self.cell_mut(x as usize, y as usize) {
+            cell.is_alive = alive != inverted;
+        }
+    }
+
+    fn toggle_current_cell(&mut self) {
+        self.board_edited();
+        let Coords { x, y } = *self.current_coords();
+        if let Some(cell) = self.cell_mut(x as usize, y as usize) {
+            cell.is_alive = !cell.is_alive;
+        }
+    }
+
+    fn open_prompt(&mut self) {
+        let Some((field, error)) = self
+            .config_errors
+            .iter()
+            .find_map(|error| Some((error.field()?, error)))
+        else {
+            return;
+        };
+
+        self.prompt = Some(Prompt {
+            field,
+            buffer: String::from(error.input()),
+        });
+    }
+
+    fn open_recent(&mut self) {
+        let labels = self.history.entries().iter().map(Recent::label).collect();
+        self.picker = Some(Picker::new(labels));
+        self.picker_kind = PickerKind::Recent;
+    }
+
+    fn open_workspace(&mut self) {
+        let names = self
+            .workspace
+            .boards()
+            .iter()
+            .map(|board| String::from(board.name()))
+            .collect();
+        self.picker = Some(Picker::new(names));
+        self.picker_kind = PickerKind::Boards;
+    }
+
+    fn open_checkpoints(&mut self) {
+        let labels = self
+            .checkpoints
+            .newest_first()
+            .map(Checkpoint::label)
+            .collect();
+        self.picker = Some(Picker::new(labels));
+        self.picker_kind = PickerKind::Checkpoints;
+    }
+
+    fn open_picker(&mut self) {
+        self.picker_kind = PickerKind::Patterns;
+        let names = Preset::ALL
+            .iter()
+            .map(|preset| String::from(preset.name()))
+            .chain(self.library.names().map(String::from))
+            .collect();
+        self.picker = Some(Picker::new(names));
+    }
+
+    /// Loads the picked pattern with its top-left corner at the cursor, or starts the picked
+    /// recent run over on an empty board.
+    fn submit_picker(&mut self) {
+        let Some(picker) = self.picker.take() else {
+            return;
+        };
+        let Some(selected) = picker.selected() else {
+            return;
+        };
+
+        match self.picker_kind {
+            PickerKind::Patterns => {
+                if let Some(preset) = self.library.resolve(selected) {
+                    let origin = self.current_coords;
+                    self.load_preset_at(preset, origin, Transform::default());
+                    self.remember();
+                }
+            }
+            PickerKind::Recent => {
+                let Some(recent) = self.recent(selected).cloned() else {
+                    return;
+                };
+                self.end_run();
+                if let Ok(rule) = recent.rule.parse() {
+                    self.rule = rule;
+                }
+                if let Some(seed) = recent.seed {
+                    self.rng = RngSource::seeded(seed);
+                }
+                self.clear_board();
+                if let Some(preset) = self.library.resolve(&recent.preset) {
+                    self.load_preset(preset);
+                }
+                self.remember();
+            }
+            PickerKind::Checkpoints => {
+                let Some(checkpoint) = self.checkpoint(selected).cloned() else {
+                    return;
+                };
+                let pattern = Pattern {
+                    cells: checkpoint.cells,
+                    rule: None,
+                    metadata: Default::default(),
+                };
+                self.restore(&pattern, checkpoint.generation);
+                self.checkpoints.drop_after(checkpoint.generation);
+            }
+            PickerKind::Boards => {
+                let Some(board) = self.workspace.board(selected).cloned() else {
+                    return;
+                };
+                self.end_run();
+                if let Some(rule) = board
+                    .pattern()
+                    .and_then(|pattern| pattern.rule.as_deref()?.parse().ok())
+                {
+                    self.rule = rule;
+                }
+                self.clear_board();
+                self.load_preset(board);
+            }
+        }
+    }
+
+    /// Ends the current run before a new pattern replaces it. A journal that can't be written
+    /// is given up on.
+    fn end_run(&mut self) {
+        if let Err(error) = self.log_run() {
+            self.journal = None;
+            self.report(ErrorReport::new("The journal stopped", &error));
+        }
+    }
+
+    /// Steps the continuous automaton and brings `cells` in line with the new field, returning
+    /// the population, births and deaths.
+    fn step_continuous(&mut self) -> (usize, usize, usize) {
+        self.sync_field();
+        let Some(continuous) = &self.continuous else {
+            return (0, 0, 0);
+        };
+        self.field = continuous.step(&self.field);
+
+        let (mut population, mut births, mut deaths) = (0, 0, 0);
+        for (cell, state) in self
+            .cells
+            .iter_mut()
+            .flatten()
+            .zip(self.field.iter().flatten())
+        {
+            let alive = *state >= continuous::ALIVE;
+            match (cell.is_alive, alive) {
+                (false, true) => births += 1,
+                (true, false) => deaths += 1,
+                _ => {}
+            }
+            if alive {
+                population += 1;
+            }
+            if cell.is_alive && alive {
+                cell.age += 1;
+            } else {
+                *cell = Cell::new(alive);
+            }
+        }
+        (population, births, deaths)
+    }
+
+    /// Brings the field in line with cells that were edited since it was last stepped: cells
+    /// made alive are set to full and cells killed to empty, the rest keeping their state.
+    fn sync_field(&mut self) {
+        let fits = self.field.len() == self.cells.len()
+            && self
+                .field
+                .iter()
+                .zip(&self.cells)
+                .all(|(a, b)| a.len() == b.len());
+        if !fits {
+            self.field = self
+                .cells
+                .iter()
+                .map(|line| line.iter().map(|_| 0.0).collect())
+                .collect();
+        }
+        for (cell, state) in self
+            .cells
+            .iter()
+            .flatten()
+            .zip(self.field.iter_mut().flatten())
+        {
+            if cell.is_alive != (*state >= continuous::ALIVE) {
+                *state = f32::from(u8::from(cell.is_alive));
+            }
+        }
+    }
+
+    /// Whether the cell at `y`, `x` was alive the generation before this one, as far as a
+    /// Reversible rule is concerned.
+    fn was_alive_earlier(&self, y: usize, x: usize) -> bool {
+        self.earlier
+            .get(y)
+            .and_then(|line| line.get(x))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// How far the neighborhood stepping the board reaches from each cell.
+    fn reach(&self) -> usize {
+        match (&self.mnca, &self.table, &self.kernel) {
+            (Some(mnca), ..) => mnca.reach(),
+            (None, None, Some(kernel)) => kernel.reach(),
+            _ => self.neighborhood.reach(),
+        }
+    }
+
+    /// The verdict of the MNCA, table or kernel standing in for the rule's neighbor counts on
+    /// the cell at `y`, `x` of `board`, if one is loaded.
+    fn replaced_verdict(&self, board: &[Vec<bool>], y: usize, x: usize) -> Option<bool> {
+        match (&self.mnca, &self.table, &self.kernel) {
+            (Some(mnca), ..) => Some(mnca.verdict(board, y, x)),
+            (None, Some(table), _) => Some(table.verdict(board, y, x)),
+            (None, None, Some(kernel)) => Some(kernel.verdict(board, y, x)),
+            (None, None, None) => None,
+        }
+    }
+
+    /// Swaps the rule for a mutation of it, remembering the old one in the explorer's lineage.
+    fn mutate_rule(&mut self) {
+        if self.continuous.is_some()
+            || self.kernel.is_some()
+            || self.table.is_some()
+            || self.mnca.is_some()
+        {
+            self.set_notice(String::from("Only rulestring rules can be mutated"));
+            return;
+        }
+        let rule = self.explorer.mutate(&self.rule, &mut self.rng);
+        debug!(
+            from = self.rulestring(),
+            to = rule.to_string(),
+            "mutated rule"
+        );
+        self.rule = rule;
+        self.board_edited();
+        self.learn(Lesson::Mutate);
+    }
+
+    /// Steps a Reversible rule's board back a generation. Since each generation is the rule's
+    /// verdict on the one before XORed with the one before that, the generation before the
+    /// earlier one is the verdict on the earlier one XORed with the current one.
+    fn step_back(&mut self) {
+        if !self.rule.second_order {
+            self.set_notice(String::from("Only Reversible rules can step back"));
+            return;
+        }
+        if self.generation == 0 {
+            self.set_notice(String::from("Already at generation 0"));
+            return;
+        }
+
+        let current = alive_grid_of(&self.cells);
+        let earlier: Vec<Vec<bool>> = current
+            .iter()
+            .enumerate()
+            .map(|(y, line)| {
+                (0..line.len())
+                    .map(|x| self.was_alive_earlier(y, x))
+                    .collect()
+            })
+            .collect();
+        let mut before = earlier.clone();
+        let margin = self.reach();
+        let padded = self.boundary.pad(&earlier, margin);
+        for (y, line) in earlier.iter().enumerate() {
+            for (x, &alive) in line.iter().enumerate() {
+                let (py, px) = (y + margin, x + margin);
+                let verdict = self.replaced_verdict(&padded, py, px).unwrap_or_else(|| {
+                    self.rule
+                        .verdict(&padded, py, px, self.neighborhood.as_ref())
+                });
+                before[y][x] = verdict != current[y][x];
+                if alive != current[y][x] {
+                    self.update_cell(y, x, alive);
+                } else if let Some(cell) = self.cell_mut(x, y) {
+                    cell.age = cell.age.saturating_sub(1);
+                }
+            }
+        }
+
+        self.earlier = before;
+        self.generation -= 1;
+        self.board_edited();
+    }
+
+    /// Kills every cell and starts counting generations from zero again.
+    fn clear_board(&mut self) {
+        self.generation = 0;
+        self.earlier.clear();
+        for cell in self.cells.iter_mut().flatten() {
+            *cell = Cell::new(false);
+        }
+        self.board_edited();
+    }
+
+    fn submit_prompt(&mut self) {
+        let Some(prompt) = self.prompt.take() else {
+            return;
+        };
+
+        let error = match prompt.field {
+            PromptField::Rulestring => match prompt.buffer.parse::<Rule>() {
+                Ok(rule) => {
+                    self.rule = rule;
+                    self.board_edited();
+                    self.remember();
+                    None
+                }
+                Err(reason) => Some(ConfigError::Rulestring {
+                    input: prompt.buffer,
+                    reason,
+                }),
+            },
+            PromptField::Preset => match self.library.resolve(&prompt.buffer) {
+                Some(preset) => {
+                    self.load_preset(preset);
+                    self.remember();
+                    None
+                }
+                None => Some(ConfigError::Preset {
+                    input: prompt.buffer,
+                }),
+            },
+            PromptField::Theme => match parse_theme(&prompt.buffer) {
+                Ok(theme) => {
+                    // a theme name only chooses glyphs, palette and whether there's color at all;
+                    // keep the other settings
+                    self.theme = Theme {
+                        alive_glyph: theme.alive_glyph,
+                        dead_glyph: theme.dead_glyph,
+                        palette: theme.palette,
+                        monochrome: theme.monochrome,
+                        ascii: theme.ascii,
+                        ..self.theme.clone()
+                    };
+                    None
+                }
+                Err(error) => Some(error),
+            },
+            PromptField::Size => {
+                match parse_board_size(&prompt.buffer) {
+                    Ok((width, height, anchor)) => self.resize(width, height, anchor),
+                    Err(reason) => self.set_notice(reason),
+                }
+                None
+            }
+        };
+
+        self.config_errors
+            .retain(|e| e.field() != Some(prompt.field));
+        if let Some(error) = error {
+            self.config_errors.insert(0, error);
+        }
+    }
+
+    fn move_cursor_in_direction(&mut self, dir: Direction, cells: i16) {
+        match dir {
+            Direction::Up => self.move_cursor(0, -cells),
+            Direction::Down => self.move_cursor(0, cells),
+            Direction::Left => self.move_cursor(-cells, 0),
+            Direction::Right => self.move_cursor(cells, 0),
+        }
+    }
+
+    fn move_cursor(&mut self, x_delta: i16, y_delta: i16) {
+        if self.state == State::Editing {
+            self.current_coords = self
+                .current_coords
+                .offset(x_delta, y_delta)
+                .within(self.max_coords);
+        }
+    }
+}
+
+impl State {
+    /// Returns the state `msg` leads to from `self`, or `None` if the message doesn't cause a
+    /// transition here. Messages that only act within a state (moving the cursor, ticking,
+    /// editing prompt text) never cause one.
+    ///
+    /// | From                   | Message                          | To                      |
+    /// |------------------------|----------------------------------|-------------------------|
+    /// | `Editing`              | `ToggleEditing`                  | `Running`               |
+    /// | `Running`              | `ToggleEditing`                  | `Editing`               |
+    /// | `Editing`, `Running`   | `OpenPrompt`, `OpenResize`       | `Prompting`             |
+    /// | `Prompting`            | `PromptSubmit`, `PromptCancel`   | the state it came from  |
+    /// | `Editing`, `Running`   | `OpenPicker`, `OpenRecent`,      | `Picking`               |
+    /// |                        | `OpenCheckpoints`,               |                         |
+    /// |                        | `OpenWorkspace`                  |                         |
+    /// | `Picking`              | `PickerSubmit`, `PickerCancel`   | the state it came from  |
+    /// | anything but `Done`    | `Quit`                           | `Done`                  |
+    pub fn transition(&self, msg: &Message) -> Option<State> {
+        match (self, msg) {
+            (State::Done, _) => None,
+            (_, Message::Quit) => Some(State::Done),
+            (State::Editing, Message::ToggleEditing) => Some(State::Running),
+            (State::Running, Message::ToggleEditing) => Some(State::Editing),
+            (State::Editing | State::Running, Message::OpenPrompt | Message::OpenResize) => {
+                Some(State::Prompting {
+                    resume: Box::new(self.clone()),
+                })
+            }
+            (State::Prompting { resume }, Message::PromptSubmit | Message::PromptCancel) => {
+                Some((**resume).clone())
+            }
+            (
+                State::Editing | State::Running,
+                Message::OpenPicker
+                | Message::OpenRecent
+                | Message::OpenCheckpoints
+                | Message::OpenWorkspace,
+            ) => Some(State::Picking {
+                resume: Box::new(self.clone()),
+            }),
+            (State::Picking { resume }, Message::PickerSubmit | Message::PickerCancel) => {
+                Some((**resume).clone())
+            }
+            (State::Editing | State::Running, Message::FastForward) => {
+                Some(State::FastForwarding {
+                    resume: Box::new(self.clone()),
+                })
+            }
+            // pauses once the board has settled, to look at how it ended
+            (State::Editing | State::Running, Message::RunUntilStable) => {
+                Some(State::FastForwarding {
+                    resume: Box::new(State::Editing),
+                })
+            }
+            (State::FastForwarding { resume }, Message::StopFastForward) => {
+                Some((**resume).clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Preset {
+    /// Every preset, in the order they're listed to users.
+    pub const ALL: [Preset; 16] = [
+        Preset::Blinker,
+        Preset::Mold,
+        Preset::Glider,
+        Preset::Lwss,
+        Preset::Mwss,
+        Preset::Hwss,
+        Preset::GosperGliderGun,
+        Preset::Pulsar,
+        Preset::Pentadecathlon,
+        Preset::RPentomino,
+        Preset::Acorn,
+        Preset::Diehard,
+        Preset::Bunnies,
+        Preset::Random,
+        Preset::HorizontalLine,
+        Preset::Empty,
+    ];
+
+    /// Looks up a built-in preset by name, ignoring case, spaces, dashes and underscores.
+    /// Returns `None` for names that aren't recognised so the caller can decide on (and report)
+    /// a fallback. Use [`Library::resolve`] to include the user's own patterns.
+    pub fn parse(preset_string: &str) -> Option<Preset> {
+        match &normalize_name(preset_string)[..] {
+            "none" => Some(Preset::Empty),
+            "gosper" => Some(Preset::GosperGliderGun),
+            name => Preset::ALL
+                .into_iter()
+                .find(|preset| normalize_name(preset.name()) == name),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Preset::Blinker => "Blinker",
+            Preset::Mold => "Mold",
+            Preset::Glider => "Glider",
+            Preset::Lwss => "LWSS",
+            Preset::Mwss => "MWSS",
+            Preset::Hwss => "HWSS",
+            Preset::GosperGliderGun => "GosperGliderGun",
+            Preset::Pulsar => "Pulsar",
+            Preset::Pentadecathlon => "Pentadecathlon",
+            Preset::RPentomino => "RPentomino",
+            Preset::Acorn => "Acorn",
+            Preset::Diehard => "Diehard",
+            Preset::Bunnies => "Bunnies",
+            Preset::Random => "Random",
+            Preset::HorizontalLine => "HorizontalLine",
+            Preset::Empty => "Empty",
+            Preset::User { name, .. } => name,
+        }
+    }
+
+    /// The embedded RLE source for presets that are fixed patterns, as opposed to those
+    /// generated to fit the board.
+    pub fn rle(&self) -> Option<&'static str> {
+        let rle = match self {
+            Preset::Blinker => {
+                "#N Blinker\n#O John Conway\n#C The smallest and most common oscillator, period 2.\n\
+                 #C Found in 1970.\nx = 3, y = 3\n$3o$!"
+            }
+            Preset::Mold => "#N Mold\n#C A period 4 oscillator.\nx = 6, y = 6\n\
+                 3b2o$2bo2bo$o2bobo$4bo$ob2o$bo!",
+            Preset::Glider => {
+                "#N Glider\n#O Richard K. Guy\n#C The smallest spaceship, travelling diagonally at c/4.\n\
+                 #C Found in 1970.\nx = 3, y = 3\nbo$2bo$3o!"
+            }
+            Preset::Lwss => {
+                "#N Lightweight spaceship\n#O John Conway\n#C An orthogonal c/2 spaceship.\n\
+                 #C Found in 1970.\nx = 5, y = 4\nbo2bo$o$o3bo$4o!"
+            }
+            Preset::Mwss => {
+                "#N Middleweight spaceship\n#O John Conway\n#C An orthogonal c/2 spaceship.\n\
+                 #C Found in 1970.\nx = 6, y = 5\n3bo$bo3bo$o$o4bo$5o!"
+            }
+            Preset::Hwss => {
+                "#N Heavyweight spaceship\n#O John Conway\n#C An orthogonal c/2 spaceship.\n\
+                 #C Found in 1970.\nx = 7, y = 5\n3b2o$bo4bo$o$o5bo$6o!"
+            }
+            Preset::GosperGliderGun => {
+                "#N Gosper glider gun\n#O Bill Gosper\n\
+                 #C The first known gun, emitting a glider every 30 generations.\n\
+                 #C Found in 1970.\nx = 36, y = 9\n\
+                 24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$\
+                 2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!"
+            }
+            Preset::Pulsar => {
+                "#N Pulsar\n#O John Conway\n#C The most common period 3 oscillator.\n\
+                 #C Found in 1970.\nx = 13, y = 13\n\
+                 2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$\
+                 2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"
+            }
+            Preset::Pentadecathlon => {
+                "#N Pentadecathlon\n#O John Conway\n#C A period 15 oscillator.\n\
+                 #C Found in 1970.\nx = 10, y = 3\n2bo4bo$2ob4ob2o$2bo4bo!"
+            }
+            Preset::RPentomino => {
+                "#N R-pentomino\n#O John Conway\n\
+                 #C A methuselah that takes 1103 generations to stabilize.\n\
+                 #C Studied in 1970.\nx = 3, y = 3\nb2o$2o$bo!"
+            }
+            Preset::Acorn => {
+                "#N Acorn\n#O Charles Corderman\n\
+                 #C A methuselah that takes 5206 generations to stabilize.\n\
+                 #C Found in 1971.\nx = 7, y = 3\nbo$3bo$2o2b3o!"
+            }
+            Preset::Diehard => {
+                "#N Diehard\n#C A methuselah that dies out completely after 130 generations.\n\
+                 x = 8, y = 3\n6bo$2o$bo3b3o!"
+            }
+            Preset::Bunnies => {
+                "#N Bunnies\n#C A methuselah that takes 17332 generations to stabilize.\n\
+                 x = 8, y = 4\no5bo$2bo3bo$2bo2bobo$bobo!"
+            }
+            Preset::Random | Preset::HorizontalLine | Preset::Empty | Preset::User { .. } => {
+                return None
+            }
+        };
+        Some(rle)
+    }
+
+    /// The generation at which the methuselahs among the presets settle into still lifes and
+    /// oscillators (or die out) on an unbounded Life board. Escaping gliders are ignored, so on a
+    /// bounded board they stabilize later, once those gliders have crashed into the edges.
+    pub fn expected_stabilization(&self) -> Option<u64> {
+        match self {
+            Preset::RPentomino => Some(1103),
+            Preset::Acorn => Some(5206),
+            Preset::Diehard => Some(130),
+            Preset::Bunnies => Some(17332),
+            _ => None,
+        }
+    }
+
+    pub fn pattern(&self) -> Option<Pattern> {
+        if let Preset::User { pattern, .. } = self {
+            return Some(pattern.clone());
+        }
+
+        self.rle()
+            .map(|rle| Pattern::from_rle(rle).expect("built-in preset RLE is valid"))
+    }
+}
+
+impl Placement {
+    /// How `pattern` is placed: at the position in its extended RLE, if the placement is left
+    /// at the default top-left and the pattern fits there as it is, or else as `self` says.
+    pub fn for_pattern(
+        self,
+        pattern: &Pattern,
+        transform: Transform,
+        board_size: (usize, usize),
+    ) -> Placement {
+        match (self, transform == Transform::default(), positioned(pattern)) {
+            (Placement::TopLeft, true, Some(at))
+                if Placement::At(at)
+                    .check_fit(pattern.size(), board_size)
+                    .is_ok() =>
+            {
+                Placement::At(at)
+            }
+            _ => self,
+        }
+    }
+
+    /// Where the top-left corner of a `pattern_size` pattern goes on a `board_size` board.
+    pub fn origin(&self, pattern_size: (usize, usize), board_size: (usize, usize)) -> Coords {
+        match self {
+            Placement::TopLeft => Coords { x: 0, y: 0 },
+            Placement::At(at) => *at,
+            Placement::Center => Coords {
+                x: (board_size.0 as i16 - pattern_size.0 as i16) / 2,
+                y: (board_size.1 as i16 - pattern_size.1 as i16) / 2,
+            },
+        }
+    }
+
+    /// Checks that a `pattern_size` pattern placed this way lies entirely on the board,
+    /// describing the problem if it doesn't.
+    pub fn check_fit(
+        &self,
+        pattern_size: (usize, usize),
+        board_size: (usize, usize),
+    ) -> Result<(), String> {
+        let origin = self.origin(pattern_size, board_size);
+        let (width, height) = pattern_size;
+        let (board_width, board_height) = board_size;
+        if origin.x < 0
+            || origin.y < 0
+            || origin.x as usize + width > board_width
+            || origin.y as usize + height > board_height
+        {
+            return Err(format!(
+                "({width}x{height}) doesn't fit at {},{} on a {board_width}x{board_height} board",
+                origin.x, origin.y
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Parses a `NAME@X,Y[,ROT]` placement.
+pub fn parse_place(input: &str) -> Result<PlaceSpec, String> {
+    let (name, position) = input
+        .split_once('@')
+        .ok_or_else(|| format!("expected NAME@X,Y[,ROT], got \"{input}\""))?;
+    let (coords, rotation) = match position.splitn(3, ',').collect::<Vec<_>>()[..] {
+        [x, y, rotation] => (format!("{x},{y}"), Rotation::parse(rotation)?),
+        _ => (String::from(position), Rotation::None),
+    };
+
+    Ok(PlaceSpec {
+        name: String::from(name.trim()),
+        at: parse_coords(&coords)?,
+        rotation,
+    })
+}
+
+impl fmt::Display for PlaceSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{},{}", self.name, self.at.x, self.at.y)?;
+        match self.rotation {
+            Rotation::None => Ok(()),
+            Rotation::Quarter => write!(f, ",90"),
+            Rotation::Half => write!(f, ",180"),
+            Rotation::ThreeQuarters => write!(f, ",270"),
+        }
+    }
+}
+
+/// Parses a soup density between 0 and 1.
+pub fn parse_density(input: &str) -> Result<f64, String> {
+    match input.trim().parse::<f64>() {
+        Ok(density) if (0.0..=1.0).contains(&density) => Ok(density),
+        _ => Err(format!("density must be between 0 and 1, got \"{input}\"")),
+    }
+}
+
+/// The position in `pattern`'s extended RLE, if it has one the board's coordinates can hold.
+fn positioned(pattern: &Pattern) -> Option<Coords> {
+    let (x, y) = pattern.metadata.position?;
+    Some(Coords {
+        x: x.try_into().ok()?,
+        y: y.try_into().ok()?,
+    })
+}
+
+/// Parses an `X,Y` coordinate pair.
+pub fn parse_coords(input: &str) -> Result<Coords, String> {
+    let (x, y) = input
+        .split_once(',')
+        .ok_or_else(|| format!("expected X,Y, got \"{input}\""))?;
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<i16>()
+            .map_err(|_| format!("\"{}\" is not a valid coordinate", value.trim()))
+    };
+    Ok(Coords {
+        x: parse(x)?,
+        y: parse(y)?,
+    })
+}
+
+/// Strips `suffix` from the end of `input`, ignoring case, returning what's left and whether
+/// it was there.
+fn strip_suffix_ignore_case<'a>(input: &'a str, suffix: &str) -> (&'a str, bool) {
+    let start = input.len().saturating_sub(suffix.len());
+    if input.is_char_boundary(start) && input[start..].eq_ignore_ascii_case(suffix) {
+        (&input[..start], true)
+    } else {
+        (input, false)
+    }
+}
+
+/// How many of the cells of `neighborhood` around `y`, `x` of `board` are alive. Cells off the
+/// board count as dead.
+fn live_neighbors(board: &[Vec<bool>], y: usize, x: usize, neighborhood: &dyn Neighborhood) -> u8 {
+    let bounds = Coords {
+        x: board.first().map_or(0, Vec::len) as i16 - 1,
+        y: board.len() as i16 - 1,
+    };
+    let here = Coords {
+        x: x as i16,
+        y: y as i16,
+    };
+    here.neighbors(neighborhood, bounds)
+        .filter(|n| board[n.y as usize][n.x as usize])
+        .count() as u8
+}
+
+/// `grid` moved `offset` cells right and down onto a `width` by `height` grid, cropping what
+/// falls off and filling in the rest with `fill`.
+fn reframe<T>(
+    grid: Vec<Vec<T>>,
+    (width, height): (usize, usize),
+    (dx, dy): (isize, isize),
+    fill: impl Fn() -> T,
+) -> Vec<Vec<T>> {
+    let mut rows: Vec<_> = grid.into_iter().map(Some).collect();
+    (0..height as isize)
+        .map(|y| {
+            let mut row: Vec<Option<T>> = usize::try_from(y - dy)
+                .ok()
+                .and_then(|y| rows.get_mut(y)?.take())
+                .map_or_else(Vec::new, |row| row.into_iter().map(Some).collect());
+            (0..width as isize)
+                .map(|x| {
+                    usize::try_from(x - dx)
+                        .ok()
+                        .and_then(|x| row.get_mut(x)?.take())
+                        .unwrap_or_else(&fill)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Which cells of `cells` are alive, as stored.
+fn alive_grid_of(cells: &[Vec<Cell>]) -> Vec<Vec<bool>> {
+    cells
+        .iter()
+        .map(|line| line.iter().map(|cell| cell.is_alive).collect())
+        .collect()
+}
+
+impl Rule {
+    /// Whether the cell at `y`, `x` of `board` is alive in the next generation under this rule,
+    /// counting `neighborhood`, ignoring B0 phase inversion and the second-order XOR.
+    fn verdict(
+        &self,
+        board: &[Vec<bool>],
+        y: usize,
+        x: usize,
+        neighborhood: &dyn Neighborhood,
+    ) -> bool {
+        let neighbors = live_neighbors(board, y, x, neighborhood);
+        if board[y][x] {
+            self.survival_list.contains(&neighbors)
+        } else {
+            self.birth_list.contains(&neighbors)
+        }
+    }
+
+    /// Rules with B0 turn the empty background on every other generation (or, with S8, on
+    /// every generation after the first). Rather than store a board full of live cells, the
+    /// model stores the complement of the real board in those phases, so the background stays
+    /// dead and the board's edges behave as they would in an infinite universe. This is the
+    /// same alternating-phase emulation Golly uses.
+    pub fn is_inverted(&self, generation: u64) -> bool {
+        if !self.birth_list.contains(&0) {
+            return false;
+        }
+
+        if self.survival_list.contains(&8) {
+            generation >= 1
+        } else {
+            generation % 2 == 1
+        }
+    }
+
+    /// A random soup density that tends to give this rule an interesting start. Known rules
+    /// use tuned values; explosive rules (births on two or fewer neighbors) get sparse soups.
+    pub fn soup_density(&self) -> f64 {
+        let mut birth = self.birth_list.clone();
+        let mut survival = self.survival_list.clone();
+        birth.sort_unstable();
+        birth.dedup();
+        survival.sort_unstable();
+        survival.dedup();
+
+        match (&birth[..], &survival[..]) {
+            // Seeds
+            ([2], []) => 0.08,
+            // Day & Night
+            ([3, 6, 7, 8], [3, 4, 6, 7, 8]) => 0.5,
+            // Replicator
+            ([1, 3, 5, 7], [1, 3, 5, 7]) => 0.05,
+            // Maze
+            ([3], [1, 2, 3, 4, 5]) => 0.15,
+            // Life without Death
+            ([3], [0, 1, 2, 3, 4, 5, 6, 7, 8]) => 0.1,
+            // 2x2
+            ([3, 6], [1, 2, 5]) => 0.25,
+            (birth, _) if birth.first().is_some_and(|b| *b <= 2) => 0.1,
+            _ => 0.3,
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule {
+            birth_list: vec![3],
+            survival_list: vec![2, 3],
+            history: false,
+            second_order: false,
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses a rulestring, `B3/S23` or the older survival-first `23/3`, optionally followed
+    /// by `Reversible`, `History` or both in that order, returning a description of the
+    /// problem on failure. Digits may come in any order, and more than once.
+    fn from_str(rulestring: &str) -> Result<Rule, String> {
+        let (rulestring, history) = strip_suffix_ignore_case(rulestring.trim(), "History");
+        let (rulestring, second_order) = strip_suffix_ignore_case(rulestring, "Reversible");
+
+        let digit = |ch: char| match ch.to_digit(10) {
+            Some(digit) if digit > 8 => {
+                Err(format!("{digit} neighbors is geometrically impossible"))
+            }
+            Some(digit) => Ok(digit as u8),
+            None => Err(format!("unexpected character '{ch}'")),
+        };
+        if rulestring.is_empty() {
+            return Err(String::from("rulestring is empty"));
+        }
+        let mut birth_list = vec![];
+        let mut survival_list = vec![];
+        let all_digits = |part: &str| part.chars().all(|ch| ch.is_ascii_digit());
+        match rulestring.split_once('/') {
+            // only digits either side of the slash is survival-first notation
+            Some((survival, birth)) if all_digits(survival) && all_digits(birth) => {
+                for ch in survival.chars() {
+                    survival_list.push(digit(ch)?);
+                }
+                for ch in birth.chars() {
+                    birth_list.push(digit(ch)?);
+                }
+            }
+            _ => {
+                let mut list = None;
+                for ch in rulestring.chars() {
+                    match ch.to_ascii_uppercase() {
+                        'B' => list = Some(&mut birth_list),
+                        'S' => list = Some(&mut survival_list),
+                        ch if ch.is_alphabetic() => {
+                            return Err(format!("unexpected character '{ch}'"))
+                        }
+                        ch => match &mut list {
+                            None => return Err(String::from("rulestring must start with B or S")),
+                            Some(list) if ch.is_ascii_digit() => list.push(digit(ch)?),
+                            Some(_) if ch == '/' => {}
+                            Some(_) => return Err(format!("unexpected character '{ch}'")),
+                        },
+                    }
+                }
+            }
+        }
+        for list in [&mut birth_list, &mut survival_list] {
+            list.sort_unstable();
+            list.dedup();
+        }
+
+        // the phase inversion that emulates B0 would need the earlier generation inverted too
+        if second_order && birth_list.contains(&0) {
+            return Err(String::from("B0 rules can't be Reversible"));
+        }
+
+        Ok(Rule {
+            birth_list,
+            survival_list,
+            history,
+            second_order,
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    // the canonical form: B/S notation, with each digit once and in order
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |list: &[u8]| -> String {
+            (0..=8u8)
+                .filter(|digit| list.contains(digit))
+                .map(|digit| char::from(b'0' + digit))
+                .collect()
+        };
+        write!(
+            f,
+            "B{}/S{}",
+            digits(&self.birth_list),
+            digits(&self.survival_list)
+        )?;
+        if self.second_order {
+            f.write_str("Reversible")?;
+        }
+        if self.history {
+            f.write_str("History")?;
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    pub fn build(
+        preset_string: &str,
+        rulestring: &str,
+        tickrate: Duration,
+        library: Library,
+    ) -> Config {
+        let mut errors = vec![];
+
+        let rule = rulestring.parse::<Rule>().unwrap_or_else(|reason| {
+            errors.push(ConfigError::Rulestring {
+                input: String::from(rulestring),
+                reason,
+            });
+            Rule::default()
+        });
+
+        let preset = library.resolve(preset_string).unwrap_or_else(|| {
+            errors.push(ConfigError::Preset {
+                input: String::from(preset_string),
+            });
+            Preset::Empty
+        });
+
+        Config {
+            preset,
+            rule,
+            tickrate,
+            theme: Theme::default(),
+            layout: LayoutSpec::default(),
+            seed: None,
+            placement: Placement::TopLeft,
+            transform: Transform::default(),
+            places: vec![],
+            density: None,
+            symmetry: Symmetry::default(),
+            compare: None,
+            continuous: None,
+            kernel: None,
+            table: None,
+            mnca: None,
+            boundary: Boundary::default(),
+            neighborhood: None,
+            #[cfg(feature = "tui")]
+            keymap: Keymap::default(),
+            frame_interval: None,
+            time_lapse: 1,
+            run_until_stable: false,
+            alerts: vec![],
+            fade: false,
+            export_html: false,
+            overlay: None,
+            screensaver: false,
+            playlist: vec![],
+            playlist_turn: Duration::from_secs(60),
+            pause_on_focus_loss: true,
+            accessible: false,
+            autosave_on_exit: false,
+            journal: false,
+            checkpoint_interval: checkpoints::DEFAULT_INTERVAL,
+            mutate_every: None,
+            workspace: Workspace::default(),
+            library,
+            history: History::default(),
+            errors,
+        }
+    }
+
+    /// Builds the configuration from command line arguments, filling in defaults for anything
+    /// that wasn't given.
+    #[cfg(feature = "tui")]
+    pub fn from_cli(cli: &Cli) -> Config {
+        let mut library_errors = vec![];
+        let presets_dir = cli
+            .presets_dir
+            .clone()
+            .or_else(|| default_presets_dir().filter(|dir| dir.is_dir()));
+        let library = match presets_dir {
+            Some(dir) => {
+                let (library, errors) = Library::load_dir(&dir);
+                library_errors = errors;
+                library
+            }
+            None => Library::default(),
+        };
+
+        let mut history_errors = vec![];
+        let history = match History::path() {
+            Some(path) => History::load(&path).unwrap_or_else(|error| {
+                history_errors.push(ConfigError::History {
+                    path: path.display().to_string(),
+                    reason: error.to_string(),
+                });
+                History::default()
+            }),
+            None => History::default(),
+        };
+        let resumed = if cli.resume_last {
+            if history.latest().is_none() && history_errors.is_empty() {
+                history_errors.push(ConfigError::History {
+                    path: History::path()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_default(),
+                    reason: String::from("no recent run to resume"),
+                });
+            }
+            history.latest().cloned()
+        } else {
+            None
+        };
+
+        let mut config = Config::build(
+            cli.preset_string
+                .as_deref()
+                .or(resumed.as_ref().map(|recent| &recent.preset[..]))
+                .unwrap_or("None"),
+            cli.rulestring
+                .as_deref()
+                .or(resumed.as_ref().map(|recent| &recent.rule[..]))
+                .unwrap_or("B3/S23"),
+            cli.tickrate.unwrap_or(DEFAULT_TICKRATE),
+            library,
+        );
+        if config.tickrate > MAX_TICKRATE {
+            config.errors.push(ConfigError::Tickrate {
+                input: format!("{:?}", config.tickrate),
+            });
+            config.tickrate = MAX_TICKRATE;
+        }
+        config
+            .errors
+            .extend(
+                library_errors
+                    .into_iter()
+                    .map(|(path, reason)| ConfigError::Library {
+                        path: path.display().to_string(),
+                        reason,
+                    }),
+            );
+        config.errors.extend(history_errors);
+        config.history = history;
+        if let Some(path) = &cli.workspace {
+            match Workspace::load(path) {
+                Ok(workspace) => config.workspace = workspace,
+                Err(error) => config.errors.push(ConfigError::Workspace {
+                    path: path.display().to_string(),
+                    reason: error.to_string(),
+                }),
+            }
+        }
+        // a workspace opens on its first board, unless told otherwise
+        let first_board = config.workspace.boards().first();
+        if let Some(board) = first_board.filter(|_| resumed.is_none()) {
+            if cli.preset_string.is_none() {
+                config.preset = board.clone();
+            }
+            if let Some(rule) = board
+                .pattern()
+                .and_then(|pattern| pattern.rule.as_deref()?.parse().ok())
+                .filter(|_| cli.rulestring.is_none())
+            {
+                config.rule = rule;
+            }
+        }
+        config.seed = cli.seed.or(resumed.and_then(|recent| recent.seed));
+        config.density = cli.density;
+        config.symmetry = cli.symmetry.unwrap_or_default();
+        config.compare = cli.compare.clone();
+        config.continuous = match cli.engine {
+            Some(EngineKind::Lenia) => Some(Continuous::Lenia(Lenia::new(
+                cli.lenia_radius,
+                cli.lenia_mu,
+                cli.lenia_sigma,
+                cli.lenia_dt,
+            ))),
+            Some(EngineKind::SmoothLife) => match SmoothLife::new(
+                cli.smoothlife_inner,
+                cli.smoothlife_outer,
+                cli.smoothlife_birth,
+                cli.smoothlife_death,
+            ) {
+                Ok(smooth_life) => Some(Continuous::SmoothLife(smooth_life)),
+                Err(reason) => {
+                    config.errors.push(ConfigError::Engine {
+                        input: String::from("smoothlife"),
+                        reason,
+                    });
+                    None
+                }
+            },
+            Some(EngineKind::Totalistic) => {
+                match Totalistic::new(cli.totalistic_states, &cli.totalistic_code) {
+                    Ok(totalistic) => Some(Continuous::Totalistic(totalistic)),
+                    Err(reason) => {
+                        config.errors.push(ConfigError::Engine {
+                            input: String::from("totalistic"),
+                            reason,
+                        });
+                        None
+                    }
+                }
+            }
+            Some(EngineKind::Life) | None => None,
+        };
+        if let Some(path) = &cli.kernel {
+            match Kernel::load(path) {
+                Ok(kernel) => config.kernel = Some(kernel),
+                Err(reason) => config.errors.push(ConfigError::Kernel {
+                    path: path.display().to_string(),
+                    reason,
+                }),
+            }
+        }
+        if let Some(path) = &cli.table {
+            match TransitionTable::load(path) {
+                Ok(table) => config.table = Some(table),
+                Err(reason) => config.errors.push(ConfigError::Table {
+                    path: path.display().to_string(),
+                    reason,
+                }),
+            }
+        }
+        if let Some(path) = &cli.mnca {
+            match Mnca::load(path) {
+                Ok(mnca) => config.mnca = Some(mnca),
+                Err(reason) => config.errors.push(ConfigError::Mnca {
+                    path: path.display().to_string(),
+                    reason,
+                }),
+            }
+        }
+        config.boundary = cli.boundary.unwrap_or_default();
+        config.neighborhood = cli.neighborhood.clone();
+        if let Some(neighborhood) = &config.neighborhood {
+            if neighborhood.size() != Moore.size() && config.rule.birth_list.contains(&0) {
+                config.errors.push(ConfigError::Neighborhood {
+                    input: neighborhood.to_string(),
+                    reason: String::from("B0 rules need the Moore neighborhood"),
+                });
+                config.neighborhood = None;
+            }
+        }
+        #[cfg(feature = "tui")]
+        {
+            config.keymap = Keymap::new(cli.jump.unwrap_or(DEFAULT_JUMP), &cli.bind);
+        }
+        config.frame_interval = match cli.low_bandwidth {
+            true => cli.fps.or(Some(LOW_BANDWIDTH_FRAME)),
+            false => cli.fps,
+        };
+        config.time_lapse = cli.time_lapse.unwrap_or(1);
+        config.run_until_stable = cli.run_until_stable;
+        config.alerts = cli.alert.clone();
+        config.fade = cli.fade;
+        config.export_html = cli.export_html;
+        config.overlay = cli.overlay;
+        config.screensaver = cli.screensaver;
+        config.playlist = cli.playlist.clone();
+        config.playlist_turn = cli.playlist_turn;
+        config.pause_on_focus_loss = !cli.run_unfocused;
+        config.accessible = cli.accessible;
+        config.autosave_on_exit = cli.autosave_on_exit;
+        config.journal = cli.journal;
+        config.checkpoint_interval = cli
+            .checkpoint_every
+            .unwrap_or(checkpoints::DEFAULT_INTERVAL);
+        config.mutate_every = cli.mutate_every;
+        config.transform = Transform {
+            rotation: cli.rotate.unwrap_or_default(),
+            flip_h: cli.flip_h,
+            flip_v: cli.flip_v,
+        };
+        config.placement = match (cli.at, cli.center) {
+            (Some(at), _) => Placement::At(at),
+            (None, true) => Placement::Center,
+            (None, false) => Placement::TopLeft,
+        };
+
+        for spec in &cli.place {
+            match config.library.resolve(&spec.name) {
+                Some(preset) if preset.pattern().is_some() => {
+                    config.places.push((spec.clone(), preset))
+                }
+                _ => config.errors.push(ConfigError::Place {
+                    spec: spec.to_string(),
+                    reason: String::from("no pattern by that name"),
+                }),
+            }
+        }
+
+        match cli.theme.as_deref() {
+            Some(name) => match parse_theme(name) {
+                Ok(theme) => config.theme = theme,
+                Err(error) => config.errors.push(error),
+            },
+            None => config.theme = Theme::for_terminal(),
+        }
+
+        if let Some(glyph) = cli.glyph.as_deref() {
+            match Theme::with_glyph(glyph) {
+                Ok(custom) => config.theme.alive_glyph = custom.alive_glyph,
+                Err(reason) => config.errors.push(ConfigError::Theme {
+                    input: String::from(glyph),
+                    reason,
+                }),
+            }
+            // keep dead cells as wide as the (possibly new) live glyph
+            config.theme.dead_glyph = " ".repeat(config.theme.cell_width() as usize);
+        }
+        config.theme.cell_style = cli.cell_style.unwrap_or_default();
+        config.layout = cli.layout.clone().unwrap_or_default();
+        config.theme.color_depth = match (cli.color_depth, ColorDepth::detect()) {
+            (Some(depth), _) => depth,
+            // truecolor sequences are the longest, and 256 colors look nearly the same
+            (None, ColorDepth::TrueColor) if cli.low_bandwidth => ColorDepth::Ansi256,
+            (None, depth) => depth,
+        };
+        config.theme.shading = cli.shading.unwrap_or_default();
+        if let Some(intensity) = cli.shading_intensity {
+            config.theme.shading_intensity = intensity;
+        }
+
+        config
+    }
+}
+
+/// Parses a tickrate with an optional unit: `us`/`µs`, `ms`, `s`, or a frequency such as
+/// `10/s`. A bare number is taken as milliseconds, matching the old behaviour.
+pub fn parse_tickrate(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let parse_number = |number: &str| {
+        number
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite() && *n > 0.0)
+            .ok_or_else(|| format!("\"{number}\" is not a positive number"))
+    };
+
+    let tickrate = if let Some(rate) = input.strip_suffix("/s") {
+        Duration::from_secs_f64(1.0 / parse_number(rate)?)
+    } else if let Some(micros) = input
+        .strip_suffix("us")
+        .or_else(|| input.strip_suffix("µs"))
+    {
+        Duration::from_secs_f64(parse_number(micros)? / 1_000_000.0)
+    } else if let Some(millis) = input.strip_suffix("ms") {
+        Duration::from_secs_f64(parse_number(millis)? / 1000.0)
+    } else if let Some(secs) = input.strip_suffix('s') {
+        Duration::from_secs_f64(parse_number(secs)?)
+    } else {
+        Duration::from_secs_f64(parse_number(input)? / 1000.0)
+    };
+
+    if tickrate < MIN_TICKRATE {
+        return Err(format!(
+            "tickrate must be at least {MIN_TICKRATE:?}, got {tickrate:?}"
+        ));
+    }
+
+    Ok(tickrate)
+}
+
+/// Parses a frame rate cap, returning the time between frames.
+pub fn parse_fps(input: &str) -> Result<Duration, String> {
+    match input.trim().parse::<f64>() {
+        Ok(fps) if fps.is_finite() && fps > 0.0 => Ok(Duration::from_secs_f64(1.0 / fps)),
+        _ => Err(format!("fps must be a positive number, got \"{input}\"")),
+    }
+}
+
+/// Parses a board size such as `120x40`, optionally followed by the [`Anchor`] to resize
+/// around, which is the center unless given.
+fn parse_board_size(input: &str) -> Result<(usize, usize, Anchor), String> {
+    let mut parts = input.split_whitespace();
+    let size = parts.next().unwrap_or_default();
+    let error = || format!("expected a board size such as 120x40, got \"{size}\"");
+    let (width, height) = size.split_once(['x', 'X']).ok_or_else(error)?;
+    let parse = |n: &str| {
+        n.parse::<usize>()
+            .ok()
+            .filter(|n| (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(n))
+            .ok_or_else(error)
+    };
+    let anchor = parts.next().map_or(Ok(Anchor::default()), Anchor::parse)?;
+    Ok((parse(width)?, parse(height)?, anchor))
+}
+
+fn parse_theme(input: &str) -> Result<Theme, ConfigError> {
+    Theme::named(input).ok_or_else(|| ConfigError::Theme {
+        input: String::from(input),
+        reason: String::from("unknown theme"),
+    })
+}
+
+impl ConfigError {
+    /// The prompt that can correct this error, if it can be corrected from inside the TUI.
+    pub fn field(&self) -> Option<PromptField> {
+        match self {
+            ConfigError::Rulestring { .. } => Some(PromptField::Rulestring),
+            ConfigError::Preset { .. } => Some(PromptField::Preset),
+            ConfigError::Theme { .. } => Some(PromptField::Theme),
+            ConfigError::Placement { .. }
+            | ConfigError::Library { .. }
+            | ConfigError::Place { .. }
+            | ConfigError::History { .. }
+            | ConfigError::Workspace { .. }
+            | ConfigError::Tickrate { .. }
+            | ConfigError::Engine { .. }
+            | ConfigError::Kernel { .. }
+            | ConfigError::Table { .. }
+            | ConfigError::Mnca { .. }
+            | ConfigError::Neighborhood { .. } => None,
+        }
+    }
+
+    pub fn input(&self) -> &str {
+        match self {
+            ConfigError::Rulestring { input, .. }
+            | ConfigError::Preset { input }
+            | ConfigError::Theme { input, .. }
+            | ConfigError::Tickrate { input }
+            | ConfigError::Engine { input, .. }
+            | ConfigError::Neighborhood { input, .. } => input,
+            ConfigError::Placement { preset, .. } => preset,
+            ConfigError::Library { path, .. }
+            | ConfigError::History { path, .. }
+            | ConfigError::Workspace { path, .. }
+            | ConfigError::Kernel { path, .. }
+            | ConfigError::Table { path, .. }
+            | ConfigError::Mnca { path, .. } => path,
+            ConfigError::Place { spec, .. } => spec,
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Rulestring { input, reason } => write!(
+                f,
+                "Invalid rulestring \"{input}\" ({reason}); using {}",
+                Rule::default()
+            ),
+            ConfigError::Preset { input } => {
+                write!(
+                    f,
+                    "Unknown preset \"{input}\"; using an empty board (--list-presets shows them all)"
+                )
+            }
+            ConfigError::Theme { input, reason } => {
+                write!(f, "Invalid theme \"{input}\" ({reason}); using classic")
+            }
+            ConfigError::Placement { preset, reason } => {
+                write!(f, "{preset} {reason}; it has been clipped")
+            }
+            ConfigError::Library { path, reason } => {
+                write!(f, "Couldn't load pattern {path} ({reason}); skipped")
+            }
+            ConfigError::Place { spec, reason } => write!(f, "--place {spec}: {reason}"),
+            ConfigError::History { path, reason } => {
+                write!(f, "Couldn't use the recent runs in {path} ({reason})")
+            }
+            ConfigError::Workspace { path, reason } => {
+                write!(f, "Couldn't open workspace {path} ({reason})")
+            }
+            ConfigError::Engine { input, reason } => {
+                write!(
+                    f,
+                    "Invalid {input} parameters ({reason}); using the rulestring"
+                )
+            }
+            ConfigError::Kernel { path, reason } => {
+                write!(
+                    f,
+                    "Couldn't use kernel {path} ({reason}); using the rulestring"
+                )
+            }
+            ConfigError::Table { path, reason } => {
+                write!(
+                    f,
+                    "Couldn't use transition table {path} ({reason}); using the rulestring"
+                )
+            }
+            ConfigError::Mnca { path, reason } => {
+                write!(
+                    f,
+                    "Couldn't use MNCA {path} ({reason}); using the rulestring"
+                )
+            }
+            ConfigError::Tickrate { input } => {
+                write!(
+                    f,
+                    "Tickrate {input} is slower than {MAX_TICKRATE:?}; using {MAX_TICKRATE:?}"
+                )
+            }
+            ConfigError::Neighborhood { input, reason } => {
+                write!(f, "Can't use neighborhood {input} ({reason}); using moore")
+            }
+        }
+    }
+}
+
+impl Cell {
+    pub fn new(state: bool) -> Cell {
+        Cell {
+            is_alive: state,
+            age: 0,
+            ever_alive: false,
+            marked: false,
+        }
+    }
+
+    pub fn vec_from(bool_cells: Vec<Vec<bool>>) -> Vec<Vec<Cell>> {
+        let mut outer = Vec::with_capacity(bool_cells.len());
+        for vector in bool_cells {
+            let mut inner = Vec::with_capacity(vector.len());
+            for cell in vector {
+                inner.push(Cell::new(cell));
+            }
+            outer.push(inner);
+        }
+        outer
+    }
+}
+
+impl Clone for Cell {
+    fn clone(&self) -> Cell {
+        Cell {
+            is_alive: self.is_alive,
+            age: self.age,
+            ever_alive: self.ever_alive,
+            marked: self.marked,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn alive_grid(model: &Model) -> Vec<Vec<bool>> {
+        model
+            .cells()
+            .iter()
+            .map(|line| line.iter().map(|cell| cell.is_alive).collect())
+            .collect()
+    }
+
+    #[test]
+    fn move_cursor() {
+        let mut model = Model::new(10, 10, vec![], vec![], Duration::from_millis(50));
+        model.move_cursor(-1, -4);
+        assert_eq!(Coords { x: 0, y: 0 }, *model.current_coords());
+        model.move_cursor(5, 6);
+        assert_eq!(Coords { x: 5, y: 6 }, *model.current_coords());
+        model.move_cursor(6, 6);
+        assert_eq!(Coords { x: 10, y: 10 }, *model.current_coords());
+    }
+
+    #[test]
+    fn observes_generations() {
+        let mut model = Model::new(4, 4, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        let seen = Arc::new(Mutex::new(vec![]));
+        let log = Arc::clone(&seen);
+        model.on_generation(move |generation, diff| {
+            log.lock().unwrap().push((generation, diff.clone()));
+        });
+        model.step_n(2);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        let (generation, diff) = &seen[0];
+        assert_eq!(*generation, 1);
+        assert_eq!(diff.born.len(), 2);
+        assert_eq!(diff.died.len(), 2);
+        assert_eq!(seen[1].1.born, seen[0].1.died);
+        for coords in &diff.born {
+            assert!(model.is_alive(coords.x as usize, coords.y as usize) == Some(false));
+        }
+        assert_eq!(model.last_diff(), &seen[1].1);
+    }
+
+    #[test]
+    fn diffs_last_generation() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        assert_eq!(model.last_diff(), &Diff::default());
+        model.load_preset(Preset::Glider);
+        let before = alive_grid(&model);
+        model.step_n(1);
+        let after = alive_grid(&model);
+
+        let diff = model.last_diff();
+        assert!(!diff.born.is_empty() && !diff.died.is_empty());
+        for coords in &diff.born {
+            let (x, y) = (coords.x as usize, coords.y as usize);
+            assert!(!before[y][x] && after[y][x]);
+        }
+        for coords in &diff.died {
+            let (x, y) = (coords.x as usize, coords.y as usize);
+            assert!(before[y][x] && !after[y][x]);
+        }
+        let changed = (0..10)
+            .flat_map(|y| (0..10).map(move |x| (x, y)))
+            .filter(|&(x, y)| before[y][x] != after[y][x])
+            .count();
+        assert_eq!(diff.born.len() + diff.died.len(), changed);
+
+        model.resize(12, 12, Anchor::TopLeft);
+        assert_eq!(model.last_diff(), &Diff::default());
+    }
+
+    #[test]
+    fn intersects_areas() {
+        let area = Area::new(2, 3, 10, 5);
+        assert!(area.contains(2, 3) && area.contains(11, 7));
+        assert!(!area.contains(12, 7) && !area.contains(2, 8));
+        assert_eq!(
+            area.intersection(Area::new(8, 0, 100, 4)),
+            Area::new(8, 3, 4, 1)
+        );
+        assert!(area.intersection(Area::new(20, 20, 5, 5)).is_empty());
+        let everything = Area::new(0, 0, u16::MAX, u16::MAX);
+        assert_eq!(everything.intersection(area), area);
+    }
+
+    #[test]
+    fn randomizes_region() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Glider);
+        let glider = alive_grid(&model);
+        let mut rng = RngSource::seeded(7);
+        model.randomize_region(Area::new(5, 5, 20, 20), 1.0, &mut rng);
+
+        let grid = alive_grid(&model);
+        for (y, line) in grid.iter().enumerate() {
+            for (x, alive) in line.iter().enumerate() {
+                let expected = if x >= 5 && y >= 5 { true } else { glider[y][x] };
+                assert_eq!(*alive, expected, "cell {x}, {y}");
+            }
+        }
+        model.randomize_region(Area::new(5, 5, 5, 5), 0.0, &mut rng);
+        assert_eq!(alive_grid(&model), glider);
+
+        // the key fills the cells in view, here 3 by 2 in the top-left corner
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_density(Some(1.0));
+        model.fit_viewport(Area::new(0, 0, 3, 2));
+        model.update(Message::RandomizeView);
+        let grid = alive_grid(&model);
+        for (y, line) in grid.iter().enumerate() {
+            for (x, alive) in line.iter().enumerate() {
+                assert_eq!(*alive, x < 3 && y < 2, "cell {x}, {y}");
+            }
+        }
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn steps_other_neighborhoods() {
+        // under B2/S, two cells side by side give birth to the four cells beside both of them
+        // in the Moore neighborhood, but no cell has both as von Neumann neighbors
+        let pair = |neighborhood: Arc<dyn Neighborhood>| {
+            let mut model = Model::new(6, 6, vec![2], vec![], Duration::from_millis(50));
+            model.set_neighborhood(neighborhood);
+            model.update_cell(3, 3, true);
+            model.update_cell(3, 4, true);
+            model.step_n(1);
+            alive_grid(&model)
+                .into_iter()
+                .flatten()
+                .filter(|alive| *alive)
+                .count()
+        };
+        assert_eq!(pair(Arc::new(Moore)), 4);
+        assert_eq!(pair(Arc::new(neighborhood::VonNeumann)), 0);
+        // a neighborhood reaching two cells out sees past the edge of a wrapped board
+        let mut model = Model::new(4, 4, vec![1], vec![], Duration::from_millis(50));
+        model.set_boundary(Boundary::Wrap);
+        model.set_neighborhood(neighborhood::parse("2,0").unwrap());
+        model.update_cell(0, 4, true);
+        model.step_n(1);
+        assert_eq!(model.is_alive(2, 0), Some(true));
+        assert_eq!(
+            alive_grid(&model)
+                .into_iter()
+                .flatten()
+                .filter(|a| *a)
+                .count(),
+            1
+        );
+
+        let cli = Cli::parse_from(["tui-ca", "--neighborhood", "hex", "-r", "B0/S"]);
+        let config = Config::from_cli(&cli);
+        assert!(config.neighborhood.is_none());
+        assert!(matches!(
+            config.errors[..],
+            [ConfigError::Neighborhood { .. }]
+        ));
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn low_bandwidth() {
+        let cli = Cli::parse_from(["ratatui-cellular-automaton", "--low-bandwidth"]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.frame_interval, Some(LOW_BANDWIDTH_FRAME));
+        assert_ne!(config.theme.color_depth, ColorDepth::TrueColor);
+
+        let cli = Cli::parse_from([
+            "ratatui-cellular-automaton",
+            "--low-bandwidth",
+            "--fps",
+            "20",
+            "--color-depth",
+            "truecolor",
+        ]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.frame_interval, Some(Duration::from_millis(50)));
+        assert_eq!(config.theme.color_depth, ColorDepth::TrueColor);
+
+        let args = ["ratatui-cellular-automaton", "--low-bandwidth", "--fade"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn headless_board_size() {
+        // a board needs 2 cells a side
+        for dimension in ["--width", "--height"] {
+            let args = ["tui-ca", "headless", dimension, "1"];
+            assert!(Cli::try_parse_from(args).is_err());
+            assert!(Cli::try_parse_from(["tui-ca", "headless", dimension, "2"]).is_ok());
+        }
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn time_lapse() {
+        let cli = Cli::parse_from(["tui-ca", "--time-lapse", "100"]);
+        assert_eq!(Config::from_cli(&cli).time_lapse, 100);
+        assert!(Cli::try_parse_from(["tui-ca", "--time-lapse", "0"]).is_err());
+
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::ToggleEditing);
+        model.update(Message::CycleTimeLapse);
+        assert_eq!(model.time_lapse(), 10);
+        model.update(Message::Idle);
+        assert_eq!(model.generation(), 10);
+        model.set_time_lapse(MAX_TIME_LAPSE);
+        model.update(Message::CycleTimeLapse);
+        assert_eq!(model.time_lapse(), 1);
+        model.update(Message::Idle);
+        assert_eq!(model.generation(), 11);
+    }
+
+    #[test]
+    fn coords_arithmetic() {
+        let here = Coords { x: 3, y: 4 };
+        assert_eq!(here + Coords { x: 1, y: -2 }, Coords { x: 4, y: 2 });
+        assert_eq!(here - Coords { x: 1, y: -2 }, Coords { x: 2, y: 6 });
+        assert_eq!(here.offset(-5, 1), Coords { x: -2, y: 5 });
+        assert_eq!(here.offset(i16::MAX, 0).x, i16::MAX);
+        assert_eq!(
+            here.offset(-5, 9).within(Coords { x: 9, y: 9 }),
+            Coords { x: 0, y: 9 }
+        );
+
+        let bounds = Coords { x: 9, y: 9 };
+        assert_eq!(here.neighbors(&Moore, bounds).count(), 8);
+        assert_eq!(here.neighbors(&crate::neighborhood::Hex, bounds).count(), 6);
+        let corner: Vec<_> = Coords { x: 0, y: 0 }.neighbors(&Moore, bounds).collect();
+        assert_eq!(
+            corner,
+            [
+                Coords { x: 1, y: 0 },
+                Coords { x: 0, y: 1 },
+                Coords { x: 1, y: 1 }
+            ]
+        );
+    }
+
+    #[test]
+    fn move_cursor_in_direction() {
+        let mut model = Model::new(10, 10, vec![], vec![], Duration::from_millis(50));
+        model.move_cursor_in_direction(Direction::Down, 1);
+        assert_eq!(Coords { x: 0, y: 1 }, *model.current_coords());
+        model.move_cursor_in_direction(Direction::Right, 1);
+        assert_eq!(Coords { x: 1, y: 1 }, *model.current_coords());
+        model.move_cursor_in_direction(Direction::Up, 1);
+        assert_eq!(Coords { x: 1, y: 0 }, *model.current_coords());
+        model.move_cursor_in_direction(Direction::Left, 1);
+        assert_eq!(Coords { x: 0, y: 0 }, *model.current_coords());
+    }
+
+    #[test]
+    #[should_panic(expected = "Geometrically impossible birth")]
+    fn too_many_neighbors_birth() {
+        Model::new(
+            10,
+            10,
+            vec![1, 2, 9],
+            vec![1, 2, 3],
+            Duration::from_millis(50),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Geometrically impossible survival")]
+    fn too_many_neighbors_survival() {
+        Model::new(
+            10,
+            10,
+            vec![4, 4, 4],
+            vec![9, 4, 4],
+            Duration::from_millis(50),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Max coords")]
+    fn max_x_too_small() {
+        Model::new(10, -1, vec![], vec![], Duration::from_millis(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "Max coords")]
+    fn max_y_too_small() {
+        Model::new(0, 10, vec![], vec![], Duration::from_millis(50));
+    }
+
+    #[test]
+    fn toggle_current_cell() {
+        let mut model = Model::new(3, 3, vec![], vec![], Duration::from_millis(50));
+        model.move_cursor_in_direction(Direction::Down, 1);
+        model.move_cursor_in_direction(Direction::Right, 1);
+        model.update(Message::ToggleCellState);
+        assert_eq!(
+            Cell::vec_from(vec![
+                vec![false; 4],
+                vec![false, true, false, false],
+                vec![false; 4],
+                vec![false; 4]
+            ]),
+            *model.cells()
+        );
+    }
+
+    #[test]
+    fn toggle_editing_state() {
+        let mut model = Model::new(5, 5, vec![], vec![], Duration::from_millis(50));
+        model.update(Message::ToggleEditing);
+        assert_eq!(*model.state(), State::Running);
+        model.update(Message::ToggleEditing);
+        assert_eq!(*model.state(), State::Editing);
+    }
+
+    #[test]
+    fn pass_tick_running_blinker() {
+        let mut model = Model::new(4, 4, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.cells = Cell::vec_from(vec![
+            vec![false, false, false, false, false],
+            vec![false, false, false, false, false],
+            vec![false, true, true, true, false],
+            vec![false, false, false, false, false],
+            vec![false, false, false, false, false],
+        ]);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert_eq!(
+            alive_grid(&model),
+            vec![
+                vec![false, false, false, false, false],
+                vec![false, false, true, false, false],
+                vec![false, false, true, false, false],
+                vec![false, false, true, false, false],
+                vec![false, false, false, false, false],
+            ]
+        );
+        assert_eq!(model.cell(2, 2).map(|cell| cell.age), Some(1));
+        assert_eq!(model.cell(2, 1).map(|cell| cell.age), Some(0));
+        model.cell_mut(2, 2).unwrap().age = u32::MAX;
+        model.increment_cell_age(2, 2);
+        assert_eq!(model.cell(2, 2).map(|cell| cell.age), Some(u32::MAX));
+        model.update(Message::Idle);
+        assert_eq!(
+            alive_grid(&model),
+            vec![
+                vec![false, false, false, false, false],
+                vec![false, false, false, false, false],
+                vec![false, true, true, true, false],
+                vec![false, false, false, false, false],
+                vec![false, false, false, false, false],
+            ]
+        );
+    }
+
+    #[test]
+    fn load_preset() {
+        let mut model = Model::new(4, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        assert_eq!(
+            alive_grid(&model),
+            vec![
+                vec![false, false, false, false, false, false],
+                vec![true, true, true, false, false, false],
+                vec![false, false, false, false, false, false],
+                vec![false, false, false, false, false, false],
+                vec![false, false, false, false, false, false],
+            ]
+        );
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert_eq!(
+            alive_grid(&model),
+            vec![
+                vec![false, true, false, false, false, false],
+                vec![false, true, false, false, false, false],
+                vec![false, true, false, false, false, false],
+                vec![false, false, false, false, false, false],
+                vec![false, false, false, false, false, false],
+            ]
+        );
+    }
+
+    #[test]
+    fn pass_tick_running_mold() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.cells = Cell::vec_from(vec![
+            vec![false, false, false, true, true, false],
+            vec![false, false, true, false, false, true],
+            vec![true, false, false, true, false, true],
+            vec![false, false, false, false, true, false],
+            vec![true, false, true, true, false, false],
+            vec![false, true, false, false, false, false],
+        ]);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert_eq!(
+            alive_grid(&model),
+            vec![
+                vec![false, false, false, true, true, false],
+                vec![false, false, true, false, false, true],
+                vec![false, false, false, true, false, true],
+                vec![false, true, true, false, true, false],
+                vec![false, true, true, true, false, false],
+                vec![false, true, true, false, false, false],
+            ]
+        );
+    }
+
+    #[test]
+    fn cell_out_of_bounds() {
+        let mut model = Model::new(3, 3, vec![], vec![], Duration::from_millis(50));
+        assert!(model.cell(3, 3).is_some());
+        assert!(model.cell(4, 0).is_none());
+        assert!(model.cell(0, 4).is_none());
+        assert_eq!(model.try_set(1, 2, true), Some(()));
+        assert!(model.cell(1, 2).unwrap().is_alive);
+        assert_eq!(model.try_set(10, 2, true), None);
+    }
+
+    #[test]
+    fn state_transitions() {
+        assert_eq!(
+            State::Editing.transition(&Message::ToggleEditing),
+            Some(State::Running)
+        );
+        assert_eq!(State::Running.transition(&Message::Idle), None);
+        assert_eq!(State::Done.transition(&Message::ToggleEditing), None);
+        assert_eq!(State::Done.transition(&Message::Quit), None);
+
+        let prompting = State::Running.transition(&Message::OpenPrompt).unwrap();
+        assert_eq!(prompting.transition(&Message::ToggleEditing), None);
+        assert_eq!(
+            prompting.transition(&Message::PromptCancel),
+            Some(State::Running)
+        );
+    }
+
+    #[test]
+    fn prompt_needs_config_error() {
+        let mut model = Model::new(3, 3, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::OpenPrompt);
+        assert_eq!(*model.state(), State::Editing);
+        assert!(model.prompt().is_none());
+    }
+
+    #[test]
+    fn config_errors_fall_back() {
+        let config = Config::build(
+            "glidr",
+            "B3/S2x",
+            Duration::from_millis(50),
+            Library::default(),
+        );
+        assert_eq!(config.rule, Rule::default());
+        assert!(matches!(config.preset, Preset::Empty));
+        assert_eq!(config.errors.len(), 2);
+
+        let config = Config::build(
+            "None",
+            "B36/S23",
+            Duration::from_millis(50),
+            Library::default(),
+        );
+        assert!(config.errors.is_empty());
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn config_from_cli_theme() {
+        let cli = Cli::parse_from(["tui-ca", "--theme", "square"]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.theme.cell_width(), 2);
+        assert!(config.errors.is_empty());
+
+        let cli = Cli::parse_from(["tui-ca", "--glyph", "🟥"]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.theme.alive_glyph, "🟥");
+        assert_eq!(config.theme.dead_glyph, "  ");
+
+        let cli = Cli::parse_from(["tui-ca", "--theme", "dots", "--cell-style", "both"]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.theme.cell_style, CellStyle::Both);
+
+        let cli = Cli::parse_from(["tui-ca", "--theme", "plaid", "--color-depth", "truecolor"]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.theme, Theme::default());
+        assert_eq!(config.errors[0].field(), Some(PromptField::Theme));
+    }
+
+    #[test]
+    fn correct_rulestring_through_prompt() {
+        let mut model = Model::new(3, 3, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_config_errors(
+            Config::build(
+                "None",
+                "B3/S9",
+                Duration::from_millis(50),
+                Library::default(),
+            )
+            .errors,
+        );
+
+        model.update(Message::OpenPrompt);
+        assert!(matches!(model.state(), State::Prompting { .. }));
+        assert_eq!(model.prompt().unwrap().buffer, "B3/S9");
+
+        model.update(Message::PromptBackspace);
+        model.update(Message::PromptInput('8'));
+        model.update(Message::PromptSubmit);
+
+        assert_eq!(*model.state(), State::Editing);
+        assert_eq!(model.rulestring(), "B3/S8");
+        assert!(model.config_errors().is_empty());
+    }
+
+    #[test]
+    fn random_preset_is_reproducible() {
+        let mut first = Model::new(8, 8, vec![3], vec![2, 3], Duration::from_millis(50));
+        first.set_rng(RngSource::seeded(7));
+        first.load_preset(Preset::Random);
+
+        let mut second = Model::new(8, 8, vec![3], vec![2, 3], Duration::from_millis(50));
+        second.set_rng(RngSource::seeded(7));
+        second.load_preset(Preset::Random);
+
+        assert_eq!(first.cells(), second.cells());
+    }
+
+    #[test]
+    fn random_preset_uses_injected_rng() {
+        // a generator that only ever yields zero makes every gen_bool(0.3) come out true
+        let mut model = Model::new(2, 2, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_rng(RngSource::from_rng(rand::rngs::mock::StepRng::new(0, 0)));
+        model.load_preset(Preset::Random);
+        assert!(model.cells().iter().flatten().all(|cell| cell.is_alive));
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn tickrate_units() {
+        assert_eq!(parse_tickrate("50"), Ok(Duration::from_millis(50)));
+        assert_eq!(parse_tickrate("50ms"), Ok(Duration::from_millis(50)));
+        assert_eq!(parse_tickrate("250us"), Ok(Duration::from_micros(250)));
+        assert_eq!(parse_tickrate("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_tickrate("0.5s"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_tickrate("10/s"), Ok(Duration::from_millis(100)));
+        assert!(parse_tickrate("0").is_err());
+        assert!(parse_tickrate("10us").is_err());
+        assert!(parse_tickrate("-5ms").is_err());
+        assert!(parse_tickrate("fast").is_err());
+
+        let cli = Cli::parse_from(["tui-ca", "--tickrate", "1/s"]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.tickrate, Duration::from_secs(1));
+        assert!(config.errors.is_empty());
+
+        let cli = Cli::parse_from(["tui-ca", "--tickrate", "90s"]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.tickrate, MAX_TICKRATE);
+        assert_eq!(config.errors[0].input(), "90s");
+    }
+
+    #[test]
+    fn b0_phase_inversion() {
+        // in B0/S8 every dead cell with fewer than eight live neighbors is born, so a lone
+        // dead cell in the live background dies again and everything else stays alive
+        let mut model = Model::new(4, 4, vec![0], vec![8], Duration::from_millis(50));
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert!(model.is_inverted());
+        assert!(alive_grid(&model).iter().flatten().all(|alive| !alive));
+        assert_eq!(model.is_alive(2, 2), Some(true));
+
+        // B0/S (no S8): the background flashes on and off, so a single live cell in a dead
+        // background sees its neighbors born and itself die
+        let mut model = Model::new(4, 4, vec![0], vec![], Duration::from_millis(50));
+        model.try_set(2, 2, true);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert!(model.is_inverted());
+        assert_eq!(model.is_alive(0, 0), Some(true));
+        assert_eq!(model.is_alive(2, 2), Some(false));
+        model.update(Message::Idle);
+        assert!(!model.is_inverted());
+        assert_eq!(model.generation(), 2);
+        // the cell is surrounded by dead neighbors, so it is born again while the rest dies
+        assert_eq!(model.is_alive(2, 2), Some(true));
+        assert_eq!(model.is_alive(0, 0), Some(false));
+    }
+
+    #[test]
+    fn builtin_presets() {
+        for preset in Preset::ALL {
+            assert_eq!(Preset::parse(preset.name()), Some(preset.clone()));
+            if let Some(pattern) = preset.pattern() {
+                assert!(pattern.cells.iter().flatten().any(|alive| *alive));
+            }
+        }
+        assert_eq!(Preset::parse("r-pentomino"), Some(Preset::RPentomino));
+        assert_eq!(Preset::parse("gosper"), Some(Preset::GosperGliderGun));
+        assert_eq!(Preset::Pulsar.pattern().unwrap().cells.len(), 13);
+    }
+
+    #[test]
+    fn glider_moves() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Glider);
+        model.update(Message::ToggleEditing);
+        for _ in 0..4 {
+            model.update(Message::Idle);
+        }
+        let mut expected = vec![vec![false; 6]; 6];
+        for (x, y) in [(2, 1), (3, 2), (1, 3), (2, 3), (3, 3)] {
+            expected[y][x] = true;
+        }
+        assert_eq!(alive_grid(&model), expected);
+    }
+
+    #[test]
+    fn placement() {
+        let board = (10, 8);
+        assert_eq!(
+            Placement::Center.origin((3, 3), board),
+            Coords { x: 3, y: 2 }
+        );
+        assert!(Placement::Center.check_fit((3, 3), board).is_ok());
+        assert!(Placement::At(Coords { x: 8, y: 0 })
+            .check_fit((3, 3), board)
+            .is_err());
+        assert!(Placement::Center.check_fit((36, 9), board).is_err());
+
+        let mut glider = Preset::Glider.pattern().unwrap();
+        glider.metadata.position = Some((5, 4));
+        let untransformed = Transform::default();
+        assert_eq!(
+            Placement::TopLeft.for_pattern(&glider, untransformed, board),
+            Placement::At(Coords { x: 5, y: 4 })
+        );
+        assert_eq!(
+            Placement::Center.for_pattern(&glider, untransformed, board),
+            Placement::Center
+        );
+        glider.metadata.position = Some((-1000, 4));
+        assert_eq!(
+            Placement::TopLeft.for_pattern(&glider, untransformed, board),
+            Placement::TopLeft
+        );
+        assert_eq!(parse_coords("4, 5"), Ok(Coords { x: 4, y: 5 }));
+        assert!(parse_coords("4").is_err());
+    }
+
+    #[test]
+    fn load_preset_at_clips() {
+        let mut model = Model::new(3, 3, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset_at(Preset::Glider, Coords { x: 2, y: -1 }, Transform::default());
+        assert_eq!(
+            alive_grid(&model),
+            vec![
+                vec![false; 4],
+                vec![false, false, true, true],
+                vec![false; 4],
+                vec![false; 4],
+            ]
+        );
+    }
+
+    #[test]
+    fn diehard_stabilizes_as_expected() {
+        let mut model = Model::new(29, 29, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset_at(Preset::Diehard, Coords { x: 8, y: 6 }, Transform::default());
+        assert_eq!(model.expected_stabilization(), Some(130));
+        model.update(Message::ToggleEditing);
+        while model.stabilization().is_none() && model.generation() < 200 {
+            model.update(Message::Idle);
+        }
+        assert_eq!(
+            model.stabilization(),
+            Some(Stabilization {
+                generation: 130,
+                period: 1
+            })
+        );
+
+        model.update(Message::ToggleEditing);
+        model.update(Message::ToggleCellState);
+        assert_eq!(model.stabilization(), None);
+        assert_eq!(model.expected_stabilization(), None);
+    }
+
+    #[test]
+    fn comparison_runs_in_lockstep() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        model.set_comparison("B3/S".parse::<Rule>().unwrap());
+        assert_eq!(alive_grid(model.comparison().unwrap()), alive_grid(&model));
+
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        let comparison = model.comparison().unwrap();
+        assert_eq!(comparison.generation(), 1);
+        assert!(model.is_alive(1, 1).unwrap());
+        assert!(!comparison.is_alive(1, 1).unwrap());
+        assert!(comparison.is_alive(1, 0).unwrap());
+        assert!(comparison.is_alive(1, 2).unwrap());
+
+        model.update(Message::ToggleEditing);
+        model.update(Message::ToggleCellState);
+        assert_eq!(alive_grid(model.comparison().unwrap()), alive_grid(&model));
+    }
+
+    #[test]
+    fn ghost_keeps_previous_generation() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        model.update(Message::ToggleGhost);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert!(model.was_alive(0, 1));
+        assert!(!model.is_alive(0, 1).unwrap());
+        assert!(!model.was_alive(1, 0));
+
+        model.update(Message::ToggleGhost);
+        assert!(!model.was_alive(0, 1));
+    }
+
+    #[test]
+    fn fades_between_generations() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_secs(2));
+        model.load_preset(Preset::Blinker);
+        model.set_fade(true);
+        model.update(Message::ToggleEditing);
+        assert_eq!(model.fade_progress(), None);
+        model.update(Message::Idle);
+        let progress = model.fade_progress().unwrap();
+        assert!(progress < 1.0);
+        assert!(model.until_next_tick() > Duration::ZERO);
+        // the end of the blinker dying, a cell born beside the middle, and the middle
+        assert!(model.fading(0, 1).is_some());
+        assert!(model.fading(1, 0).is_some());
+        assert_eq!(model.fading(1, 1), None);
+        // without ghosts on, the previous generation is only for fading
+        assert!(!model.was_alive(0, 1));
+
+        // 62.5ms ticks are too fast to see a fade
+        for _ in 0..5 {
+            model.update(Message::Faster);
+        }
+        assert_eq!(model.fade_progress(), None);
+        model.set_fade(false);
+        model.update(Message::Slower);
+        assert_eq!(model.fading(0, 1), None);
+    }
+
+    #[test]
+    fn inspects_the_cursor_cell() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        model.update(Message::ToggleInspector);
+        assert!(model.is_inspector());
+
+        // the blinker's middle, its end and a cell beside the middle
+        let inspect_at = |model: &mut Model, x, y| {
+            model.current_coords = Coords { x, y };
+            model.inspect().unwrap()
+        };
+        let middle = inspect_at(&mut model, 1, 1);
+        assert!(middle.alive);
+        assert_eq!((middle.neighbors, middle.next), (2, Some(true)));
+        assert_eq!(middle.reason, "Survives: S23 has 2");
+        let end = inspect_at(&mut model, 0, 1);
+        assert_eq!((end.neighbors, end.next), (1, Some(false)));
+        assert_eq!(end.reason, "Dies: S23 lacks 1");
+        let beside = inspect_at(&mut model, 1, 0);
+        assert!(!beside.alive);
+        assert_eq!((beside.neighbors, beside.next), (3, Some(true)));
+        assert_eq!(beside.reason, "Is born: B3 has 3");
+
+        // the next tick does what the inspector said
+        model.update(Message::ToggleEditing);
+        assert!(!model.is_inspector());
+        model.update(Message::Idle);
+        assert_eq!(model.is_alive(1, 0), Some(true));
+        assert_eq!(model.is_alive(0, 1), Some(false));
+    }
+
+    #[test]
+    fn steps_in_bulk() {
+        let mut stepped = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        stepped.load_preset(Preset::Glider);
+        let mut ticked = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        ticked.load_preset(Preset::Glider);
+        ticked.update(Message::ToggleEditing);
+        for _ in 0..12 {
+            ticked.update(Message::Idle);
+        }
+        assert_eq!(stepped.step_n(12), 12);
+        assert_eq!(stepped.generation(), 12);
+        assert_eq!(alive_grid(&stepped), alive_grid(&ticked));
+        assert_eq!(stepped.stats().samples().count(), 12);
+
+        // the callback sees every generation and can stop early
+        let mut seen = vec![];
+        let count = stepped.step_n_with(100, |model| {
+            seen.push(model.generation());
+            match model.generation() {
+                15 => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            }
+        });
+        assert_eq!((count, seen), (3, vec![13, 14, 15]));
+        assert_eq!(stepped.generation(), 15);
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn population_alerts() {
+        let cli = Cli::parse_from([
+            "ratatui-cellular-automaton",
+            "--alert",
+            "below:5:pause",
+            "--alert",
+            "below:5:bell",
+        ]);
+        let alerts = Config::from_cli(&cli).alerts;
+        assert_eq!(alerts.len(), 2);
+
+        // the glider runs into the corner and becomes a block of 4 cells
+        let mut model = Model::new(10, 10, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Glider);
+        model.set_alerts(alerts);
+        model.update(Message::ToggleEditing);
+        let mut ticks = 0;
+        while *model.state() == State::Running && ticks < 100 {
+            model.update(Message::Idle);
+            ticks += 1;
+        }
+        assert_eq!(*model.state(), State::Editing);
+        assert!(model
+            .notice()
+            .unwrap()
+            .starts_with("Population below 5 at generation"));
+        assert!(model.take_bell());
+        assert!(!model.take_bell());
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn runs_until_stable() {
+        let mut model = Model::new(10, 10, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Glider);
+        model.update(Message::ToggleEditing);
+        model.update(Message::RunUntilStable);
+        assert!(matches!(model.state(), State::FastForwarding { .. }));
+        assert!(model.fast_forward().unwrap().until_stable);
+        while model.fast_forward().is_some() {
+            model.update(Message::Idle);
+        }
+        // pauses once settled, even though it started out running
+        assert_eq!(*model.state(), State::Editing);
+        assert!(model.generation() < RUN_UNTIL_STABLE);
+        let notice = model.notice().unwrap();
+        assert!(notice.starts_with(&format!("Ran {} generations: ", model.generation())));
+
+        // a board that has already settled is reported without stepping
+        let generation = model.generation();
+        model.update(Message::RunUntilStable);
+        assert_eq!(model.fast_forward(), None);
+        assert_eq!(model.generation(), generation);
+
+        let cli = Cli::parse_from(["ratatui-cellular-automaton", "--run-until-stable"]);
+        assert!(Config::from_cli(&cli).run_until_stable);
+    }
+
+    #[test]
+    fn fast_forwards_in_batches() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        let start = alive_grid(&model);
+        model.update(Message::FastForward);
+        assert!(matches!(model.state(), State::FastForwarding { .. }));
+        let mut batches = 0;
+        while model.fast_forward().is_some() {
+            model.update(Message::Idle);
+            batches += 1;
+        }
+        assert_eq!(batches, FAST_FORWARD / FAST_FORWARD_BATCH);
+        assert_eq!(*model.state(), State::Editing);
+        assert_eq!(model.generation(), FAST_FORWARD);
+        assert_eq!(alive_grid(&model), start);
+
+        // stopping early goes back to running where it got to
+        model.update(Message::ToggleEditing);
+        model.update(Message::FastForward);
+        model.update(Message::Idle);
+        model.update(Message::StopFastForward);
+        assert_eq!(*model.state(), State::Running);
+        assert_eq!(model.generation(), FAST_FORWARD + FAST_FORWARD_BATCH);
+        assert_eq!(model.fast_forward(), None);
+    }
+
+    #[test]
+    fn plays_lessons() {
+        let scenario = Scenario::parse(
+            "load blinker 2,2\n\
+             say Three in a row.\n\
+             predict 3,3\n\
+             advance 1\n\
+             say Turned on its side.\n",
+        )
+        .unwrap();
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_scenario(Some(scenario));
+        let scenario = |model: &Model| model.scenario().unwrap().clone();
+        assert_eq!(scenario(&model).waiting(), Some(Waiting::Continue));
+        assert_eq!(model.is_alive(3, 3), Some(true));
+
+        model.update(Message::Continue);
+        assert_eq!(scenario(&model).waiting(), Some(Waiting::Prediction));
+        assert_eq!(*model.current_coords(), Coords { x: 3, y: 3 });
+        model.update(Message::Continue);
+        assert_eq!(scenario(&model).waiting(), Some(Waiting::Prediction));
+        model.update(Message::Predict(false));
+        let answer = scenario(&model).answer().cloned().unwrap();
+        assert_eq!((answer.predicted, answer.actual), (false, Some(true)));
+        assert_eq!(answer.reason, "Survives: S23 has 2");
+
+        model.update(Message::Continue);
+        assert_eq!(model.generation(), 1);
+        assert_eq!(model.is_alive(3, 2), Some(true));
+        model.update(Message::Continue);
+        assert_eq!(scenario(&model).current(), None);
+        assert_eq!(scenario(&model).waiting(), None);
+    }
+
+    #[test]
+    fn zen_only_while_running() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::ToggleZen);
+        assert!(!model.is_zen());
+
+        model.update(Message::ToggleEditing);
+        model.update(Message::ToggleZen);
+        assert!(model.is_zen());
+        model.update(Message::ToggleZen);
+        assert!(!model.is_zen());
+    }
+
+    #[test]
+    fn records_stats() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert_eq!(
+            model.stats().latest(),
+            Some(&Sample {
+                generation: 1,
+                population: 3,
+                births: 2,
+                deaths: 2,
+            })
+        );
+
+        model.update(Message::SwitchScreen);
+        assert_eq!(model.screen(), Screen::Stats);
+    }
+
+    #[test]
+    fn inset_follows_cursor_until_pinned() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        assert_eq!(model.inset_center(), None);
+
+        model.update(Message::ToggleInset);
+        model.update(Message::Move(Direction::Right, 1));
+        assert_eq!(model.inset_center(), Some(Coords { x: 1, y: 0 }));
+
+        model.update(Message::ToggleInsetPin);
+        model.update(Message::Move(Direction::Down, 1));
+        assert_eq!(model.inset_center(), Some(Coords { x: 1, y: 0 }));
+
+        model.update(Message::ToggleInsetPin);
+        assert_eq!(model.inset_center(), Some(Coords { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn move_by_several_cells() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::Move(Direction::Right, 5));
+        model.update(Message::Move(Direction::Down, 3));
+        assert_eq!(*model.current_coords(), Coords { x: 5, y: 3 });
+
+        model.update(Message::Move(Direction::Right, i16::MAX));
+        model.update(Message::Move(Direction::Up, i16::MAX));
+        assert_eq!(*model.current_coords(), Coords { x: 9, y: 0 });
+    }
+
+    #[test]
+    fn pause_while_unfocused() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_pause_on_focus_loss(true);
+        model.update(Message::ToggleEditing);
+        model.update(Message::FocusLost);
+        assert_eq!(*model.state(), State::Editing);
+        model.update(Message::FocusGained);
+        assert_eq!(*model.state(), State::Running);
+
+        // a pause chosen by the user isn't undone by regaining focus
+        model.update(Message::ToggleEditing);
+        model.update(Message::FocusLost);
+        model.update(Message::FocusGained);
+        assert_eq!(*model.state(), State::Editing);
+
+        model.set_pause_on_focus_loss(false);
+        model.update(Message::ToggleEditing);
+        model.update(Message::FocusLost);
+        assert_eq!(*model.state(), State::Running);
+    }
+
+    #[test]
+    fn speed_and_zoom() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::Faster);
+        assert_eq!(model.tickrate(), Duration::from_millis(25));
+        for _ in 0..20 {
+            model.update(Message::Slower);
+        }
+        assert_eq!(model.tickrate(), MAX_TICKRATE);
+        for _ in 0..30 {
+            model.update(Message::Faster);
+        }
+        assert_eq!(model.tickrate(), MIN_TICKRATE);
+
+        model.update(Message::Zoom(2));
+        assert_eq!(model.zoom(), 3);
+        model.update(Message::Zoom(i16::MAX));
+        assert_eq!(model.zoom(), MAX_ZOOM);
+        model.update(Message::Zoom(-10));
+        assert_eq!(model.zoom(), 1);
+    }
+
+    #[test]
+    fn viewport_follows_cursor() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::Move(Direction::Right, 7));
+        model.fit_viewport(Area::new(0, 0, 4, 4));
+        assert_eq!(*model.viewport(), Coords { x: 4, y: 0 });
+
+        // moving back within the view leaves it where it is
+        model.update(Message::Move(Direction::Left, 2));
+        model.fit_viewport(Area::new(0, 0, 4, 4));
+        assert_eq!(*model.viewport(), Coords { x: 4, y: 0 });
+
+        // panning carries the cursor along
+        model.update(Message::Pan(-2, 1));
+        model.fit_viewport(Area::new(0, 0, 4, 4));
+        assert_eq!(*model.viewport(), Coords { x: 2, y: 1 });
+        assert_eq!(*model.current_coords(), Coords { x: 3, y: 1 });
+
+        // a view larger than the board starts at the corner
+        model.fit_viewport(Area::new(0, 0, 20, 20));
+        assert_eq!(*model.viewport(), Coords { x: 0, y: 0 });
+    }
+
+    #[test]
+    fn set_cells_under_mouse() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::Zoom(1));
+        model.fit_viewport(Area::new(10, 5, 8, 8));
+        assert_eq!(model.cell_at(9, 5), None);
+        let coords = model.cell_at(13, 8).unwrap();
+        assert_eq!(coords, Coords { x: 1, y: 1 });
+
+        model.update(Message::SetCell(coords, true));
+        assert_eq!(model.is_alive(1, 1), Some(true));
+        model.update(Message::SetCell(coords, false));
+        assert_eq!(model.is_alive(1, 1), Some(false));
+
+        model.update(Message::ToggleEditing);
+        model.update(Message::SetCell(coords, true));
+        assert_eq!(model.is_alive(1, 1), Some(false));
+    }
+
+    #[test]
+    fn reopen_recent_run() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_rng(RngSource::seeded(5));
+        model.load_preset(Preset::Glider);
+        model.remember();
+
+        model.load_preset(Preset::Random);
+        model.rule = "B36/S23".parse::<Rule>().unwrap();
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+
+        model.update(Message::OpenRecent);
+        assert_eq!(model.picker_kind(), PickerKind::Recent);
+        model.update(Message::PickerSubmit);
+        assert_eq!(*model.state(), State::Running);
+        assert_eq!(model.generation(), 0);
+        assert_eq!(model.rulestring(), "B3/S23");
+        assert_eq!(model.rng().seed(), Some(5));
+        let live = model.cells().iter().flatten().filter(|cell| cell.is_alive);
+        assert_eq!(live.count(), 5);
+        assert_eq!(model.history().entries().len(), 1);
+    }
+
+    #[test]
+    fn roll_back_to_checkpoint() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_checkpoint_interval(2);
+        model.load_preset(Preset::Glider);
+        let start: Vec<bool> = model.cells().iter().flatten().map(|c| c.is_alive).collect();
+        model.update(Message::ToggleEditing);
+        for _ in 0..5 {
+            model.update(Message::Idle);
+        }
+        assert_eq!(model.generation(), 5);
+
+        model.update(Message::OpenCheckpoints);
+        assert_eq!(model.picker_kind(), PickerKind::Checkpoints);
+        model.update(Message::PickerMove(Direction::Down));
+        model.update(Message::PickerSubmit);
+        assert_eq!(model.generation(), 2);
+        let live = model.cells().iter().flatten().filter(|cell| cell.is_alive);
+        assert_eq!(live.count(), 5);
+
+        // the later checkpoint is gone, and the glider carries on from where it was
+        model.update(Message::OpenCheckpoints);
+        model.update(Message::PickerSubmit);
+        assert_eq!(model.generation(), 2);
+        model.update(Message::Idle);
+        model.update(Message::Idle);
+        let moved: Vec<bool> = model.cells().iter().flatten().map(|c| c.is_alive).collect();
+        assert_ne!(moved, start);
+    }
+
+    #[test]
+    fn history_rule_remembers_cells() {
+        let mut model = Model::new(4, 4, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.rule = "B3/S23History".parse::<Rule>().unwrap();
+        model.load_preset(Preset::Blinker);
+        model.update(Message::ToggleMark);
+        assert!(model.cell(0, 0).unwrap().marked);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+
+        // the blinker's ends died but are remembered, and the mark stays with its cell
+        let cell = model.cell(0, 1).unwrap();
+        assert!(!cell.is_alive && cell.ever_alive);
+        let marked = model.cell(0, 0).unwrap();
+        assert!(marked.marked && !marked.ever_alive);
+        assert!(!model.cell(3, 3).unwrap().ever_alive);
+
+        // only History rules keep the envelope or allow marks
+        let mut model = Model::new(4, 4, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        model.update(Message::ToggleMark);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert!(model
+            .cells()
+            .iter()
+            .flatten()
+            .all(|c| !c.ever_alive && !c.marked));
+    }
+
+    #[test]
+    fn journal_logs_each_run() {
+        let path = std::env::temp_dir()
+            .join(format!("tui-ca-run-journal-{}", std::process::id()))
+            .join("journal.tsv");
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_journal(Journal::new(path.clone()));
+        model.set_rng(RngSource::seeded(1));
+        model.load_preset(Preset::Blinker);
+        model.update(Message::ToggleEditing);
+        for _ in 0..4 {
+            model.update(Message::Idle);
         }
-    }
+        model.load_preset(Preset::Glider);
+        model.log_run().unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
 
-    fn set_cell(&mut self, y: usize, x: usize, val: bool) {
-        self.cells[y][x].is_alive = val;
+        // the glider never ran, so only the blinker is logged
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1].split_once('\t').unwrap().1,
+            "Blinker\tB3/S23\t1\t4\t3\tgen 0 period 2"
+        );
     }
 
-    fn toggle_current_cell(&mut self) {
-        let Coords { x: xp, y: yp } = self.current_coords();
-        let (x, y) = (*xp, *yp);
-        self.cells[y as usize][x as usize].is_alive = !self.cells[y as usize][x as usize].is_alive;
-    }
+    #[cfg(feature = "tui")]
+    #[test]
+    fn switch_workspace_boards() {
+        let path = std::env::temp_dir().join(format!("tui-ca-workspace-{}", std::process::id()));
+        std::fs::write(
+            &path,
+            "#N Blinker\nx = 3, y = 1, rule = B3/S23\n3o!\n#N Seeds\nx = 2, y = 1, rule = B2/S\n2o!\n",
+        )
+        .unwrap();
+        let cli = Cli::parse_from(["tui-ca", "--workspace", path.to_str().unwrap()]);
+        let config = Config::from_cli(&cli);
+        std::fs::remove_file(&path).unwrap();
+        assert!(config.errors.is_empty());
+        assert_eq!(config.preset.name(), "Blinker");
 
-    fn toggle_editing_state(&mut self) {
-        if self.state == State::Editing {
-            self.state = State::Running;
-        } else if self.state == State::Running {
-            self.state = State::Editing;
-        }
-    }
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_workspace(config.workspace);
+        model.update(Message::OpenWorkspace);
+        assert_eq!(model.picker_kind(), PickerKind::Boards);
+        model.update(Message::PickerInput('s'));
+        model.update(Message::PickerSubmit);
+        assert_eq!(model.rulestring(), "B2/S");
+        let live = model.cells().iter().flatten().filter(|cell| cell.is_alive);
+        assert_eq!(live.count(), 2);
 
-    fn quit(&mut self) {
-        self.state = State::Done
+        let cli = Cli::parse_from(["tui-ca", "--workspace", "/nonexistent/workspace.rle"]);
+        assert!(matches!(
+            Config::from_cli(&cli).errors[..],
+            [ConfigError::Workspace { .. }]
+        ));
     }
 
-    fn move_cursor_in_direction(&mut self, dir: Direction) {
-        match dir {
-            Direction::Up => self.move_cursor(0, -1),
-            Direction::Down => self.move_cursor(0, 1),
-            Direction::Left => self.move_cursor(-1, 0),
-            Direction::Right => self.move_cursor(1, 0),
-        }
+    #[test]
+    fn dismiss_errors_newest_first() {
+        let mut model = Model::new(3, 3, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.report(ErrorReport::message("first"));
+        model.report(ErrorReport::message("second"));
+        model.update(Message::DismissError);
+        assert_eq!(model.errors(), [ErrorReport::message("first")]);
+        model.update(Message::DismissError);
+        model.update(Message::DismissError);
+        assert!(model.errors().is_empty());
     }
 
-    fn move_cursor(&mut self, x_delta: i16, y_delta: i16) {
-        if self.state == State::Editing {
-            let temp_x = self.current_coords.x + x_delta;
-            if temp_x <= 0 {
-                self.current_coords.x = 0;
-            } else if temp_x >= self.max_coords.x {
-                self.current_coords.x = self.max_coords.x;
-            } else {
-                self.current_coords.x = temp_x;
-            }
-
-            let temp_y = self.current_coords.y + y_delta;
-            if temp_y <= 0 {
-                self.current_coords.y = 0;
-            } else if temp_y >= self.max_coords.y {
-                self.current_coords.y = self.max_coords.y;
-            } else {
-                self.current_coords.y = temp_y;
-            }
-        }
+    #[test]
+    fn debug_metrics() {
+        let mut model = Model::new(9, 4, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::ToggleDebug);
+        assert!(model.is_debug_overlay());
+        model.load_preset(Preset::Glider);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert_eq!(model.metrics().cells_evaluated, 50);
+        assert_eq!(model.engine(), "naive");
     }
-}
 
-impl Preset {
-    pub fn from(preset_string: &str) -> Preset {
-        let preset_string = preset_string.to_lowercase();
-        match &preset_string[..] {
-            "blinker" => Preset::Blinker,
-            "mold" => Preset::Mold,
-            "random" => Preset::Random,
-            "horizontalline" => Preset::HorizontalLine,
-            _ => Preset::Empty,
-        }
-    }
-}
+    #[test]
+    fn resize_keeps_cells() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Glider);
+        let alive = |model: &Model| -> Vec<(usize, usize)> {
+            let (width, height) = model.size();
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .filter(|&(x, y)| model.is_alive(x, y) == Some(true))
+                .collect()
+        };
+        let before = alive(&model);
 
-impl Rule {
-    pub fn from(rulestring: &str) -> Rule {
-        let mut in_born = false;
-        let mut in_survival = false;
+        model.resize(14, 13, Anchor::Center);
+        assert_eq!(model.size(), (14, 13));
+        let moved: Vec<_> = before.iter().map(|&(x, y)| (x + 2, y + 1)).collect();
+        assert_eq!(alive(&model), moved);
+        model.resize(10, 10, Anchor::Center);
+        assert_eq!(alive(&model), before);
+        model.resize(2, 2, Anchor::TopLeft);
+        let cropped: Vec<_> = before
+            .into_iter()
+            .filter(|&(x, y)| x < 2 && y < 2)
+            .collect();
+        assert_eq!(alive(&model), cropped);
 
-        let mut birth_list = vec![];
-        let mut survival_list = vec![];
-        for ch in rulestring.chars() {
-            if ch == 'B' {
-                in_survival = false;
-                in_born = true;
-            } else if ch == 'S' {
-                in_born = false;
-                in_survival = true;
-            } else if ch.is_alphabetic() {
-                return Rule::default();
-            }
-
-            if !in_born && !in_survival {
-                return Rule::default();
-            }
-
-            if ch.is_digit(10) {
-                if in_born {
-                    birth_list.push(ch.to_digit(10).unwrap() as u8);
-                } else if in_survival {
-                    survival_list.push(ch.to_digit(10).unwrap() as u8);
-                } else {
-                    return Rule::default();
-                }
-            }
+        // the dialog takes a size and an anchor, and leaves the board alone if it can't
+        model.update(Message::OpenResize);
+        assert_eq!(model.prompt().unwrap().buffer, "2x2");
+        for _ in 0..3 {
+            model.update(Message::PromptBackspace);
         }
-
-        Rule {
-            birth_list,
-            survival_list,
+        for ch in "20x8 top-left".chars() {
+            model.update(Message::PromptInput(ch));
         }
-    }
+        model.update(Message::PromptSubmit);
+        assert_eq!(*model.state(), State::Editing);
+        assert_eq!(model.size(), (20, 8));
+        assert_eq!(alive(&model), cropped);
+        assert_eq!(
+            parse_board_size("0x5").map(|_| ()),
+            Err(String::from(
+                "expected a board size such as 120x40, got \"0x5\""
+            ))
+        );
 
-    pub fn default() -> Rule {
-        Rule {
-            birth_list: vec![3],
-            survival_list: vec![2, 3],
-        }
-    }
-}
+        // conpty repeats resizes to the size the board already is, which change nothing
+        model.step_n(1);
+        let diff = model.last_diff().clone();
+        model.update(Message::Resize(20, 8, Anchor::TopLeft));
+        assert_eq!(*model.last_diff(), diff);
 
-impl Config {
-    pub fn build(preset_string: &str, rulestring: &str, tickrate: u16) -> Config {
-        Config {
-            preset: Preset::from(preset_string),
-            rule: Rule::from(rulestring),
-            tickrate,
-        }
+        // nothing makes a board smaller than Model::new can
+        model.resize(1, 0, Anchor::TopLeft);
+        assert_eq!(model.size(), (MIN_BOARD_SIZE, MIN_BOARD_SIZE));
+        assert!(parse_board_size("1x5").is_err());
     }
-}
 
-impl Cell {
-    pub fn new(state: bool) -> Cell {
-        Cell {
-            is_alive: state,
-            age: 0,
-        }
-    }
+    #[test]
+    fn soup_density_follows_rule() {
+        assert_eq!("B3/S23".parse::<Rule>().unwrap().soup_density(), 0.3);
+        assert_eq!("B2/S".parse::<Rule>().unwrap().soup_density(), 0.08);
+        assert_eq!("B3678/S34678".parse::<Rule>().unwrap().soup_density(), 0.5);
+        assert_eq!("B8763/S87643".parse::<Rule>().unwrap().soup_density(), 0.5);
+        assert_eq!("B12/S4".parse::<Rule>().unwrap().soup_density(), 0.1);
 
-    pub fn update(&mut self, state: bool) {
-        self.is_alive = state;
+        let mut model = Model::new(9, 9, vec![2], vec![], Duration::from_millis(50));
+        assert_eq!(model.density(), 0.08);
+        model.set_density(Some(1.0));
+        model.load_preset(Preset::Random);
+        assert!(model.cells().iter().flatten().all(|cell| cell.is_alive));
+        assert!(parse_density("1.5").is_err());
     }
 
-    pub fn vec_from(bool_cells: Vec<Vec<bool>>) -> Vec<Vec<Cell>> {
-        let mut outer = Vec::with_capacity(bool_cells.len());
-        for vector in bool_cells {
-            let mut inner = Vec::with_capacity(vector.len());
-            for cell in vector {
-                inner.push(Cell::new(cell));
-            }
-            outer.push(inner);
-        }
-        outer
+    #[test]
+    fn place_reports_collisions_and_clipping() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        let glider = Preset::Glider.pattern().unwrap();
+        let merge = |model: &mut Model, x, y| {
+            let at = Coords { x, y };
+            model.place(&glider, at, Transform::default(), CollisionPolicy::Merge)
+        };
+        assert_eq!(merge(&mut model, 0, 0), Ok(PlaceReport::default()));
+        // shares the cell at (2, 1) with the first glider
+        let report = merge(&mut model, 1, 1).unwrap();
+        assert_eq!(report.collisions, 1);
+        let report = merge(&mut model, 4, 4).unwrap();
+        assert_eq!(report.clipped, 4);
+        assert!(model.cell(5, 4).unwrap().is_alive);
     }
-}
 
-impl Clone for Cell {
-    fn clone(&self) -> Cell {
-        Cell {
-            is_alive: self.is_alive,
-            age: self.age,
+    #[test]
+    fn place_policies() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        let block = Pattern::from_rle("x = 2, y = 2\n2o$2o!").unwrap();
+        let glider = Preset::Glider.pattern().unwrap();
+        let origin = Coords { x: 0, y: 0 };
+        let place = |model: &mut Model, pattern: &Pattern, policy| {
+            model.place(pattern, origin, Transform::default(), policy)
+        };
+
+        place(&mut model, &block, CollisionPolicy::Overwrite).unwrap();
+        let before = alive_grid(&model);
+        // the glider's live cell at (1, 0) lands on the block
+        let report = place(&mut model, &glider, CollisionPolicy::Error).unwrap_err();
+        assert_eq!(report.collisions, 1);
+        assert_eq!(alive_grid(&model), before);
+
+        place(&mut model, &glider, CollisionPolicy::Merge).unwrap();
+        assert!(model.cell(0, 0).unwrap().is_alive);
+        place(&mut model, &glider, CollisionPolicy::Overwrite).unwrap();
+        assert!(!model.cell(0, 0).unwrap().is_alive);
+
+        let flipped = Transform {
+            flip_v: true,
+            ..Transform::default()
+        };
+        model
+            .place(&glider, origin, flipped, CollisionPolicy::Overwrite)
+            .unwrap();
+        for (y, line) in glider.transformed(flipped).cells.iter().enumerate() {
+            for (x, alive) in line.iter().enumerate() {
+                assert_eq!(model.is_alive(x, y), Some(*alive), "cell {x}, {y}");
+            }
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
+    #[cfg(feature = "tui")]
     #[test]
-    fn move_cursor() {
-        let mut model = Model::new(10, 10, vec![], vec![], 50);
-        model.move_cursor(-1, -4);
-        assert_eq!(Coords { x: 0, y: 0 }, *model.current_coords());
-        model.move_cursor(5, 6);
-        assert_eq!(Coords { x: 5, y: 6 }, *model.current_coords());
-        model.move_cursor(6, 6);
-        assert_eq!(Coords { x: 10, y: 10 }, *model.current_coords());
+    fn place_spec() {
+        let spec = parse_place("GosperGliderGun@3,-4,180").unwrap();
+        assert_eq!(spec.at, Coords { x: 3, y: -4 });
+        assert_eq!(spec.rotation, Rotation::Half);
+        assert_eq!(spec.to_string(), "GosperGliderGun@3,-4,180");
+        assert_eq!(parse_place("glider@1,2").unwrap().rotation, Rotation::None);
+        assert!(parse_place("glider").is_err());
+        assert!(parse_place("glider@1,2,45").is_err());
+
+        let cli = Cli::parse_from(["tui-ca", "--place", "glider@1,2", "--place", "nope@0,0"]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.places.len(), 1);
+        assert_eq!(config.places[0].1, Preset::Glider);
+        assert_eq!(config.errors.len(), 1);
     }
 
     #[test]
-    fn move_cursor_in_direction() {
-        let mut model = Model::new(10, 10, vec![], vec![], 50);
-        model.move_cursor_in_direction(Direction::Down);
-        assert_eq!(Coords { x: 0, y: 1 }, *model.current_coords());
-        model.move_cursor_in_direction(Direction::Right);
-        assert_eq!(Coords { x: 1, y: 1 }, *model.current_coords());
-        model.move_cursor_in_direction(Direction::Up);
-        assert_eq!(Coords { x: 1, y: 0 }, *model.current_coords());
-        model.move_cursor_in_direction(Direction::Left);
-        assert_eq!(Coords { x: 0, y: 0 }, *model.current_coords());
+    fn pick_pattern_at_cursor() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::Move(Direction::Down, 1));
+        model.update(Message::Move(Direction::Right, 1));
+        model.update(Message::OpenPicker);
+        assert!(matches!(model.state(), State::Picking { .. }));
+        for ch in "blnk".chars() {
+            model.update(Message::PickerInput(ch));
+        }
+        assert_eq!(model.picker().unwrap().selected(), Some("Blinker"));
+        model.update(Message::PickerSubmit);
+
+        assert_eq!(*model.state(), State::Editing);
+        assert!(model.picker().is_none());
+        let alive: Vec<_> = (0..6)
+            .flat_map(|y| (0..6).map(move |x| (x, y)))
+            .filter(|(x, y)| model.cell(*x, *y).unwrap().is_alive)
+            .collect();
+        assert_eq!(alive, vec![(1, 2), (2, 2), (3, 2)]);
     }
 
     #[test]
-    #[should_panic(expected = "Geometrically impossible birth")]
-    fn too_many_neighbors_birth() {
-        Model::new(10, 10, vec![1, 2, 9], vec![1, 2, 3], 50);
+    fn rulestring() {
+        let model = Model::new(3, 3, vec![2, 3, 5], vec![1, 7], Duration::from_millis(50));
+        assert_eq!(model.rulestring(), "B235/S17");
     }
 
     #[test]
-    #[should_panic(expected = "Geometrically impossible survival")]
-    fn too_many_neighbors_survival() {
-        Model::new(10, 10, vec![4, 4, 4], vec![9, 4, 4], 50);
+    fn rulestring_from() {
+        assert!("2983uhjnere".parse::<Rule>().is_err());
+        assert!("".parse::<Rule>().is_err());
+        assert!("History".parse::<Rule>().is_err());
+        assert_eq!(
+            "B3/S23!!".parse::<Rule>(),
+            Err(String::from("unexpected character '!'"))
+        );
+        assert_eq!(
+            "B3;S23".parse::<Rule>(),
+            Err(String::from("unexpected character ';'"))
+        );
+
+        let rule = "B45/S10".parse::<Rule>().unwrap();
+        let expected = Rule {
+            birth_list: vec![4, 5],
+            survival_list: vec![0, 1],
+            history: false,
+            second_order: false,
+        };
+
+        assert_eq!(rule, expected);
+
+        let rule = "B3/S23History".parse::<Rule>().unwrap();
+        assert!(rule.history);
+        assert_eq!(rule.birth_list, vec![3]);
+        assert_eq!(rule.to_string(), "B3/S23History");
+        assert!("B3/S23Histor".parse::<Rule>().is_err());
+
+        let rule = "B3/S23reversibleHISTORY".parse::<Rule>().unwrap();
+        assert!(rule.second_order && rule.history);
+        assert_eq!(rule.to_string(), "B3/S23ReversibleHistory");
+        assert!("B03/S23Reversible".parse::<Rule>().is_err());
+
+        // either notation, in any order, comes out canonical
+        let canonical = |rulestring: &str| rulestring.parse::<Rule>().map(|rule| rule.to_string());
+        assert_eq!(canonical("23/3"), Ok(String::from("B3/S23")));
+        assert_eq!(canonical("b63/s3223"), Ok(String::from("B36/S23")));
+        assert_eq!(canonical("/2History"), Ok(String::from("B2/SHistory")));
+        assert_eq!(canonical("23/36"), canonical("B63/S32"));
+        assert!(canonical("23/39").is_err());
+        assert!(canonical("3/S23").is_err());
     }
 
+    #[cfg(feature = "tui")]
     #[test]
-    #[should_panic(expected = "Max coords")]
-    fn max_x_too_small() {
-        Model::new(10, -1, vec![], vec![], 50);
+    fn config_from_cli_engine() {
+        let cli = Cli::parse_from(["tui-ca", "--engine", "smoothlife"]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(config.continuous.unwrap().name(), "smoothlife");
+
+        let cli = Cli::parse_from([
+            "tui-ca",
+            "--engine",
+            "smoothlife",
+            "--smoothlife-inner",
+            "9",
+        ]);
+        let config = Config::from_cli(&cli);
+        assert!(config.continuous.is_none());
+        assert_eq!(config.errors[0].input(), "smoothlife");
+
+        let cli = Cli::parse_from([
+            "tui-ca",
+            "--engine",
+            "totalistic",
+            "--totalistic-states",
+            "4",
+            "--totalistic-code",
+            "1024",
+        ]);
+        let config = Config::from_cli(&cli);
+        assert_eq!(
+            config.continuous.unwrap().to_string(),
+            "Totalistic k=4 code 1024"
+        );
+        assert!(Cli::try_parse_from(["tui-ca", "--totalistic-states", "9"]).is_err());
     }
 
     #[test]
-    #[should_panic(expected = "Max coords")]
-    fn max_y_too_small() {
-        Model::new(0, 10, vec![], vec![], 50);
+    fn weighted_kernel() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        let life = Kernel::parse("birth 3\nsurvival 2 3\n1 1 1\n1 0 1\n1 1 1\n").unwrap();
+        model.set_kernel(Some(life));
+        assert_eq!(model.engine(), "weighted");
+        let start = alive_grid(&model);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert_ne!(alive_grid(&model), start);
+        let after_life = alive_grid(&model);
+        model.update(Message::Idle);
+        assert_eq!(alive_grid(&model), start);
+
+        // doubling the orthogonal weights overcrowds the blinker's middle
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        let weighted = Kernel::parse("birth 3\nsurvival 2 3\n1 2 1\n2 0 2\n1 2 1\n").unwrap();
+        model.set_kernel(Some(weighted));
+        let middle = (1..9)
+            .flat_map(|y| (1..9).map(move |x| (x, y)))
+            .find(|&(x, y)| {
+                model.cell(x - 1, y).unwrap().is_alive
+                    && model.cell(x, y).unwrap().is_alive
+                    && model.cell(x + 1, y).unwrap().is_alive
+            })
+            .unwrap();
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert_ne!(alive_grid(&model), after_life);
+        assert!(!model.cell(middle.0, middle.1).unwrap().is_alive);
     }
 
     #[test]
-    fn toggle_current_cell() {
-        let mut model = Model::new(3, 3, vec![], vec![], 50);
-        model.move_cursor_in_direction(Direction::Down);
-        model.move_cursor_in_direction(Direction::Right);
-        model.update(Message::ToggleCellState);
-        assert_eq!(
-            Cell::vec_from(vec![
-                vec![false; 4],
-                vec![false, true, false, false],
-                vec![false; 4],
-                vec![false; 4]
-            ]),
-            *model.cells()
-        );
+    fn boundaries() {
+        let step_edge_blinker = |boundary| {
+            let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+            model.set_boundary(boundary);
+            for y in 4..=6 {
+                model.update(Message::SetCell(Coords { x: 0, y }, true));
+            }
+            model.update(Message::ToggleEditing);
+            model.update(Message::Idle);
+            (0..10)
+                .filter(|&x| model.cell(x, 5).unwrap().is_alive)
+                .collect::<Vec<_>>()
+        };
+        // the blinker's left end falls off the board
+        assert_eq!(step_edge_blinker(Boundary::Dead), [0, 1]);
+        assert_eq!(step_edge_blinker(Boundary::Wrap), [0, 1, 9]);
+        // the mirrored blinker beyond the edge crowds out the middle
+        assert_eq!(step_edge_blinker(Boundary::Mirror), [1]);
     }
 
     #[test]
-    fn toggle_editing_state() {
-        let mut model = Model::new(5, 5, vec![], vec![], 50);
+    fn tutorial_follows_actions() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_tutorial(Some(Tutorial::default()));
+        let done = |model: &Model| model.tutorial().unwrap().done();
+        // running before moving and drawing doesn't skip ahead
         model.update(Message::ToggleEditing);
-        assert_eq!(*model.state(), State::Running);
         model.update(Message::ToggleEditing);
-        assert_eq!(*model.state(), State::Editing);
+        assert_eq!(done(&model), 0);
+        model.update(Message::Move(Direction::Right, 1));
+        model.update(Message::SetCell(Coords { x: 2, y: 2 }, true));
+        model.update(Message::ToggleEditing);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Mutate);
+        assert_eq!(done(&model), 5);
+        model.learn(Lesson::Save);
+        assert!(model.tutorial().unwrap().is_finished());
     }
 
     #[test]
-    fn pass_tick_running_blinker() {
-        let mut model = Model::new(4, 4, vec![3], vec![2, 3], 50);
-        model.cells = Cell::vec_from(vec![
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-            vec![false, true, true, true, false],
-            vec![false, false, false, false, false],
-            vec![false, false, false, false, false],
-        ]);
+    fn accessible_announcements() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_accessible(true);
+        model.load_preset(Preset::Blinker);
+        assert!(model.status_line().starts_with("Cursor at column 0, row 0"));
+        model.update(Message::Describe);
+        assert!(model
+            .status_line()
+            .starts_with("Generation 0: 3 live cells in 1 group"));
         model.update(Message::ToggleEditing);
-        model.update(Message::Idle);
-        assert_eq!(
-            *model.cells(),
-            Cell::vec_from(vec![
-                vec![false, false, false, false, false],
-                vec![false, false, true, false, false],
-                vec![false, false, true, false, false],
-                vec![false, false, true, false, false],
-                vec![false, false, false, false, false],
-            ])
-        );
-        model.update(Message::Idle);
-        assert_eq!(
-            *model.cells(),
-            Cell::vec_from(vec![
-                vec![false, false, false, false, false],
-                vec![false, false, false, false, false],
-                vec![false, true, true, true, false],
-                vec![false, false, false, false, false],
-                vec![false, false, false, false, false],
-            ])
-        );
+        assert_eq!(model.status_line(), "Running | Generation 0, 3 alive.");
+        for _ in 0..4 {
+            model.update(Message::Idle);
+        }
+        assert!(model.status_line().starts_with("Settled at generation"));
+
+        // without the mode, descriptions are notices
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.update(Message::Describe);
+        assert_eq!(model.notice(), Some("Generation 0: The board is empty."));
     }
 
     #[test]
-    fn load_preset() {
-        let mut model = Model::new(4, 5, vec![3], vec![2, 3], 50);
+    fn mutate_rule() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_rng(RngSource::seeded(3));
+        model.update(Message::Mutate);
+        assert_ne!(model.rulestring(), "B3/S23");
+        assert_eq!(model.explorer().lineage(), ["B3/S23"]);
+
+        model.set_mutation_interval(Some(2));
         model.load_preset(Preset::Blinker);
-        assert_eq!(
-            *model.cells(),
-            Cell::vec_from(vec![
-                vec![false, false, false, false, false, false],
-                vec![true, true, true, false, false, false],
-                vec![false, false, false, false, false, false],
-                vec![false, false, false, false, false, false],
-                vec![false, false, false, false, false, false],
-            ])
-        );
         model.update(Message::ToggleEditing);
         model.update(Message::Idle);
-        assert_eq!(
-            *model.cells(),
-            Cell::vec_from(vec![
-                vec![false, true, false, false, false, false],
-                vec![false, true, false, false, false, false],
-                vec![false, true, false, false, false, false],
-                vec![false, false, false, false, false, false],
-                vec![false, false, false, false, false, false],
-            ])
-        );
+        assert_eq!(model.explorer().lineage().len(), 0);
+        model.update(Message::Idle);
+        assert_eq!(model.explorer().lineage().len(), 1);
     }
 
     #[test]
-    fn pass_tick_running_mold() {
-        let mut model = Model::new(5, 5, vec![3], vec![2, 3], 50);
-        model.cells = Cell::vec_from(vec![
-            vec![false, false, false, true, true, false],
-            vec![false, false, true, false, false, true],
-            vec![true, false, false, true, false, true],
-            vec![false, false, false, false, true, false],
-            vec![true, false, true, true, false, false],
-            vec![false, true, false, false, false, false],
-        ]);
+    fn transition_table() {
+        // every live cell moves one cell east: born with a live W neighbor, otherwise dead
+        let table = TransitionTable::parse("* ***1**** 1\n1 ******** 0\n").unwrap();
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_table(Some(table));
+        assert_eq!(model.engine(), "table");
+        model.update(Message::SetCell(Coords { x: 2, y: 4 }, true));
         model.update(Message::ToggleEditing);
         model.update(Message::Idle);
-        assert_eq!(
-            *model.cells(),
-            Cell::vec_from(vec![
-                vec![false, false, false, true, true, false],
-                vec![false, false, true, false, false, true],
-                vec![false, false, false, true, false, true],
-                vec![false, true, true, false, true, false],
-                vec![false, true, true, true, false, false],
-                vec![false, true, true, false, false, false],
-            ])
-        );
+        model.update(Message::Idle);
+        let alive: Vec<(usize, usize)> = (0..10)
+            .flat_map(|y| (0..10).map(move |x| (x, y)))
+            .filter(|&(x, y)| model.cell(x, y).unwrap().is_alive)
+            .collect();
+        assert_eq!(alive, vec![(4, 4)]);
     }
 
     #[test]
-    fn rulestring() {
-        let model = Model::new(3, 3, vec![2, 3, 5], vec![1, 7], 50);
-        assert_eq!(model.rulestring(), "B235/S17");
+    fn lenia_follows_field() {
+        let mut model = Model::new(29, 29, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_rng(RngSource::seeded(7));
+        model.set_continuous(Some(Continuous::Lenia(continuous::Lenia::new(
+            5, 0.15, 0.03, 0.1,
+        ))));
+        assert_eq!(model.engine(), "lenia");
+        model.load_preset(Preset::Random);
+        let states: Vec<f32> = (0..30)
+            .flat_map(|y| (0..30).map(move |x| (x, y)))
+            .filter_map(|(x, y)| model.intensity(x, y))
+            .collect();
+        assert_eq!(states.len(), 900);
+        assert!(states.iter().any(|state| *state > 0.0 && *state < 1.0));
+
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        for (y, line) in model.cells().iter().enumerate() {
+            for (x, cell) in line.iter().enumerate() {
+                let state = model.intensity(x, y).unwrap();
+                assert_eq!(cell.is_alive, state >= continuous::ALIVE);
+            }
+        }
+
+        // a cell edited by hand overrides its state
+        model.update(Message::ToggleEditing);
+        let alive = model.cell(0, 0).unwrap().is_alive;
+        model.update(Message::ToggleCellState);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert_eq!(model.generation(), 2);
+        assert_ne!(model.cell(0, 0).unwrap().is_alive, alive);
     }
 
     #[test]
-    fn rulestring_from() {
-        let rule = Rule::from("2983uhjnere");
-        assert_eq!(Rule::default(), rule);
+    fn step_back_reversible_rule() {
+        let mut model = Model::new(12, 12, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.rule = "B3/S23Reversible".parse::<Rule>().unwrap();
+        model.load_preset(Preset::Glider);
+        let start = alive_grid(&model);
+        model.update(Message::ToggleEditing);
+        let mut boards = vec![start.clone()];
+        for _ in 0..6 {
+            model.update(Message::Idle);
+            boards.push(alive_grid(&model));
+        }
+        assert_ne!(boards[6], start);
 
-        let rule = Rule::from("B45/S10");
-        let expected = Rule {
-            birth_list: vec![4, 5],
-            survival_list: vec![1, 0],
-        };
+        model.update(Message::ToggleEditing);
+        for generation in (0..6).rev() {
+            model.update(Message::StepBack);
+            assert_eq!(model.generation(), generation);
+            assert_eq!(alive_grid(&model), boards[generation as usize]);
+        }
+        model.update(Message::StepBack);
+        assert_eq!(model.notice(), Some("Already at generation 0"));
 
-        assert_eq!(rule, expected);
+        // stepping forwards again from the start gives the same boards
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        assert_eq!(alive_grid(&model), boards[1]);
     }
 }
@@ -0,0 +1,129 @@
+use std::fmt;
+
+/// What the neighborhoods of cells along the edges see beyond the board, chosen with
+/// `--boundary`. Under B0 rules, dead means the background and alive its opposite, whichever
+/// phase the background is in. Continuous engines always see dead cells beyond the edges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Boundary {
+    /// Cells beyond the edges are dead, as if the board were surrounded by empty space.
+    #[default]
+    Dead,
+    /// Cells beyond the edges are alive.
+    Alive,
+    /// Cells beyond an edge mirror the cells inside it, as if the edge were a mirror.
+    Mirror,
+    /// The board wraps around, so the cells beyond an edge are those along the opposite one.
+    Wrap,
+}
+
+impl Boundary {
+    pub fn parse(input: &str) -> Result<Boundary, String> {
+        match input.trim().to_lowercase().as_str() {
+            "dead" => Ok(Boundary::Dead),
+            "alive" => Ok(Boundary::Alive),
+            "mirror" => Ok(Boundary::Mirror),
+            "wrap" => Ok(Boundary::Wrap),
+            _ => Err(format!(
+                "unknown boundary \"{input}\", expected dead, alive, mirror or wrap"
+            )),
+        }
+    }
+
+    /// `board` surrounded by `margin` cells on every side, filled in as this boundary says, so
+    /// a neighborhood reaching up to `margin` cells can be read without bounds checks. The cell
+    /// at `y`, `x` of `board` is at `y + margin`, `x + margin` of the result.
+    pub fn pad(&self, board: &[Vec<bool>], margin: usize) -> Vec<Vec<bool>> {
+        let height = board.len();
+        let width = board.first().map_or(0, Vec::len);
+        let margin = margin as isize;
+        (-margin..height as isize + margin)
+            .map(|y| {
+                (-margin..width as isize + margin)
+                    .map(|x| match self.resolve(y, height, x, width) {
+                        Some((y, x)) => board[y][x],
+                        None => *self == Boundary::Alive,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The cell of a `height` by `width` board that stands in at `y`, `x`, or `None` if it's a
+    /// fixed dead or alive cell beyond the edges.
+    fn resolve(&self, y: isize, height: usize, x: isize, width: usize) -> Option<(usize, usize)> {
+        let inside = (0..height as isize).contains(&y) && (0..width as isize).contains(&x);
+        if inside {
+            return Some((y as usize, x as usize));
+        }
+        match self {
+            Boundary::Dead | Boundary::Alive => None,
+            Boundary::Mirror => Some((mirror(y, height), mirror(x, width))),
+            Boundary::Wrap => Some((
+                y.rem_euclid(height as isize) as usize,
+                x.rem_euclid(width as isize) as usize,
+            )),
+        }
+    }
+}
+
+/// `index` reflected back into `0..len`, repeatedly if it's more than `len` beyond an edge.
+fn mirror(index: isize, len: usize) -> usize {
+    let len = len as isize;
+    let folded = index.rem_euclid(2 * len);
+    (if folded < len {
+        folded
+    } else {
+        2 * len - 1 - folded
+    }) as usize
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Boundary::Dead => "dead",
+            Boundary::Alive => "alive",
+            Boundary::Mirror => "mirror",
+            Boundary::Wrap => "wrap",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad() {
+        let board = vec![vec![true, false, false], vec![false, false, true]];
+        let dead = Boundary::Dead.pad(&board, 1);
+        assert_eq!(dead.len(), 4);
+        assert_eq!(dead[0], vec![false; 5]);
+        assert_eq!(dead[1], vec![false, true, false, false, false]);
+
+        let alive = Boundary::Alive.pad(&board, 1);
+        assert_eq!(alive[0], vec![true; 5]);
+        assert_eq!(alive[2], vec![true, false, false, true, true]);
+
+        let mirror = Boundary::Mirror.pad(&board, 1);
+        assert_eq!(mirror[0], vec![true, true, false, false, false]);
+        assert_eq!(mirror[3], vec![false, false, false, true, true]);
+
+        let wrap = Boundary::Wrap.pad(&board, 1);
+        assert_eq!(wrap[0], vec![true, false, false, true, false]);
+        assert_eq!(wrap[1], vec![false, true, false, false, true]);
+
+        // a margin wider than the board reflects back and forth
+        assert_eq!(
+            mirror_row(&[true, false], 3),
+            [false, false, true, true, false, false, true, true]
+        );
+
+        assert_eq!(Boundary::parse("Mirror"), Ok(Boundary::Mirror));
+        assert!(Boundary::parse("bouncy").is_err());
+    }
+
+    fn mirror_row(row: &[bool], margin: usize) -> Vec<bool> {
+        Boundary::Mirror.pad(&[row.to_vec()], margin)[margin].clone()
+    }
+}
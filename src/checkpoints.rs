@@ -0,0 +1,105 @@
+use std::collections::VecDeque;
+
+/// Checkpoints kept before the oldest are dropped.
+const MAX_CHECKPOINTS: usize = 50;
+
+/// Generations between checkpoints unless `--checkpoint-every` says otherwise.
+pub const DEFAULT_INTERVAL: u64 = 100;
+
+/// A copy of the board taken while it ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub generation: u64,
+    pub population: usize,
+    /// Which cells were really alive, row by row.
+    pub cells: Vec<Vec<bool>>,
+}
+
+impl Checkpoint {
+    /// How the checkpoint is listed in the rollback menu.
+    pub fn label(&self) -> String {
+        format!("gen {} ({} alive)", self.generation, self.population)
+    }
+}
+
+/// Copies of the board taken every few generations, to roll back to. Only the most recent
+/// [`MAX_CHECKPOINTS`] are kept.
+#[derive(Debug)]
+pub struct Checkpoints {
+    /// Generations between checkpoints; 0 turns them off.
+    interval: u64,
+    checkpoints: VecDeque<Checkpoint>,
+}
+
+impl Default for Checkpoints {
+    fn default() -> Checkpoints {
+        Checkpoints::new(DEFAULT_INTERVAL)
+    }
+}
+
+impl Checkpoints {
+    pub fn new(interval: u64) -> Checkpoints {
+        Checkpoints {
+            interval,
+            checkpoints: VecDeque::new(),
+        }
+    }
+
+    /// Whether the board should be copied on reaching `generation`.
+    pub fn is_due(&self, generation: u64) -> bool {
+        self.interval > 0 && generation.is_multiple_of(self.interval)
+    }
+
+    pub fn record(&mut self, checkpoint: Checkpoint) {
+        if self.checkpoints.len() == MAX_CHECKPOINTS {
+            self.checkpoints.pop_front();
+        }
+        self.checkpoints.push_back(checkpoint);
+    }
+
+    pub fn clear(&mut self) {
+        self.checkpoints.clear();
+    }
+
+    /// Checkpoints, newest first.
+    pub fn newest_first(&self) -> impl Iterator<Item = &Checkpoint> {
+        self.checkpoints.iter().rev()
+    }
+
+    /// Drops the checkpoints taken after `generation`, which a rollback makes unreachable.
+    pub fn drop_after(&mut self, generation: u64) {
+        self.checkpoints
+            .retain(|checkpoint| checkpoint.generation <= generation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(generation: u64) -> Checkpoint {
+        Checkpoint {
+            generation,
+            population: 0,
+            cells: vec![],
+        }
+    }
+
+    #[test]
+    fn keeps_recent_checkpoints() {
+        let mut checkpoints = Checkpoints::new(10);
+        assert!(checkpoints.is_due(30));
+        assert!(!checkpoints.is_due(35));
+        assert!(!Checkpoints::new(0).is_due(30));
+
+        for generation in 1..=60 {
+            checkpoints.record(checkpoint(generation * 10));
+        }
+        assert_eq!(checkpoints.newest_first().count(), MAX_CHECKPOINTS);
+        assert_eq!(checkpoints.newest_first().last().unwrap().generation, 110);
+
+        checkpoints.drop_after(300);
+        assert_eq!(checkpoints.newest_first().next().unwrap().generation, 300);
+        assert_eq!(checkpoint(300).label(), "gen 300 (0 alive)");
+    }
+}
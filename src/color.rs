@@ -0,0 +1,218 @@
+use std::env;
+
+#[cfg(feature = "tui")]
+use ratatui::style::Color;
+
+/// How many colors the terminal can show. RGB colors are approximated to fit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    /// The xterm 256 color palette.
+    Ansi256,
+    /// The 16 basic ANSI colors, which terminals are free to restyle.
+    Ansi16,
+}
+
+/// Typical RGB values of the 16 ANSI colors, in index order, used to pick the closest one.
+#[cfg(feature = "tui")]
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// The channel values of the 6x6x6 color cube in the 256 color palette.
+#[cfg(feature = "tui")]
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    pub fn parse(input: &str) -> Result<ColorDepth, String> {
+        match input.trim().to_lowercase().as_str() {
+            "truecolor" | "24bit" => Ok(ColorDepth::TrueColor),
+            "256" => Ok(ColorDepth::Ansi256),
+            "16" => Ok(ColorDepth::Ansi16),
+            _ => Err(format!(
+                "unknown color depth \"{input}\", expected truecolor, 256 or 16"
+            )),
+        }
+    }
+
+    /// Guesses the terminal's color depth from `$COLORTERM` and `$TERM`, or `$WT_SESSION` for
+    /// Windows Terminal, which has truecolor but sets neither.
+    pub fn detect() -> ColorDepth {
+        ColorDepth::from_env(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+            env::var_os("WT_SESSION").is_some(),
+        )
+    }
+
+    fn from_env(colorterm: Option<&str>, term: Option<&str>, windows_terminal: bool) -> ColorDepth {
+        if matches!(colorterm, Some("truecolor" | "24bit")) || windows_terminal {
+            return ColorDepth::TrueColor;
+        }
+        match term {
+            Some(term) if term.contains("truecolor") || term.contains("direct") => {
+                ColorDepth::TrueColor
+            }
+            Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+
+    /// The closest color to `(red, green, blue)` that the terminal can show.
+    #[cfg(feature = "tui")]
+    pub fn approximate(&self, (red, green, blue): (u8, u8, u8)) -> Color {
+        match self {
+            ColorDepth::TrueColor => Color::Rgb(red, green, blue),
+            ColorDepth::Ansi256 => {
+                let cube_index = |value: u8| {
+                    (0..CUBE_LEVELS.len())
+                        .min_by_key(|i| CUBE_LEVELS[*i].abs_diff(value))
+                        .unwrap() as u8
+                };
+                let (r, g, b) = (cube_index(red), cube_index(green), cube_index(blue));
+                let cube = (
+                    CUBE_LEVELS[r as usize],
+                    CUBE_LEVELS[g as usize],
+                    CUBE_LEVELS[b as usize],
+                );
+
+                // the grayscale ramp is finer than the cube's gray diagonal
+                let average = (red as u16 + green as u16 + blue as u16) / 3;
+                let gray_step = ((average.saturating_sub(3)) / 10).min(23) as u8;
+                let gray_level = 8 + 10 * gray_step;
+
+                if distance((red, green, blue), (gray_level, gray_level, gray_level))
+                    < distance((red, green, blue), cube)
+                {
+                    Color::Indexed(232 + gray_step)
+                } else {
+                    Color::Indexed(16 + 36 * r + 6 * g + b)
+                }
+            }
+            ColorDepth::Ansi16 => {
+                ANSI16
+                    .iter()
+                    .min_by_key(|(_, rgb)| distance((red, green, blue), *rgb))
+                    .unwrap()
+                    .0
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+/// The red, green and blue `color` typically shows as, or `None` for the terminal's default.
+#[cfg(feature = "tui")]
+pub fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb(red, green, blue) => Some((red, green, blue)),
+        Color::Indexed(index @ 0..=15) => Some(ANSI16[index as usize].1),
+        Color::Indexed(index @ 16..=231) => {
+            let index = index - 16;
+            let level = |i: u8| CUBE_LEVELS[i as usize];
+            Some((level(index / 36), level(index / 6 % 6), level(index % 6)))
+        }
+        Color::Indexed(index) => {
+            let gray = 8 + 10 * (index - 232);
+            Some((gray, gray, gray))
+        }
+        named => ANSI16
+            .iter()
+            .find(|(ansi, _)| *ansi == named)
+            .map(|(_, rgb)| *rgb),
+    }
+}
+
+/// The color `t` of the way from `from` to `to`, with `t` from 0 to 1.
+pub fn blend(from: (u8, u8, u8), to: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_from_env() {
+        assert_eq!(
+            ColorDepth::from_env(Some("truecolor"), Some("xterm"), false),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("xterm-256color"), false),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("linux"), false),
+            ColorDepth::Ansi16
+        );
+        assert_eq!(ColorDepth::from_env(None, None, false), ColorDepth::Ansi16);
+        assert_eq!(
+            ColorDepth::from_env(None, None, true),
+            ColorDepth::TrueColor
+        );
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn approximate() {
+        assert_eq!(
+            ColorDepth::TrueColor.approximate((1, 2, 3)),
+            Color::Rgb(1, 2, 3)
+        );
+        assert_eq!(
+            ColorDepth::Ansi256.approximate((255, 0, 0)),
+            Color::Indexed(196)
+        );
+        assert_eq!(
+            ColorDepth::Ansi256.approximate((128, 128, 128)),
+            Color::Indexed(244)
+        );
+        assert_eq!(
+            ColorDepth::Ansi16.approximate((250, 10, 5)),
+            Color::LightRed
+        );
+        assert_eq!(ColorDepth::Ansi16.approximate((0, 180, 20)), Color::Green);
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn rgb_of_colors() {
+        assert_eq!(to_rgb(Color::Reset), None);
+        assert_eq!(to_rgb(Color::LightRed), Some((255, 0, 0)));
+        assert_eq!(to_rgb(Color::Indexed(196)), Some((255, 0, 0)));
+        assert_eq!(to_rgb(Color::Indexed(244)), Some((128, 128, 128)));
+        assert_eq!(to_rgb(Color::Rgb(1, 2, 3)), Some((1, 2, 3)));
+    }
+
+    #[test]
+    fn blends() {
+        assert_eq!(blend((0, 0, 0), (200, 100, 50), 0.0), (0, 0, 0));
+        assert_eq!(blend((0, 0, 0), (200, 100, 50), 0.5), (100, 50, 25));
+        assert_eq!(blend((0, 0, 0), (200, 100, 50), 2.0), (200, 100, 50));
+    }
+}
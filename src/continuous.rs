@@ -0,0 +1,539 @@
+use std::fmt;
+
+use rand::Rng;
+
+/// The state from which a cell of a continuous automaton counts as alive, for the population,
+/// period detection and anything else that sees the board as live and dead cells.
+pub const ALIVE: f32 = 0.1;
+
+/// The state of every cell of a continuous automaton, from 0 to 1, indexed by row then column
+/// like the board. Cells beyond the edges count as 0.
+pub type Field = Vec<Vec<f32>>;
+
+/// An automaton whose cells have continuous states, or more states than alive and dead,
+/// stepped in place of a [`Rule`].
+///
+/// [`Rule`]: crate::app::Rule
+#[derive(Debug, Clone, PartialEq)]
+pub enum Continuous {
+    Lenia(Lenia),
+    SmoothLife(SmoothLife),
+    Totalistic(Totalistic),
+}
+
+impl Continuous {
+    /// The name of the engine, for the debug overlay.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Continuous::Lenia(_) => "lenia",
+            Continuous::SmoothLife(_) => "smoothlife",
+            Continuous::Totalistic(_) => "totalistic",
+        }
+    }
+
+    /// A random soup density that gives the automaton enough to work with.
+    pub fn soup_density(&self) -> f64 {
+        match self {
+            Continuous::Lenia(_) => 0.3,
+            Continuous::SmoothLife(_) => 0.5,
+            Continuous::Totalistic(_) => 0.5,
+        }
+    }
+
+    /// A `width` by `height` field of randomly placed squares as wide as the automaton's
+    /// neighborhood, covering about `density` of it. Single cells are too small for Lenia or
+    /// SmoothLife to grow from, so this stands in for [`soup`](crate::soup::soup); totalistic
+    /// soups are of single cells in random live states.
+    pub fn soup(&self, rng: &mut impl Rng, width: usize, height: usize, density: f64) -> Field {
+        let side = match self {
+            Continuous::Lenia(lenia) => lenia.radius,
+            Continuous::SmoothLife(smooth_life) => smooth_life.outer_radius,
+            Continuous::Totalistic(_) => 1,
+        } as usize;
+        let mut field = vec![vec![0.0; width]; height];
+        let squares = (density * (width * height) as f64 / (side * side) as f64).round() as usize;
+        for _ in 0..squares {
+            let (left, top) = (rng.gen_range(0..width), rng.gen_range(0..height));
+            for line in field.iter_mut().skip(top).take(side) {
+                for state in line.iter_mut().skip(left).take(side) {
+                    *state = match self {
+                        Continuous::Lenia(_) => rng.gen_range(0.0..1.0),
+                        Continuous::SmoothLife(_) => 1.0,
+                        Continuous::Totalistic(totalistic) => {
+                            totalistic.value(rng.gen_range(1..totalistic.states))
+                        }
+                    };
+                }
+            }
+        }
+        field
+    }
+
+    /// The field a generation after `field`.
+    pub fn step(&self, field: &Field) -> Field {
+        match self {
+            Continuous::Lenia(lenia) => lenia.step(field),
+            Continuous::SmoothLife(smooth_life) => smooth_life.step(field),
+            Continuous::Totalistic(totalistic) => totalistic.step(field),
+        }
+    }
+}
+
+impl fmt::Display for Continuous {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Continuous::Lenia(lenia) => write!(
+                f,
+                "Lenia R={} μ={} σ={} dt={}",
+                lenia.radius, lenia.mu, lenia.sigma, lenia.dt
+            ),
+            Continuous::SmoothLife(smooth_life) => write!(
+                f,
+                "SmoothLife r={} R={} birth {}-{} death {}-{}",
+                smooth_life.inner_radius,
+                smooth_life.outer_radius,
+                smooth_life.birth.0,
+                smooth_life.birth.1,
+                smooth_life.death.0,
+                smooth_life.death.1
+            ),
+            Continuous::Totalistic(totalistic) => write!(
+                f,
+                "Totalistic k={} code {}",
+                totalistic.states, totalistic.code
+            ),
+        }
+    }
+}
+
+/// The continuous automata the engine can be switched to from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineKind {
+    /// The rulestring's Life-like rule.
+    Life,
+    Lenia,
+    SmoothLife,
+    Totalistic,
+}
+
+impl EngineKind {
+    pub fn parse(input: &str) -> Result<EngineKind, String> {
+        match input.trim().to_lowercase().as_str() {
+            "life" => Ok(EngineKind::Life),
+            "lenia" => Ok(EngineKind::Lenia),
+            "smoothlife" => Ok(EngineKind::SmoothLife),
+            "totalistic" => Ok(EngineKind::Totalistic),
+            _ => Err(format!(
+                "unknown engine \"{input}\", expected life, lenia, smoothlife or totalistic"
+            )),
+        }
+    }
+}
+
+/// Parses a number greater than 0, for the continuous engines' parameters.
+pub fn parse_positive(input: &str) -> Result<f32, String> {
+    match input.trim().parse::<f32>() {
+        Ok(value) if value.is_finite() && value > 0.0 => Ok(value),
+        _ => Err(format!("expected a number greater than 0, got \"{input}\"")),
+    }
+}
+
+/// Parses an interval of fills written `LOW,HIGH`, both between 0 and 1.
+pub fn parse_interval(input: &str) -> Result<(f32, f32), String> {
+    let (low, high) = input
+        .split_once(',')
+        .ok_or_else(|| format!("expected LOW,HIGH, got \"{input}\""))?;
+    let parse = |value: &str| {
+        value
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|value| (0.0..=1.0).contains(value))
+            .ok_or_else(|| format!("\"{value}\" is not a number from 0 to 1"))
+    };
+    let (low, high) = (parse(low)?, parse(high)?);
+    if low >= high {
+        return Err(format!("{low} must be below {high}"));
+    }
+    Ok((low, high))
+}
+
+/// Bert Chan's Lenia: every generation, each cell's state moves by `dt` times the growth
+/// function of a weighted average of the states around it, the weights forming a smooth ring
+/// of the given radius.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lenia {
+    pub radius: u16,
+    /// The neighborhood average at which cells grow fastest.
+    pub mu: f32,
+    /// How far from `mu` the average can be before cells shrink instead.
+    pub sigma: f32,
+    /// The fraction of the growth applied each generation.
+    pub dt: f32,
+    /// The nonzero weights of the ring, as row and column offsets, summing to 1.
+    kernel: Vec<(isize, isize, f32)>,
+}
+
+impl Lenia {
+    pub const DEFAULT_RADIUS: u16 = 10;
+    pub const DEFAULT_MU: f32 = 0.15;
+    pub const DEFAULT_SIGMA: f32 = 0.015;
+    pub const DEFAULT_DT: f32 = 0.1;
+
+    pub fn new(radius: u16, mu: f32, sigma: f32, dt: f32) -> Lenia {
+        let radius_f = radius as f32;
+        let reach = radius as isize;
+        let mut kernel = vec![];
+        for dy in -reach..=reach {
+            for dx in -reach..=reach {
+                let r = ((dx * dx + dy * dy) as f32).sqrt() / radius_f;
+                // the exponential bump, 1 halfway out and falling smoothly to 0 at both ends
+                if r > 0.0 && r < 1.0 {
+                    let weight = (4.0 - 1.0 / (r * (1.0 - r))).exp();
+                    if weight > f32::EPSILON {
+                        kernel.push((dy, dx, weight));
+                    }
+                }
+            }
+        }
+        let total: f32 = kernel.iter().map(|(_, _, weight)| weight).sum();
+        for (_, _, weight) in &mut kernel {
+            *weight /= total;
+        }
+
+        Lenia {
+            radius,
+            mu,
+            sigma,
+            dt,
+            kernel,
+        }
+    }
+
+    /// From -1 for averages far from `mu` up to 1 at `mu`.
+    fn growth(&self, average: f32) -> f32 {
+        let distance = (average - self.mu) / self.sigma;
+        2.0 * (-distance * distance / 2.0).exp() - 1.0
+    }
+
+    fn step(&self, field: &Field) -> Field {
+        convolve(field, &self.kernel)
+            .into_iter()
+            .zip(field)
+            .map(|(averages, line)| {
+                averages
+                    .into_iter()
+                    .zip(line)
+                    .map(|(average, state)| {
+                        (state + self.dt * self.growth(average)).clamp(0.0, 1.0)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Stephan Rafler's SmoothLife, stepped a whole generation at a time: each cell looks at how
+/// full the disk of `inner_radius` around it is, which decides whether it's alive, and how
+/// full the ring out to `outer_radius` is, which it needs to be within `birth` to be born or
+/// within `death` to survive. The edges of both and of the intervals are smoothed, so the
+/// state varies continuously.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmoothLife {
+    pub inner_radius: u16,
+    pub outer_radius: u16,
+    /// The ring fills at which dead cells are born.
+    pub birth: (f32, f32),
+    /// The ring fills at which live cells survive, called the death interval in the paper.
+    pub death: (f32, f32),
+    disk: Vec<(isize, isize, f32)>,
+    ring: Vec<(isize, isize, f32)>,
+}
+
+impl SmoothLife {
+    pub const DEFAULT_INNER_RADIUS: u16 = 3;
+    pub const DEFAULT_OUTER_RADIUS: u16 = 9;
+    /// How gradually a ring fill crosses into and out of the intervals.
+    const RING_SMOOTHING: f32 = 0.028;
+    /// How gradually a disk fill turns a cell from dead to alive.
+    const DISK_SMOOTHING: f32 = 0.147;
+
+    /// Fails unless the disk is smaller than the ring around it.
+    pub fn new(
+        inner_radius: u16,
+        outer_radius: u16,
+        birth: (f32, f32),
+        death: (f32, f32),
+    ) -> Result<SmoothLife, String> {
+        if inner_radius >= outer_radius {
+            return Err(format!(
+                "the inner radius {inner_radius} must be smaller than the outer radius {outer_radius}"
+            ));
+        }
+
+        // cells straddling a boundary are counted by how far they're inside it
+        let coverage = |radius: f32, distance: f32| (radius + 0.5 - distance).clamp(0.0, 1.0);
+        let reach = outer_radius as isize + 1;
+        let (mut disk, mut ring) = (vec![], vec![]);
+        for dy in -reach..=reach {
+            for dx in -reach..=reach {
+                let distance = ((dx * dx + dy * dy) as f32).sqrt();
+                let inner = coverage(inner_radius as f32, distance);
+                let outer = coverage(outer_radius as f32, distance) - inner;
+                if inner > 0.0 {
+                    disk.push((dy, dx, inner));
+                }
+                if outer > 0.0 {
+                    ring.push((dy, dx, outer));
+                }
+            }
+        }
+        for kernel in [&mut disk, &mut ring] {
+            let total: f32 = kernel.iter().map(|(_, _, weight)| weight).sum();
+            for (_, _, weight) in kernel.iter_mut() {
+                *weight /= total;
+            }
+        }
+
+        Ok(SmoothLife {
+            inner_radius,
+            outer_radius,
+            birth,
+            death,
+            disk,
+            ring,
+        })
+    }
+
+    /// The next state of a cell whose disk is `disk` full and ring `ring` full.
+    fn transition(&self, disk: f32, ring: f32) -> f32 {
+        let sigmoid =
+            |x: f32, a: f32, smoothing: f32| 1.0 / (1.0 + (-(x - a) * 4.0 / smoothing).exp());
+        // how alive the cell is, blending the birth and death intervals
+        let alive = sigmoid(disk, 0.5, SmoothLife::DISK_SMOOTHING);
+        let mix = |birth: f32, death: f32| birth * (1.0 - alive) + death * alive;
+        let (low, high) = (
+            mix(self.birth.0, self.death.0),
+            mix(self.birth.1, self.death.1),
+        );
+        sigmoid(ring, low, SmoothLife::RING_SMOOTHING)
+            * (1.0 - sigmoid(ring, high, SmoothLife::RING_SMOOTHING))
+    }
+
+    fn step(&self, field: &Field) -> Field {
+        convolve(field, &self.disk)
+            .into_iter()
+            .zip(convolve(field, &self.ring))
+            .map(|(disks, rings)| {
+                disks
+                    .into_iter()
+                    .zip(rings)
+                    .map(|(disk, ring)| self.transition(disk, ring).clamp(0.0, 1.0))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// A k-state totalistic automaton given by a code number, as Wolfram numbers them: a cell's
+/// next state depends only on the sum of its own and its eight neighbors' states, and digit
+/// `n` of the code written in base k, counting from the least significant, is the next state
+/// for a sum of `n`. State `s` is stored in the field as `s / (k - 1)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Totalistic {
+    /// The number of states k, from 2 to [`Totalistic::MAX_STATES`].
+    pub states: u8,
+    /// The code number in decimal, as given.
+    pub code: String,
+    /// The next state for each sum, the code's base-k digits from the least significant.
+    next: Vec<u8>,
+}
+
+impl Totalistic {
+    pub const DEFAULT_STATES: u8 = 3;
+    pub const DEFAULT_CODE: &'static str = "19848";
+    /// The most states, which keeps state 1 above [`ALIVE`].
+    pub const MAX_STATES: u8 = 8;
+
+    /// Fails if the code has digits for sums past the largest possible, `9 * (k - 1)`, or
+    /// would turn an empty neighborhood into a live cell.
+    pub fn new(states: u8, code: &str) -> Result<Totalistic, String> {
+        if !(2..=Totalistic::MAX_STATES).contains(&states) {
+            return Err(format!(
+                "{states} states is not from 2 to {}",
+                Totalistic::MAX_STATES
+            ));
+        }
+        let mut decimal = code
+            .trim()
+            .chars()
+            .map(|ch| ch.to_digit(10).map(|digit| digit as u8))
+            .collect::<Option<Vec<u8>>>()
+            .filter(|digits| !digits.is_empty())
+            .ok_or_else(|| format!("the code \"{code}\" is not a whole number"))?;
+
+        // long division by k, collecting the remainders
+        let mut next = vec![];
+        while decimal.iter().any(|digit| *digit > 0) {
+            let mut remainder = 0;
+            for digit in decimal.iter_mut() {
+                let value = remainder * 10 + u32::from(*digit);
+                *digit = (value / u32::from(states)) as u8;
+                remainder = value % u32::from(states);
+            }
+            next.push(remainder as u8);
+        }
+
+        let sums = 9 * (usize::from(states) - 1) + 1;
+        if next.len() > sums {
+            return Err(format!(
+                "the code has {} base-{states} digits, but there are only {sums} sums",
+                next.len()
+            ));
+        }
+        if next.first().is_some_and(|state| *state > 0) {
+            return Err(String::from(
+                "the code must be a multiple of k, or empty neighborhoods come alive",
+            ));
+        }
+        Ok(Totalistic {
+            states,
+            code: code.trim().to_string(),
+            next,
+        })
+    }
+
+    /// The field value of `state`.
+    fn value(&self, state: u8) -> f32 {
+        f32::from(state) / f32::from(self.states - 1)
+    }
+
+    /// The state a field value stands for.
+    fn state(&self, value: f32) -> u8 {
+        (value * f32::from(self.states - 1)).round() as u8
+    }
+
+    fn step(&self, field: &Field) -> Field {
+        let height = field.len();
+        (0..height)
+            .map(|y| {
+                let width = field[y].len();
+                (0..width)
+                    .map(|x| {
+                        let mut sum = 0;
+                        for line in &field[y.saturating_sub(1)..(y + 2).min(height)] {
+                            for value in &line[x.saturating_sub(1)..(x + 2).min(width)] {
+                                sum += usize::from(self.state(*value));
+                            }
+                        }
+                        self.value(self.next.get(sum).copied().unwrap_or(0))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The weighted sum of `field` around every cell, with weights given as row and column offsets.
+fn convolve(field: &Field, kernel: &[(isize, isize, f32)]) -> Field {
+    let height = field.len() as isize;
+    field
+        .iter()
+        .enumerate()
+        .map(|(y, line)| {
+            let width = line.len() as isize;
+            (0..line.len())
+                .map(|x| {
+                    let mut sum = 0.0;
+                    for &(dy, dx, weight) in kernel {
+                        let (ny, nx) = (y as isize + dy, x as isize + dx);
+                        if (0..height).contains(&ny) && (0..width).contains(&nx) {
+                            sum += weight * field[ny as usize][nx as usize];
+                        }
+                    }
+                    sum
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lenia() {
+        let lenia = Lenia::new(3, 0.15, 0.015, 0.1);
+        let total: f32 = lenia.kernel.iter().map(|(_, _, weight)| weight).sum();
+        assert!((total - 1.0).abs() < 1e-5);
+        assert!((lenia.growth(0.15) - 1.0).abs() < 1e-6);
+        assert!((lenia.growth(0.5) + 1.0).abs() < 1e-6);
+
+        // an empty field shrinks to nothing and stays there
+        let empty = vec![vec![0.0; 8]; 8];
+        assert_eq!(lenia.step(&empty), empty);
+
+        // a lone full cell has no neighbors, so its average is 0 and it fades
+        let mut lone = empty.clone();
+        lone[4][4] = 1.0;
+        let next = lenia.step(&lone);
+        assert!((next[4][4] - 0.9).abs() < 1e-6);
+
+        assert_eq!(EngineKind::parse("Lenia"), Ok(EngineKind::Lenia));
+        assert!(parse_positive("0").is_err());
+    }
+
+    #[test]
+    fn totalistic() {
+        // 21 is 210 in base 3: sums of 1 become state 1 and sums of 2 state 2
+        let totalistic = Totalistic::new(3, "21").unwrap();
+        assert_eq!(totalistic.next, vec![0, 1, 2]);
+        let mut field = vec![vec![0.0; 4]; 3];
+        field[1][1] = 0.5;
+        let next = totalistic.step(&field);
+        assert_eq!(next[0][0], 0.5);
+        assert_eq!(next[2][2], 0.5);
+        assert_eq!(next[0][3], 0.0);
+
+        field[1][2] = 0.5;
+        let next = totalistic.step(&field);
+        assert_eq!(next[1][1], 1.0);
+        assert_eq!(next[0][3], 0.5);
+
+        assert!(Totalistic::new(3, "1").is_err());
+        assert!(Totalistic::new(3, "12a").is_err());
+        assert!(Totalistic::new(9, "3").is_err());
+        // 3^19 has a 20th base-3 digit, past the largest sum of 18
+        assert!(Totalistic::new(3, "1162261467").is_err());
+        assert!(Totalistic::new(3, Totalistic::DEFAULT_CODE).is_ok());
+        assert_eq!(EngineKind::parse("Totalistic"), Ok(EngineKind::Totalistic));
+    }
+
+    #[test]
+    fn smooth_life() {
+        let smooth_life = SmoothLife::new(3, 9, (0.278, 0.365), (0.267, 0.445)).unwrap();
+        for kernel in [&smooth_life.disk, &smooth_life.ring] {
+            let total: f32 = kernel.iter().map(|(_, _, weight)| weight).sum();
+            assert!((total - 1.0).abs() < 1e-5);
+        }
+        // dead cells are born with their ring inside the birth interval, not above it
+        assert!(smooth_life.transition(0.0, 0.32) > 0.9);
+        assert!(smooth_life.transition(0.0, 0.6) < 0.1);
+        // and live ones survive inside the death interval
+        assert!(smooth_life.transition(1.0, 0.4) > 0.9);
+        assert!(smooth_life.transition(1.0, 0.1) < 0.1);
+
+        let empty = vec![vec![0.0; 16]; 16];
+        assert!(smooth_life
+            .step(&empty)
+            .iter()
+            .flatten()
+            .all(|state| *state < 0.01));
+
+        assert!(SmoothLife::new(9, 9, (0.278, 0.365), (0.267, 0.445)).is_err());
+        assert_eq!(parse_interval("0.25, 0.5"), Ok((0.25, 0.5)));
+        assert!(parse_interval("0.5,0.25").is_err());
+        assert!(parse_interval("0.5").is_err());
+    }
+}
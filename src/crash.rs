@@ -0,0 +1,108 @@
+use std::{
+    backtrace::Backtrace,
+    fs, io,
+    panic::PanicHookInfo,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{app::Model, session};
+
+/// Log lines copied into a crash report.
+const LOG_LINES: usize = 200;
+
+/// What's kept for a crash report once `--crash-report` turns them on.
+struct Reporter {
+    /// Where reports go; each gets its own directory inside.
+    dir: PathBuf,
+    /// The log file, whose last lines are copied into reports.
+    log: Option<PathBuf>,
+    /// The board as RLE, as of the last time it was remembered.
+    board: Mutex<Option<String>>,
+}
+
+static REPORTER: OnceLock<Reporter> = OnceLock::new();
+
+/// Writes a report into `dir` if the app panics from now on, with the last lines of `log` if
+/// there is one.
+pub fn enable(dir: PathBuf, log: Option<PathBuf>) {
+    let _ = REPORTER.set(Reporter {
+        dir,
+        log,
+        board: Mutex::new(None),
+    });
+}
+
+/// Keeps a copy of the board for the crash report, if reports are on. Called before every
+/// frame, so the report shows the board just before whatever went wrong.
+pub fn remember(model: &Model) {
+    if let Some(reporter) = REPORTER.get() {
+        let rle = session::snapshot(model, "crash").to_rle();
+        if let Ok(mut board) = reporter.board.lock() {
+            *board = Some(rle);
+        }
+    }
+}
+
+/// Writes a crash report for `panic_info`, if reports are on, returning the directory it went
+/// in: `report.txt` with the panic and a backtrace, `log.txt` with the end of the log and
+/// `board.rle` with the last board remembered.
+pub fn write_report(panic_info: &PanicHookInfo) -> Option<io::Result<PathBuf>> {
+    let reporter = REPORTER.get()?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or(0);
+    let dir = reporter.dir.join(format!("crash-{secs}"));
+    Some(reporter.write(&dir, panic_info).map(|()| dir))
+}
+
+impl Reporter {
+    fn write(&self, dir: &PathBuf, panic_info: &PanicHookInfo) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let report = format!(
+            "{panic_info}\n\nversion {}\n\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            Backtrace::force_capture()
+        );
+        fs::write(dir.join("report.txt"), report)?;
+
+        if let Some(log) = &self.log {
+            // a missing or unreadable log shouldn't keep the rest of the report from being written
+            let text = fs::read_to_string(log).unwrap_or_default();
+            fs::write(dir.join("log.txt"), last_lines(&text, LOG_LINES))?;
+        }
+        // the lock may be poisoned if the panic happened while remembering the board
+        let board = match self.board.lock() {
+            Ok(board) => board.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        if let Some(board) = board {
+            fs::write(dir.join("board.rle"), board)?;
+        }
+        Ok(())
+    }
+}
+
+/// The last `count` lines of `text`.
+fn last_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(count);
+    lines[start..]
+        .iter()
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_last_lines() {
+        assert_eq!(last_lines("a\nb\nc\n", 2), "b\nc\n");
+        assert_eq!(last_lines("a\n", 5), "a\n");
+        assert_eq!(last_lines("", 5), "");
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+
+/// Small still lifes, oscillators and spaceships picked out of a board by [`describe`], each as
+/// its singular and plural names and one phase's rows, in any orientation.
+const KNOWN: [(&str, &str, &[&str]); 12] = [
+    ("block", "blocks", &["**", "**"]),
+    ("blinker", "blinkers", &["***"]),
+    ("beehive", "beehives", &[".**.", "*..*", ".**."]),
+    ("loaf", "loaves", &[".**.", "*..*", ".*.*", "..*."]),
+    ("boat", "boats", &["**.", "*.*", ".*."]),
+    ("ship", "ships", &["**.", "*.*", ".**"]),
+    ("tub", "tubs", &[".*.", "*.*", ".*."]),
+    ("pond", "ponds", &[".**.", "*..*", "*..*", ".**."]),
+    ("glider", "gliders", &[".*.", "..*", "***"]),
+    ("glider", "gliders", &["*.*", ".**", ".*."]),
+    ("glider", "gliders", &["..*", "*.*", ".**"]),
+    ("glider", "gliders", &["*..", ".**", "**."]),
+];
+
+/// A cell's position as a row and column, which may be negative while transforming shapes.
+type Offset = (isize, isize);
+
+/// The offsets of the eight cells touching a cell.
+const NEIGHBORS: [Offset; 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A sentence or two about `board` for the screen-reader friendly mode: how many cells are
+/// alive, where, in how many separate groups, and which of the groups are well-known
+/// patterns.
+pub fn describe(board: &[Vec<bool>]) -> String {
+    let groups = groups(board);
+    let population: usize = groups.iter().map(Vec::len).sum();
+    if population == 0 {
+        return String::from("The board is empty.");
+    }
+
+    let cells = groups.iter().flatten();
+    let (top, bottom) = bounds(cells.clone().map(|&(y, _)| y));
+    let (left, right) = bounds(cells.map(|&(_, x)| x));
+    let mut description = format!(
+        "{population} live {} in {} {} between columns {left} and {right} and rows {top} and {bottom}",
+        plural(population, "cell", "cells"),
+        groups.len(),
+        plural(groups.len(), "group", "groups"),
+    );
+
+    let mut counts: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    let mut others = 0;
+    for group in &groups {
+        match recognize(group) {
+            Some(names) => *counts.entry(names).or_default() += 1,
+            None => others += 1,
+        }
+    }
+    let mut parts: Vec<String> = counts
+        .into_iter()
+        .map(|((singular, plural_name), count)| {
+            format!("{count} {}", plural(count, singular, plural_name))
+        })
+        .collect();
+    if others > 0 && !parts.is_empty() {
+        parts.push(format!("{others} {}", plural(others, "other", "others")));
+    }
+    if !parts.is_empty() {
+        description.push_str(": ");
+        description.push_str(&parts.join(", "));
+    }
+    description.push('.');
+    description
+}
+
+fn plural<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
+    if count == 1 {
+        singular
+    } else {
+        plural
+    }
+}
+
+fn bounds(values: impl Iterator<Item = usize>) -> (usize, usize) {
+    values.fold((usize::MAX, 0), |(low, high), value| {
+        (low.min(value), high.max(value))
+    })
+}
+
+/// The live cells of `board` split into groups of cells touching, diagonally included.
+fn groups(board: &[Vec<bool>]) -> Vec<Vec<(usize, usize)>> {
+    let mut seen: Vec<Vec<bool>> = board.iter().map(|line| vec![false; line.len()]).collect();
+    let mut groups = vec![];
+    for (y, line) in board.iter().enumerate() {
+        for (x, &alive) in line.iter().enumerate() {
+            if !alive || seen[y][x] {
+                continue;
+            }
+            seen[y][x] = true;
+            let (mut group, mut frontier) = (vec![], vec![(y, x)]);
+            while let Some((y, x)) = frontier.pop() {
+                group.push((y, x));
+                for (dy, dx) in NEIGHBORS {
+                    let (Some(ny), Some(nx)) = (y.checked_add_signed(dy), x.checked_add_signed(dx))
+                    else {
+                        continue;
+                    };
+                    let alive = board.get(ny).and_then(|line| line.get(nx)) == Some(&true);
+                    if alive && !seen[ny][nx] {
+                        seen[ny][nx] = true;
+                        frontier.push((ny, nx));
+                    }
+                }
+            }
+            groups.push(group);
+        }
+    }
+    groups
+}
+
+/// The names of the known pattern `group` is, if it's one.
+fn recognize(group: &[(usize, usize)]) -> Option<(&'static str, &'static str)> {
+    let cells: Vec<Offset> = group
+        .iter()
+        .map(|&(y, x)| (y as isize, x as isize))
+        .collect();
+    let shape = normalized(cells);
+    KNOWN.iter().find_map(|&(singular, plural, rows)| {
+        let known: Vec<Offset> = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|&(_, ch)| ch == '*')
+                    .map(move |(x, _)| (y as isize, x as isize))
+            })
+            .collect();
+        (known.len() == shape.len() && orientations(&known).contains(&shape))
+            .then_some((singular, plural))
+    })
+}
+
+/// `cells` moved so the topmost and leftmost are at 0, sorted.
+fn normalized(mut cells: Vec<Offset>) -> Vec<Offset> {
+    let top = cells.iter().map(|&(y, _)| y).min().unwrap_or_default();
+    let left = cells.iter().map(|&(_, x)| x).min().unwrap_or_default();
+    for cell in &mut cells {
+        *cell = (cell.0 - top, cell.1 - left);
+    }
+    cells.sort_unstable();
+    cells
+}
+
+/// `cells` in each of its eight rotations and reflections, normalized.
+fn orientations(cells: &[Offset]) -> Vec<Vec<Offset>> {
+    let transforms: [fn(Offset) -> Offset; 8] = [
+        |(y, x)| (y, x),
+        |(y, x)| (x, -y),
+        |(y, x)| (-y, -x),
+        |(y, x)| (-x, y),
+        |(y, x)| (y, -x),
+        |(y, x)| (-y, x),
+        |(y, x)| (x, y),
+        |(y, x)| (-x, -y),
+    ];
+    transforms
+        .iter()
+        .map(|transform| normalized(cells.iter().copied().map(transform).collect()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(rows: &[&str]) -> Vec<Vec<bool>> {
+        rows.iter()
+            .map(|row| row.chars().map(|ch| ch == '*').collect())
+            .collect()
+    }
+
+    #[test]
+    fn describes_groups() {
+        assert_eq!(describe(&board(&["...", "..."])), "The board is empty.");
+        let board = board(&[
+            "**.......*",
+            "**.......*",
+            ".........*",
+            "..........",
+            "...*......",
+            "....*..*..",
+            "..***.**..",
+        ]);
+        assert_eq!(
+            describe(&board),
+            "15 live cells in 4 groups between columns 0 and 9 and rows 0 and 6: \
+             1 blinker, 1 block, 1 glider, 1 other."
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use std::{path::PathBuf, sync::OnceLock};
+
+use directories::ProjectDirs;
+
+/// The directory the app's files go in, under each of the platform's directories.
+const APP_DIR: &str = "tui-cellular-automaton";
+
+/// The directory set with `--portable`, which replaces the platform's directories.
+static PORTABLE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keeps every file under `dir` from now on, for running from a USB stick or a synced folder
+/// without touching the home directory. Only the first call has an effect.
+pub fn set_portable(dir: PathBuf) {
+    let _ = PORTABLE.set(dir);
+}
+
+/// Where the app keeps its files: the pattern library, saves and the recent history. That's
+/// the `--portable` directory if one was given, or else the app's directory in the platform's
+/// data directory: `$XDG_DATA_HOME` or `~/.local/share` on Linux and other unixes,
+/// `~/Library/Application Support` on macOS and `%APPDATA%` on Windows. `None` if there's no
+/// home directory to find those in.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = PORTABLE.get() {
+        return Some(dir.clone());
+    }
+    Some(project_dirs()?.data_dir().to_path_buf())
+}
+
+/// Where logs go unless `--log-file` says otherwise: `logs` in the `--portable` directory, or
+/// else the app's directory in `$XDG_STATE_HOME` or `~/.local/state` on Linux and other unixes.
+/// macOS and Windows have no state directory, so there it's `logs` in the app's directory
+/// under `~/Library/Application Support` or `%LOCALAPPDATA%`.
+pub fn log_dir() -> Option<PathBuf> {
+    if let Some(dir) = PORTABLE.get() {
+        return Some(dir.join("logs"));
+    }
+    let project = project_dirs()?;
+    Some(match project.state_dir() {
+        Some(dir) => dir.to_path_buf(),
+        None => project.data_local_dir().join("logs"),
+    })
+}
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from_path(PathBuf::from(APP_DIR))
+}
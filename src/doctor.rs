@@ -0,0 +1,267 @@
+use std::{
+    env, fmt,
+    io::{self, stdout, IsTerminal, Write},
+};
+
+use ratatui::crossterm::{
+    cursor::{self, MoveTo},
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::color::ColorDepth;
+
+/// The smallest terminal that fits a board, the status lines and the key hints.
+const MIN_SIZE: (u16, u16) = (20, 10);
+
+/// Glyphs the themes and cell styles draw with, to check the terminal gives them the width
+/// the layout assumes.
+const GLYPHS: [(&str, &str); 4] = [
+    ("block", "█"),
+    ("half block", "▀"),
+    ("braille", "⣿"),
+    ("emoji", "🟩"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Ok,
+    Warn,
+    Unknown,
+}
+
+/// The result of one probe, printed as a line of the report.
+#[derive(Debug, PartialEq, Eq)]
+struct Check {
+    name: &'static str,
+    outcome: Outcome,
+    detail: String,
+    /// What to do about a warning.
+    hint: Option<&'static str>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Check {
+        Check {
+            name,
+            outcome: Outcome::Ok,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: &'static str) -> Check {
+        Check {
+            name,
+            outcome: Outcome::Warn,
+            detail: detail.into(),
+            hint: Some(hint),
+        }
+    }
+
+    fn unknown(name: &'static str, detail: impl Into<String>) -> Check {
+        Check {
+            name,
+            outcome: Outcome::Unknown,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = match self.outcome {
+            Outcome::Ok => "ok",
+            Outcome::Warn => "warn",
+            Outcome::Unknown => "??",
+        };
+        write!(f, "{outcome:<5} {:<14} {}", self.name, self.detail)?;
+        if let Some(hint) = self.hint {
+            write!(f, "\n      {:<14} {hint}", "")?;
+        }
+        Ok(())
+    }
+}
+
+/// Probes the terminal and prints what it supports, for `doctor`.
+pub fn run() -> io::Result<()> {
+    if !stdout().is_terminal() {
+        println!("stdout isn't a terminal; run doctor directly in the terminal to check it");
+        return Ok(());
+    }
+
+    let mut checks = vec![check_size(size().ok()), check_colors(color_env())];
+    checks.push(check_mouse(env::var("TERM").ok().as_deref()));
+    // the probes move the cursor around, so they're kept off the main screen
+    let probes = probe();
+    match probes {
+        Ok((widths, restored)) => {
+            checks.extend(widths);
+            checks.push(check_alternate_screen(restored));
+        }
+        Err(error) => checks.push(Check::unknown(
+            "glyphs",
+            format!("couldn't probe the terminal ({error})"),
+        )),
+    }
+
+    for check in &checks {
+        println!("{check}");
+    }
+    Ok(())
+}
+
+fn color_env() -> (Option<String>, Option<String>, ColorDepth) {
+    (
+        env::var("COLORTERM").ok(),
+        env::var("TERM").ok(),
+        ColorDepth::detect(),
+    )
+}
+
+fn check_size(size: Option<(u16, u16)>) -> Check {
+    let Some((columns, rows)) = size else {
+        return Check::unknown("size", "the terminal didn't report its size");
+    };
+    let detail = format!("{columns}x{rows}");
+    if columns < MIN_SIZE.0 || rows < MIN_SIZE.1 {
+        Check::warn(
+            "size",
+            format!("{detail}, smaller than {}x{}", MIN_SIZE.0, MIN_SIZE.1),
+            "enlarge the window or shrink the font",
+        )
+    } else {
+        Check::ok("size", detail)
+    }
+}
+
+fn check_colors((colorterm, term, depth): (Option<String>, Option<String>, ColorDepth)) -> Check {
+    let detail = format!(
+        "COLORTERM={} TERM={}",
+        colorterm.as_deref().unwrap_or("(unset)"),
+        term.as_deref().unwrap_or("(unset)")
+    );
+    match depth {
+        ColorDepth::TrueColor => Check::ok("colors", format!("truecolor ({detail})")),
+        ColorDepth::Ansi256 => Check::warn(
+            "colors",
+            format!("256 colors ({detail})"),
+            "if the terminal does have truecolor, set COLORTERM=truecolor or pass --color-depth truecolor",
+        ),
+        ColorDepth::Ansi16 => Check::warn(
+            "colors",
+            format!("16 colors ({detail})"),
+            "ages will be hard to tell apart; try --cell-style glyphs",
+        ),
+    }
+}
+
+/// Mouse reporting can't be detected without the user clicking, so this goes by `$TERM`.
+fn check_mouse(term: Option<&str>) -> Check {
+    match term {
+        None | Some("dumb") => Check::warn(
+            "mouse",
+            format!("unlikely with TERM={}", term.unwrap_or("(unset)")),
+            "use the arrow keys and space to edit the board",
+        ),
+        Some("linux") => Check::warn(
+            "mouse",
+            "the Linux console only reports the mouse with gpm running",
+            "use the arrow keys and space to edit the board",
+        ),
+        Some(term) => Check::ok(
+            "mouse",
+            format!("xterm-style reporting expected (TERM={term})"),
+        ),
+    }
+}
+
+/// Compares the columns `glyph` took when printed with the columns the layout gives it.
+fn check_width(name: &'static str, glyph: &str, measured: Option<u16>) -> Check {
+    let expected = glyph.width() as u16;
+    match measured {
+        None => Check::unknown(name, format!("{glyph} couldn't be measured")),
+        Some(columns) if columns == expected => {
+            Check::ok(name, format!("{glyph} is {columns} column(s) wide"))
+        }
+        Some(columns) => Check::warn(
+            name,
+            format!("{glyph} is {columns} column(s) wide, expected {expected}"),
+            "the board will be misaligned with themes using it; try another font, --theme classic or --theme ascii",
+        ),
+    }
+}
+
+fn check_alternate_screen(restored: Option<bool>) -> Check {
+    match restored {
+        Some(true) => Check::ok("alt screen", "the screen is restored on exit"),
+        Some(false) => Check::warn(
+            "alt screen",
+            "the cursor moved while on the alternate screen",
+            "the board may be left behind in the scrollback after quitting",
+        ),
+        None => Check::unknown("alt screen", "the cursor position couldn't be read"),
+    }
+}
+
+/// Prints each of [`GLYPHS`] on the alternate screen and reads back where the cursor ended up,
+/// then checks leaving the alternate screen puts the cursor back where it was.
+fn probe() -> io::Result<(Vec<Check>, Option<bool>)> {
+    enable_raw_mode()?;
+    let Ok(before) = cursor::position() else {
+        disable_raw_mode()?;
+        return Err(io::Error::other(
+            "the terminal doesn't report the cursor position",
+        ));
+    };
+    let result = probe_widths();
+    execute!(stdout(), LeaveAlternateScreen)?;
+    let after = cursor::position().ok();
+    disable_raw_mode()?;
+
+    let restored = after.map(|after| before == after);
+    Ok((result?, restored))
+}
+
+fn probe_widths() -> io::Result<Vec<Check>> {
+    let mut stdout = stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut checks = vec![];
+    for (name, glyph) in GLYPHS {
+        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
+        write!(stdout, "{glyph}")?;
+        stdout.flush()?;
+        let measured = cursor::position().ok().map(|(column, _)| column);
+        checks.push(check_width(name, glyph, measured));
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks() {
+        assert_eq!(check_size(Some((80, 24))).outcome, Outcome::Ok);
+        assert_eq!(check_size(Some((80, 6))).outcome, Outcome::Warn);
+        assert_eq!(check_size(None).outcome, Outcome::Unknown);
+
+        assert_eq!(check_width("block", "█", Some(1)).outcome, Outcome::Ok);
+        let emoji = check_width("emoji", "🟩", Some(1));
+        assert_eq!(emoji.outcome, Outcome::Warn);
+        assert!(emoji.detail.ends_with("expected 2"));
+
+        assert_eq!(check_mouse(Some("xterm-256color")).outcome, Outcome::Ok);
+        assert_eq!(check_mouse(Some("linux")).outcome, Outcome::Warn);
+        assert_eq!(
+            check_colors((None, Some(String::from("xterm")), ColorDepth::Ansi16)).outcome,
+            Outcome::Warn
+        );
+    }
+}
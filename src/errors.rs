@@ -1,25 +1,96 @@
-use std::{error::Error, panic};
+use std::error::Error;
+#[cfg(feature = "tui")]
+use std::panic;
 
+#[cfg(feature = "tui")]
 use color_eyre::{config::HookBuilder, eyre::set_hook};
 
-use crate::tui::restore;
+#[cfg(feature = "tui")]
+use crate::{crash, tui::restore};
 
+/// A recoverable error, shown in the TUI's error panel until dismissed: what was being done,
+/// then the chain of errors that stopped it, outermost first. Only fatal errors reach the
+/// hooks installed by [`install_hooks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    pub context: String,
+    pub chain: Vec<String>,
+}
+
+impl ErrorReport {
+    pub fn new(context: impl Into<String>, error: &(dyn Error + 'static)) -> ErrorReport {
+        let mut chain = vec![];
+        let mut source = Some(error);
+        while let Some(error) = source {
+            chain.push(error.to_string());
+            source = error.source();
+        }
+        ErrorReport {
+            context: context.into(),
+            chain,
+        }
+    }
+
+    /// A report with no underlying error, for problems found by the app itself.
+    pub fn message(context: impl Into<String>) -> ErrorReport {
+        ErrorReport {
+            context: context.into(),
+            chain: vec![],
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
 pub fn install_hooks() -> color_eyre::Result<()> {
     let (panic_hook, eyre_hook) = HookBuilder::default().into_hooks();
-    
+
     let panic_hook = panic_hook.into_panic_hook();
     panic::set_hook(Box::new(move |panic_info| {
         restore().unwrap();
         panic_hook(panic_info);
+        match crash::write_report(panic_info) {
+            Some(Ok(dir)) => eprintln!("A crash report was saved to {}", dir.display()),
+            Some(Err(error)) => eprintln!("Couldn't save a crash report: {error}"),
+            None => {}
+        }
     }));
 
     let eyre_hook = eyre_hook.into_eyre_hook();
-    set_hook(Box::new(
-        move |error: &(dyn Error + 'static)| {
-            restore().unwrap();
-            eyre_hook(error)
-        },
-    ))?;
+    set_hook(Box::new(move |error: &(dyn Error + 'static)| {
+        restore().unwrap();
+        eyre_hook(error)
+    }))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fmt, io};
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Parse(io::Error);
+
+    impl fmt::Display for Parse {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "couldn't read the pattern")
+        }
+    }
+
+    impl Error for Parse {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn report_error_chain() {
+        let error = Parse(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+        let report = ErrorReport::new("Couldn't load slot 2", &error);
+        assert_eq!(report.context, "Couldn't load slot 2");
+        assert_eq!(report.chain, ["couldn't read the pattern", "no such file"]);
+        assert!(ErrorReport::message("Nothing to load").chain.is_empty());
+    }
+}
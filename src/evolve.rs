@@ -0,0 +1,203 @@
+use std::{cmp::Ordering, fmt, time::Duration};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    app::{Model, Preset, Rule},
+    explorer,
+    headless::{self, Outcome},
+    rng::RngSource,
+};
+
+/// Rules listed at the end of a search.
+const REPORTED: usize = 5;
+
+/// What a search rewards a rule for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fitness {
+    /// Generations before the soup dies out or becomes periodic.
+    Longevity,
+    /// How many times over the soup's population grows.
+    Growth,
+    /// The period of the oscillation the soup settles into, if it settles into a live one.
+    Oscillation,
+}
+
+impl Fitness {
+    pub fn parse(input: &str) -> Result<Fitness, String> {
+        match input.trim().to_lowercase().as_str() {
+            "longevity" => Ok(Fitness::Longevity),
+            "growth" => Ok(Fitness::Growth),
+            "oscillation" => Ok(Fitness::Oscillation),
+            _ => Err(format!(
+                "unknown fitness \"{input}\"; expected longevity, growth or oscillation"
+            )),
+        }
+    }
+}
+
+/// How the rules of a search are tried out.
+#[derive(Debug, Clone, Copy)]
+pub struct Search {
+    pub fitness: Fitness,
+    /// Rules in each round.
+    pub population: usize,
+    /// Rounds of selection and breeding.
+    pub rounds: u32,
+    /// Generations each soup is run for at most.
+    pub max_generations: u64,
+    pub width: u16,
+    pub height: u16,
+    /// Fraction of each soup alive, or the rule's suggested density.
+    pub density: Option<f64>,
+}
+
+/// A rule tried by a search, with the soup it was tried on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub rule: Rule,
+    /// The seed of the soup, to reproduce it with `--seed`.
+    pub seed: u64,
+    pub score: f64,
+}
+
+impl fmt::Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<16} score {:<10.1} -r {} -p random --seed {}",
+            self.rule.to_string(),
+            self.score,
+            self.rule,
+            self.seed
+        )
+    }
+}
+
+/// Searches rule space with a genetic algorithm: each round keeps the better half of the
+/// rules and refills the rest with crossovers of them, each mutated by a digit. Returns the
+/// best rules found, best first. The search is reproducible from `seed`.
+pub fn run(search: &Search, seed: u64) -> Vec<Candidate> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let population = search.population.max(2);
+    let mut candidates: Vec<Candidate> = (0..population)
+        .map(|_| evaluate(search, random_rule(&mut rng), &mut rng))
+        .collect();
+    let mut best = vec![];
+
+    for _ in 0..search.rounds {
+        candidates.sort_by(by_score);
+        remember(&mut best, &candidates);
+        candidates.truncate(population / 2);
+        while candidates.len() < population {
+            let mother = &candidates[rng.gen_range(0..population / 2)].rule;
+            let father = &candidates[rng.gen_range(0..population / 2)].rule;
+            let child = explorer::mutate(&crossover(mother, father, &mut rng), &mut rng);
+            candidates.push(evaluate(search, child, &mut rng));
+        }
+    }
+    candidates.sort_by(by_score);
+    remember(&mut best, &candidates);
+    best
+}
+
+/// Best first.
+fn by_score(a: &Candidate, b: &Candidate) -> Ordering {
+    b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal)
+}
+
+/// Merges `candidates` into the [`REPORTED`] best rules found so far, keeping each rule once.
+fn remember(best: &mut Vec<Candidate>, candidates: &[Candidate]) {
+    for candidate in candidates {
+        match best.iter_mut().find(|known| known.rule == candidate.rule) {
+            Some(known) if known.score < candidate.score => *known = candidate.clone(),
+            Some(_) => {}
+            None => best.push(candidate.clone()),
+        }
+    }
+    best.sort_by(by_score);
+    best.truncate(REPORTED);
+}
+
+/// A rule with each birth digit from 1 and survival digit from 0 present by a coin flip.
+fn random_rule(rng: &mut impl Rng) -> Rule {
+    Rule {
+        birth_list: (1..=8).filter(|_| rng.gen_bool(0.5)).collect(),
+        survival_list: (0..=8).filter(|_| rng.gen_bool(0.5)).collect(),
+        history: false,
+        second_order: false,
+    }
+}
+
+/// A rule taking each digit from one of `mother` and `father`, chosen by a coin flip.
+fn crossover(mother: &Rule, father: &Rule, rng: &mut impl Rng) -> Rule {
+    let mut pick = |mother: &[u8], father: &[u8]| -> Vec<u8> {
+        (0..=8)
+            .filter(|digit| {
+                let parent = if rng.gen_bool(0.5) { mother } else { father };
+                parent.contains(digit)
+            })
+            .collect()
+    };
+    Rule {
+        birth_list: pick(&mother.birth_list, &father.birth_list),
+        survival_list: pick(&mother.survival_list, &father.survival_list),
+        history: false,
+        second_order: false,
+    }
+}
+
+/// Runs `rule` on a fresh random soup and scores how it went.
+fn evaluate(search: &Search, rule: Rule, rng: &mut impl Rng) -> Candidate {
+    let seed = rng.gen();
+    let mut model = Model::new(
+        search.height as i16 - 1,
+        search.width as i16 - 1,
+        rule.birth_list.clone(),
+        rule.survival_list.clone(),
+        Duration::ZERO,
+    );
+    model.set_rng(RngSource::seeded(seed));
+    model.set_density(search.density);
+    model.load_preset(Preset::Random);
+    let start = headless::population(&model).max(1);
+    let outcome = headless::run(&mut model, search.max_generations);
+
+    let score = match (search.fitness, outcome) {
+        (Fitness::Longevity, Outcome::Stabilized(stabilization)) => stabilization.generation as f64,
+        (
+            Fitness::Longevity,
+            Outcome::Extinct(generation) | Outcome::MaxGenerations(generation),
+        ) => generation as f64,
+        (Fitness::Growth, _) => headless::population(&model) as f64 / start as f64,
+        (Fitness::Oscillation, Outcome::Stabilized(stabilization)) => stabilization.period as f64,
+        (Fitness::Oscillation, _) => 0.0,
+    };
+    Candidate { rule, seed, score }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search() {
+        assert_eq!(Fitness::parse("Growth"), Ok(Fitness::Growth));
+        assert!(Fitness::parse("beauty").is_err());
+
+        let search = Search {
+            fitness: Fitness::Longevity,
+            population: 6,
+            rounds: 2,
+            max_generations: 50,
+            width: 16,
+            height: 16,
+            density: None,
+        };
+        let best = run(&search, 1);
+        assert!(!best.is_empty() && best.len() <= REPORTED);
+        assert!(best.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert_eq!(run(&search, 1), best);
+    }
+}
@@ -0,0 +1,116 @@
+use rand::Rng;
+
+use crate::app::Rule;
+
+/// Rules shown in the title before older ones are left out.
+pub const SHOWN_LINEAGE: usize = 3;
+
+/// Tracks the rules tried by mutating the current one, on demand with `u` or every
+/// `--mutate-every` generations.
+#[derive(Debug, Default)]
+pub struct Explorer {
+    /// The rulestrings mutated away from, oldest first.
+    lineage: Vec<String>,
+    /// Generations between automatic mutations, if they're on.
+    every: Option<u64>,
+}
+
+impl Explorer {
+    pub fn new(every: Option<u64>) -> Explorer {
+        Explorer {
+            lineage: vec![],
+            every: every.filter(|every| *every > 0),
+        }
+    }
+
+    pub fn lineage(&self) -> &[String] {
+        &self.lineage
+    }
+
+    /// Whether a run that has stepped `run_generations` times is due an automatic mutation.
+    pub fn is_due(&self, run_generations: u64) -> bool {
+        self.every
+            .is_some_and(|every| run_generations > 0 && run_generations.is_multiple_of(every))
+    }
+
+    /// Mutates `rule` and remembers what it was.
+    pub fn mutate(&mut self, rule: &Rule, rng: &mut impl Rng) -> Rule {
+        self.lineage.push(rule.to_string());
+        mutate(rule, rng)
+    }
+}
+
+/// `rule` with one birth or survival digit added or removed. B0, and S8 alongside it, are left
+/// alone since they decide how the board's phase is emulated.
+pub fn mutate(rule: &Rule, rng: &mut impl Rng) -> Rule {
+    let mut mutated = rule.clone();
+    let b0 = rule.birth_list.contains(&0);
+    loop {
+        let birth = rng.gen_bool(0.5);
+        let digit = if birth {
+            rng.gen_range(1..=8)
+        } else {
+            rng.gen_range(0..=8)
+        };
+        if b0 && !birth && digit == 8 {
+            continue;
+        }
+        let list = if birth {
+            &mut mutated.birth_list
+        } else {
+            &mut mutated.survival_list
+        };
+        match list.iter().position(|existing| *existing == digit) {
+            Some(index) => {
+                list.remove(index);
+            }
+            None => {
+                list.push(digit);
+                list.sort_unstable();
+            }
+        }
+        return mutated;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn mutations() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let life = "B3/S23".parse::<Rule>().unwrap();
+        for _ in 0..100 {
+            let mutated = mutate(&life, &mut rng);
+            let changes = (0..=8)
+                .filter(|digit| {
+                    life.birth_list.contains(digit) != mutated.birth_list.contains(digit)
+                        || life.survival_list.contains(digit)
+                            != mutated.survival_list.contains(digit)
+                })
+                .count();
+            assert_eq!(changes, 1);
+            assert!(!mutated.birth_list.contains(&0));
+        }
+
+        let b0 = "B01/S8".parse::<Rule>().unwrap();
+        for _ in 0..100 {
+            let mutated = mutate(&b0, &mut rng);
+            assert!(mutated.birth_list.contains(&0) && mutated.survival_list.contains(&8));
+        }
+
+        let mut explorer = Explorer::new(Some(10));
+        let mutated = explorer.mutate(&life, &mut rng);
+        explorer.mutate(&mutated, &mut rng);
+        assert_eq!(explorer.lineage()[0], "B3/S23");
+        assert_eq!(explorer.lineage()[1], mutated.to_string());
+        assert!(!explorer.is_due(0));
+        assert!(explorer.is_due(20));
+        assert!(!explorer.is_due(25));
+        assert!(!Explorer::new(None).is_due(10));
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+    widgets::WidgetRef,
+};
+use tracing::info;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{app::Model, color::to_rgb, dirs::data_dir};
+
+/// Colors of HTML exports where the terminal would use its own.
+const HTML_FOREGROUND: &str = "#cccccc";
+const HTML_BACKGROUND: &str = "#000000";
+
+/// Where an export of generation `generation` goes: `exports/generation-N.EXTENSION` in the
+/// [`data_dir`].
+pub fn path(generation: u64, extension: &str) -> Option<PathBuf> {
+    Some(
+        data_dir()?
+            .join("exports")
+            .join(format!("generation-{generation}.{extension}")),
+    )
+}
+
+/// Draws the board as it's shown on screen, into a buffer of its own.
+pub fn render(model: &Model) -> Buffer {
+    let area = Rect::from(model.board_area());
+    let mut buffer = Buffer::empty(Rect { x: 0, y: 0, ..area });
+    model.render_ref(buffer.area, &mut buffer);
+    buffer
+}
+
+/// Writes `text` to `path`, making its directory if need be.
+pub fn write(path: &Path, text: &str) -> io::Result<()> {
+    info!(path = %path.display(), "exporting the board");
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, text)
+}
+
+/// The cells of `buffer` a row at a time, leaving out those hidden under wide glyphs.
+fn rows(buffer: &Buffer) -> impl Iterator<Item = Vec<&ratatui::buffer::Cell>> {
+    let area = buffer.area;
+    (area.top()..area.bottom()).map(move |y| {
+        let mut cells = vec![];
+        let mut x = area.left();
+        while x < area.right() {
+            let cell = buffer.get(x, y);
+            cells.push(cell);
+            x += (cell.symbol().width() as u16).max(1);
+        }
+        cells
+    })
+}
+
+/// `buffer` as text with ANSI escape sequences for its colors, to `cat` in a terminal.
+pub fn ansi(buffer: &Buffer) -> String {
+    let mut text = String::new();
+    for row in rows(buffer) {
+        let mut last = None;
+        for cell in row {
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if last != Some(style) {
+                let mut codes = vec![String::from("0")];
+                if cell.modifier.contains(Modifier::BOLD) {
+                    codes.push(String::from("1"));
+                }
+                if cell.modifier.contains(Modifier::REVERSED) {
+                    codes.push(String::from("7"));
+                }
+                codes.extend(sgr(cell.fg, false));
+                codes.extend(sgr(cell.bg, true));
+                let _ = write!(text, "\x1b[{}m", codes.join(";"));
+                last = Some(style);
+            }
+            text.push_str(cell.symbol());
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// The SGR parameters that set `color` as the foreground, or the background if `background`
+/// is set.
+fn sgr(color: Color, background: bool) -> Option<String> {
+    let offset = if background { 10 } else { 0 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Indexed(index) => return Some(format!("{};5;{index}", 38 + offset)),
+        Color::Rgb(red, green, blue) => {
+            return Some(format!("{};2;{red};{green};{blue}", 38 + offset))
+        }
+    };
+    Some((code + offset).to_string())
+}
+
+/// `buffer` as an HTML page, each run of cells with the same style in a `<span>` with
+/// inline colors.
+pub fn html(buffer: &Buffer, title: &str) -> String {
+    let mut page = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n\
+         <body style=\"background: {HTML_BACKGROUND}\">\n\
+         <pre style=\"color: {HTML_FOREGROUND}; background: {HTML_BACKGROUND}; line-height: 1\">",
+        escape(title)
+    );
+    for row in rows(buffer) {
+        let mut runs: Vec<(String, String)> = vec![];
+        for cell in row {
+            let style = css(cell.fg, cell.bg, cell.modifier);
+            match runs.last_mut() {
+                Some((last, text)) if *last == style => text.push_str(cell.symbol()),
+                _ => runs.push((style, String::from(cell.symbol()))),
+            }
+        }
+        for (style, text) in runs {
+            match style.is_empty() {
+                true => page.push_str(&escape(&text)),
+                false => {
+                    let _ = write!(page, "<span style=\"{style}\">{}</span>", escape(&text));
+                }
+            }
+        }
+        page.push('\n');
+    }
+    page.push_str("</pre>\n</body>\n</html>\n");
+    page
+}
+
+/// Inline CSS for a cell's colors and modifiers, empty for the default look.
+fn css(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let hex = |color: Color| to_rgb(color).map(|(r, g, b)| format!("#{r:02x}{g:02x}{b:02x}"));
+    let (mut fg, mut bg) = (hex(fg), hex(bg));
+    if modifier.contains(Modifier::REVERSED) {
+        (fg, bg) = (
+            bg.or_else(|| Some(String::from(HTML_BACKGROUND))),
+            fg.or_else(|| Some(String::from(HTML_FOREGROUND))),
+        );
+    }
+    let mut style = String::new();
+    if let Some(fg) = fg {
+        let _ = write!(style, "color: {fg}; ");
+    }
+    if let Some(bg) = bg {
+        let _ = write!(style, "background: {bg}; ");
+    }
+    if modifier.contains(Modifier::BOLD) {
+        style.push_str("font-weight: bold; ");
+    }
+    String::from(style.trim_end())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ratatui::style::Style;
+
+    #[test]
+    fn exports_styles() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer.set_string(0, 0, "██", Style::default().fg(Color::Rgb(255, 0, 0)));
+        buffer.set_string(1, 1, "<", Style::default().bg(Color::Indexed(196)));
+
+        assert_eq!(
+            ansi(&buffer),
+            "\x1b[0;38;2;255;0;0m██\x1b[0m  \x1b[0m\n\
+             \x1b[0m \x1b[0;48;5;196m<\x1b[0m  \x1b[0m\n"
+        );
+        let page = html(&buffer, "B3/S23");
+        assert!(page.contains("<title>B3/S23</title>"));
+        assert!(page.contains("<span style=\"color: #ff0000;\">██</span>  \n"));
+        assert!(page.contains(" <span style=\"background: #ff0000;\">&lt;</span>  \n"));
+    }
+}
@@ -0,0 +1,120 @@
+use std::{fmt, ops::ControlFlow};
+
+use crate::{
+    app::{Message, Model, State},
+    stability::Stabilization,
+};
+
+/// Exit status of a headless run whose board became periodic.
+pub const STABILIZED: i32 = 0;
+/// Exit status for problems with the command line, headless or under `--strict`.
+pub const CONFIG_ERROR: i32 = 2;
+/// Exit status of a headless run whose board died out.
+pub const EXTINCT: i32 = 3;
+/// Exit status of a headless run stopped by `--max-generations`.
+pub const MAX_GENERATIONS: i32 = 4;
+
+/// How a headless run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Stabilized(Stabilization),
+    /// Every cell died at this generation.
+    Extinct(u64),
+    /// Still changing after this many generations.
+    MaxGenerations(u64),
+}
+
+impl Outcome {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Outcome::Stabilized(_) => STABILIZED,
+            Outcome::Extinct(_) => EXTINCT,
+            Outcome::MaxGenerations(_) => MAX_GENERATIONS,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Stabilized(stabilization) => write!(
+                f,
+                "stabilized at generation {} (period {})",
+                stabilization.generation, stabilization.period
+            ),
+            Outcome::Extinct(generation) => write!(f, "died out at generation {generation}"),
+            Outcome::MaxGenerations(generation) => {
+                write!(f, "still changing at generation {generation}")
+            }
+        }
+    }
+}
+
+/// Steps `model` without drawing it until the board dies out, becomes periodic or reaches
+/// `max_generations`. A board that dies out is reported as extinct rather than as a still
+/// life.
+pub fn run(model: &mut Model, max_generations: u64) -> Outcome {
+    if *model.state() == State::Editing {
+        model.update(Message::ToggleEditing);
+    }
+    let mut outcome = ended(model);
+    if outcome.is_none() {
+        model.step_n_with(
+            max_generations.saturating_sub(model.generation()),
+            |model| {
+                outcome = ended(model);
+                match outcome {
+                    Some(_) => ControlFlow::Break(()),
+                    None => ControlFlow::Continue(()),
+                }
+            },
+        );
+    }
+    outcome.unwrap_or(Outcome::MaxGenerations(model.generation()))
+}
+
+/// How the board has ended up, once it has died out or become periodic.
+pub fn ended(model: &Model) -> Option<Outcome> {
+    if population(model) == 0 {
+        Some(Outcome::Extinct(model.generation()))
+    } else {
+        model.stabilization().map(Outcome::Stabilized)
+    }
+}
+
+pub fn population(model: &Model) -> usize {
+    model
+        .cells()
+        .iter()
+        .flatten()
+        .filter(|cell| cell.is_alive)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::app::Preset;
+
+    #[test]
+    fn outcomes() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        let outcome = run(&mut model, 100);
+        assert!(matches!(
+            outcome,
+            Outcome::Stabilized(Stabilization { period: 2, .. })
+        ));
+        assert_eq!(outcome.exit_code(), STABILIZED);
+
+        let mut model = Model::new(9, 9, vec![], vec![], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        assert_eq!(run(&mut model, 100), Outcome::Extinct(1));
+
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        assert_eq!(run(&mut model, 1), Outcome::MaxGenerations(1));
+    }
+}
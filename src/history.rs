@@ -0,0 +1,157 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use tracing::debug;
+
+use crate::dirs::data_dir;
+
+/// Runs remembered at most; older ones are forgotten.
+const MAX_RECENT: usize = 20;
+
+/// A run as remembered in the recent history: the pattern loaded, the rule it ran under and
+/// the seed of the random number generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recent {
+    pub preset: String,
+    pub rule: String,
+    pub seed: Option<u64>,
+}
+
+impl Recent {
+    /// How the run is listed in the recent menu.
+    pub fn label(&self) -> String {
+        match self.seed {
+            Some(seed) => format!("{}  {}  seed {seed}", self.preset, self.rule),
+            None => format!("{}  {}", self.preset, self.rule),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        let seed = self.seed.map(|seed| seed.to_string()).unwrap_or_default();
+        format!("{}\t{}\t{seed}", self.preset, self.rule)
+    }
+
+    fn from_line(line: &str) -> Option<Recent> {
+        let mut fields = line.split('\t');
+        let (preset, rule, seed) = (fields.next()?, fields.next()?, fields.next()?);
+        let seed = match seed {
+            "" => None,
+            seed => Some(seed.parse().ok()?),
+        };
+        Some(Recent {
+            preset: String::from(preset),
+            rule: String::from(rule),
+            seed,
+        })
+    }
+}
+
+/// Recently loaded runs, newest first, each listed once. Kept in the data directory as one
+/// tab separated line per run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    entries: Vec<Recent>,
+}
+
+impl History {
+    /// Where the history is kept: `recent.tsv` in the [`data_dir`].
+    pub fn path() -> Option<PathBuf> {
+        Some(data_dir()?.join("recent.tsv"))
+    }
+
+    /// Reads the history at `path`. A missing file is an empty history, and lines that can't
+    /// be read are skipped.
+    pub fn load(path: &Path) -> io::Result<History> {
+        debug!(path = %path.display(), "loading recent runs");
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        let mut entries: Vec<Recent> = text.lines().filter_map(Recent::from_line).collect();
+        entries.truncate(MAX_RECENT);
+        Ok(History { entries })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        debug!(path = %path.display(), "saving recent runs");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text: String = self
+            .entries
+            .iter()
+            .map(|entry| entry.to_line() + "\n")
+            .collect();
+        fs::write(path, text)
+    }
+
+    /// Puts `entry` at the top, removing any earlier record of the same run.
+    pub fn record(&mut self, entry: Recent) {
+        self.entries.retain(|recent| *recent != entry);
+        self.entries.insert(0, entry);
+        self.entries.truncate(MAX_RECENT);
+    }
+
+    pub fn entries(&self) -> &[Recent] {
+        &self.entries
+    }
+
+    pub fn latest(&self) -> Option<&Recent> {
+        self.entries.first()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn recent(preset: &str, seed: Option<u64>) -> Recent {
+        Recent {
+            preset: String::from(preset),
+            rule: String::from("B3/S23"),
+            seed,
+        }
+    }
+
+    #[test]
+    fn records_newest_first() {
+        let mut history = History::default();
+        history.record(recent("Glider", Some(1)));
+        history.record(recent("Acorn", None));
+        history.record(recent("Glider", Some(1)));
+        assert_eq!(
+            history.entries(),
+            [recent("Glider", Some(1)), recent("Acorn", None)]
+        );
+
+        for seed in 0..30 {
+            history.record(recent("Random", Some(seed)));
+        }
+        assert_eq!(history.entries().len(), MAX_RECENT);
+        assert_eq!(history.latest(), Some(&recent("Random", Some(29))));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = env::temp_dir()
+            .join(format!("tui-ca-history-{}", std::process::id()))
+            .join("recent.tsv");
+        assert_eq!(History::load(&path).unwrap(), History::default());
+
+        let mut history = History::default();
+        history.record(recent("Acorn", None));
+        history.record(recent("Random", Some(7)));
+        history.save(&path).unwrap();
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "garbage\n").unwrap();
+        let loaded = History::load(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(loaded, history);
+        assert_eq!(loaded.entries()[0].label(), "Random  B3/S23  seed 7");
+    }
+}
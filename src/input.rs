@@ -0,0 +1,880 @@
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use crate::app::{Anchor, Direction, Message, Model};
+
+/// Cells moved by Shift+WASD unless `--jump` says otherwise.
+pub const DEFAULT_JUMP: i16 = 5;
+
+/// Presses of the same movement key closer together than this count as the key being held.
+const REPEAT_WINDOW: Duration = Duration::from_millis(80);
+/// Held repeats needed before the step doubles.
+const REPEATS_PER_DOUBLING: u32 = 8;
+/// The most cells a single repeat can move the cursor.
+const MAX_STEP: i16 = 8;
+
+/// Speeds up cursor movement while a movement key is held: 1, then 2, 4 and at most
+/// [`MAX_STEP`] cells per key event.
+#[derive(Debug, Default)]
+pub struct Accelerator {
+    last: Option<(Direction, Instant)>,
+    repeats: u32,
+}
+
+impl Accelerator {
+    /// How many cells to move for a key event in `dir` at `now`. `repeat` is set for events the
+    /// terminal itself reports as repeats; others count as repeats if they follow quickly
+    /// enough.
+    pub fn step(&mut self, dir: Direction, now: Instant, repeat: bool) -> i16 {
+        let held = match self.last {
+            Some((last_dir, at)) => last_dir == dir && (repeat || now - at <= REPEAT_WINDOW),
+            None => false,
+        };
+        self.repeats = if held { self.repeats + 1 } else { 0 };
+        self.last = Some((dir, now));
+
+        let doublings = (self.repeats / REPEATS_PER_DOUBLING).min(MAX_STEP.ilog2());
+        1 << doublings
+    }
+}
+
+/// Something a key does while editing or running, which `--bind` can move to another key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    ToggleCell,
+    Pick,
+    Recent,
+    Rollback,
+    Workspace,
+    Randomize,
+    RandomizeView,
+    Ghost,
+    Mark,
+    StepBack,
+    Mutate,
+    Faster,
+    Slower,
+    TimeLapse,
+    Zen,
+    Inset,
+    PinInset,
+    Stats,
+    Correct,
+    Dismiss,
+    Debug,
+    Overlay,
+    Resize,
+    Help,
+    Describe,
+    Inspect,
+    FastForward,
+    RunUntilStable,
+    ToggleEditing,
+    Quit,
+}
+
+impl Action {
+    /// Every action, in the order they're listed in the help.
+    pub const ALL: [Action; 30] = [
+        Action::ToggleCell,
+        Action::Pick,
+        Action::Recent,
+        Action::Rollback,
+        Action::Workspace,
+        Action::Randomize,
+        Action::RandomizeView,
+        Action::Ghost,
+        Action::Mark,
+        Action::StepBack,
+        Action::Mutate,
+        Action::Faster,
+        Action::Slower,
+        Action::TimeLapse,
+        Action::Zen,
+        Action::Inset,
+        Action::PinInset,
+        Action::Stats,
+        Action::Correct,
+        Action::Dismiss,
+        Action::Debug,
+        Action::Overlay,
+        Action::Resize,
+        Action::Help,
+        Action::Describe,
+        Action::Inspect,
+        Action::FastForward,
+        Action::RunUntilStable,
+        Action::ToggleEditing,
+        Action::Quit,
+    ];
+
+    /// The name `--bind` knows the action by.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::ToggleCell => "toggle-cell",
+            Action::Pick => "pick",
+            Action::Recent => "recent",
+            Action::Rollback => "rollback",
+            Action::Workspace => "workspace",
+            Action::Randomize => "randomize",
+            Action::RandomizeView => "randomize-view",
+            Action::Ghost => "ghost",
+            Action::Mark => "mark",
+            Action::StepBack => "step-back",
+            Action::Mutate => "mutate",
+            Action::Faster => "faster",
+            Action::Slower => "slower",
+            Action::TimeLapse => "time-lapse",
+            Action::Zen => "zen",
+            Action::Inset => "inset",
+            Action::PinInset => "pin-inset",
+            Action::Stats => "stats",
+            Action::Correct => "correct",
+            Action::Dismiss => "dismiss",
+            Action::Debug => "debug",
+            Action::Overlay => "overlay",
+            Action::Resize => "resize",
+            Action::Help => "help",
+            Action::Describe => "describe",
+            Action::Inspect => "inspect",
+            Action::FastForward => "fast-forward",
+            Action::RunUntilStable => "run-until-stable",
+            Action::ToggleEditing => "toggle-editing",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::ToggleCell => "toggle cell",
+            Action::Pick => "pick a pattern",
+            Action::Recent => "recent runs",
+            Action::Rollback => "roll back",
+            Action::Workspace => "workspace boards",
+            Action::Randomize => "randomize",
+            Action::RandomizeView => "fill the view with a soup",
+            Action::Ghost => "toggle ghosts",
+            Action::Mark => "mark cell, History rules",
+            Action::StepBack => "step back, Reversible rules",
+            Action::Mutate => "mutate rule",
+            Action::Faster => "faster",
+            Action::Slower => "slower",
+            Action::TimeLapse => "10x more generations a tick",
+            Action::Zen => "zen mode",
+            Action::Inset => "zoom inset",
+            Action::PinInset => "pin inset",
+            Action::Stats => "stats",
+            Action::Correct => "correct the configuration",
+            Action::Dismiss => "dismiss error",
+            Action::Debug => "debug overlay",
+            Action::Overlay => "recording overlay",
+            Action::Resize => "change the board's size",
+            Action::Help => "help",
+            Action::Describe => "describe the board",
+            Action::Inspect => "inspect the cell",
+            Action::FastForward => "skip 1000 generations",
+            Action::RunUntilStable => "run until the board settles, then pause",
+            Action::ToggleEditing => "enter or exit editing mode",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn message(self) -> Message {
+        match self {
+            Action::ToggleCell => Message::ToggleCellState,
+            Action::Pick => Message::OpenPicker,
+            Action::Recent => Message::OpenRecent,
+            Action::Rollback => Message::OpenCheckpoints,
+            Action::Workspace => Message::OpenWorkspace,
+            Action::Randomize => Message::Randomize,
+            Action::RandomizeView => Message::RandomizeView,
+            Action::Ghost => Message::ToggleGhost,
+            Action::Mark => Message::ToggleMark,
+            Action::StepBack => Message::StepBack,
+            Action::Mutate => Message::Mutate,
+            Action::Faster => Message::Faster,
+            Action::Slower => Message::Slower,
+            Action::TimeLapse => Message::CycleTimeLapse,
+            Action::Zen => Message::ToggleZen,
+            Action::Inset => Message::ToggleInset,
+            Action::PinInset => Message::ToggleInsetPin,
+            Action::Stats => Message::SwitchScreen,
+            Action::Correct => Message::OpenPrompt,
+            Action::Dismiss => Message::DismissError,
+            Action::Debug => Message::ToggleDebug,
+            Action::Overlay => Message::ToggleOverlay,
+            Action::Resize => Message::OpenResize,
+            Action::Help => Message::ToggleHelp,
+            Action::Describe => Message::Describe,
+            Action::Inspect => Message::ToggleInspector,
+            Action::FastForward => Message::FastForward,
+            Action::RunUntilStable => Message::RunUntilStable,
+            Action::ToggleEditing => Message::ToggleEditing,
+            Action::Quit => Message::Quit,
+        }
+    }
+
+    /// Whether the action's key works while editing (`true`) or running (`false`).
+    pub fn works(self, editing: bool) -> bool {
+        match self {
+            Action::ToggleCell
+            | Action::Pick
+            | Action::Randomize
+            | Action::RandomizeView
+            | Action::Mark
+            | Action::StepBack
+            | Action::PinInset
+            | Action::Correct
+            | Action::Inspect => editing,
+            Action::Zen | Action::TimeLapse => !editing,
+            _ => true,
+        }
+    }
+
+    fn default_keys(self) -> &'static [KeyCode] {
+        match self {
+            Action::ToggleCell => &[KeyCode::Char(' ')],
+            Action::Pick => &[KeyCode::Char('/')],
+            Action::Recent => &[KeyCode::Char('h')],
+            Action::Rollback => &[KeyCode::Char('b')],
+            Action::Workspace => &[KeyCode::Char('o')],
+            Action::Randomize => &[KeyCode::Char('r')],
+            Action::RandomizeView => &[KeyCode::Char('l')],
+            Action::Ghost => &[KeyCode::Char('g')],
+            Action::Mark => &[KeyCode::Char('m')],
+            Action::StepBack => &[KeyCode::Char(',')],
+            Action::Mutate => &[KeyCode::Char('u')],
+            Action::Faster => &[KeyCode::Char('+'), KeyCode::Char('=')],
+            Action::Slower => &[KeyCode::Char('-')],
+            Action::TimeLapse => &[KeyCode::Char('t')],
+            Action::Zen => &[KeyCode::Char('z')],
+            Action::Inset => &[KeyCode::Char('i')],
+            Action::PinInset => &[KeyCode::Char('p')],
+            Action::Stats => &[KeyCode::Tab],
+            Action::Correct => &[KeyCode::Char('c')],
+            Action::Dismiss => &[KeyCode::Esc],
+            Action::Debug => &[KeyCode::F(12)],
+            Action::Overlay => &[KeyCode::Char('v')],
+            Action::Resize => &[KeyCode::Char('R')],
+            Action::Help => &[KeyCode::Char('?')],
+            Action::Describe => &[KeyCode::Char('n')],
+            Action::Inspect => &[KeyCode::Char('x')],
+            Action::FastForward => &[KeyCode::Char('f')],
+            Action::RunUntilStable => &[KeyCode::Char('F')],
+            Action::ToggleEditing => &[KeyCode::Char('e')],
+            Action::Quit => &[KeyCode::Char('q')],
+        }
+    }
+}
+
+/// A line of the key cheat sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyHelp {
+    pub keys: String,
+    pub description: String,
+    /// The name to give `--bind`, for keys that can be moved.
+    pub action: Option<&'static str>,
+    pub editing: bool,
+    pub running: bool,
+}
+
+/// Maps key events while editing or running to cursor movements and [`Action`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    /// Cells moved by Shift+WASD.
+    pub jump: i16,
+    bindings: Vec<(KeyCode, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap::new(DEFAULT_JUMP, &[])
+    }
+}
+
+impl Keymap {
+    /// The default keys with `bindings` applied on top, in order.
+    pub fn new(jump: i16, bindings: &[(Action, KeyCode)]) -> Keymap {
+        let mut keymap = Keymap {
+            jump,
+            bindings: Action::ALL
+                .iter()
+                .flat_map(|&action| action.default_keys().iter().map(move |&key| (key, action)))
+                .collect(),
+        };
+        for &(action, key) in bindings {
+            keymap.bind(action, key);
+        }
+        keymap
+    }
+
+    /// Moves `action` to `key` alone, taking `key` from any action it did the same for while
+    /// editing or running.
+    pub fn bind(&mut self, action: Action, key: KeyCode) {
+        let shares_a_mode = |other: Action| {
+            [true, false]
+                .iter()
+                .any(|&e| other.works(e) && action.works(e))
+        };
+        self.bindings
+            .retain(|&(bound, other)| other != action && !(bound == key && shares_a_mode(other)));
+        self.bindings.push((key, action));
+    }
+
+    /// The action `key` stands for while editing, or running if `editing` is unset.
+    pub fn action(&self, key: KeyCode, editing: bool) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|&&(bound, action)| bound == key && action.works(editing))
+            .map(|&(_, action)| action)
+    }
+
+    /// The keys bound to `action`, as shown in the help, or `None` if it has none.
+    pub fn keys(&self, action: Action) -> Option<String> {
+        let keys: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|&&(_, bound)| bound == action)
+            .map(|&(key, _)| key_name(key))
+            .collect();
+        (!keys.is_empty()).then(|| keys.join("/"))
+    }
+
+    /// `text` with each `{name}` of an action replaced by its keys.
+    pub fn fill(&self, text: &str) -> String {
+        Action::ALL
+            .iter()
+            .fold(String::from(text), |text, &action| {
+                let keys = self
+                    .keys(action)
+                    .unwrap_or_else(|| String::from("(unbound)"));
+                text.replace(&format!("{{{}}}", action.name()), &keys)
+            })
+    }
+
+    /// Every key, mouse button and action that does something while editing or running, for
+    /// the help and `--print-keys`.
+    pub fn help(&self) -> Vec<KeyHelp> {
+        let fixed = |keys: &str, description: String, editing, running| KeyHelp {
+            keys: String::from(keys),
+            description,
+            action: None,
+            editing,
+            running,
+        };
+        let mut lines = vec![
+            fixed(
+                "WASD",
+                format!("move, Shift to jump {}", self.jump),
+                true,
+                false,
+            ),
+            fixed(
+                "Home/End/PgUp/PgDn",
+                String::from("move to the edge"),
+                true,
+                false,
+            ),
+            fixed(
+                "click",
+                String::from("draw, right-click to erase"),
+                true,
+                false,
+            ),
+            fixed("middle-drag", String::from("pan"), true, false),
+            fixed("scroll", String::from("speed, Ctrl to zoom"), true, true),
+            fixed("F5/F9", String::from("quick save/load"), true, true),
+            fixed("F6", String::from("export as ANSI art"), true, true),
+            fixed("1-9", String::from("quick-save slot"), true, true),
+        ];
+        lines.extend(Action::ALL.iter().filter_map(|&action| {
+            Some(KeyHelp {
+                keys: self.keys(action)?,
+                description: String::from(action.description()),
+                action: Some(action.name()),
+                editing: action.works(true),
+                running: action.works(false),
+            })
+        }));
+        lines
+    }
+
+    /// The one-line summary of the keys for the footer, while editing or running.
+    pub fn hints(&self, editing: bool) -> String {
+        self.help()
+            .into_iter()
+            .filter(|line| if editing { line.editing } else { line.running })
+            .map(|line| format!("({}) {}", line.keys, line.description))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+
+    /// The direction and number of cells `key` moves the cursor, if it's a movement key. WASD
+    /// moves one cell, Shift+WASD moves [`Keymap::jump`] cells, and Home, End, PageUp and
+    /// PageDown move by `i16::MAX` so the cursor stops at the board's edge.
+    pub fn movement(&self, key: &KeyEvent) -> Option<(Direction, i16)> {
+        let movement = match key.code {
+            KeyCode::Char(ch) => {
+                let dir = match ch.to_ascii_lowercase() {
+                    'w' => Direction::Up,
+                    'a' => Direction::Left,
+                    's' => Direction::Down,
+                    'd' => Direction::Right,
+                    _ => return None,
+                };
+                let shifted =
+                    ch.is_ascii_uppercase() || key.modifiers.contains(KeyModifiers::SHIFT);
+                (dir, if shifted { self.jump } else { 1 })
+            }
+            KeyCode::Home => (Direction::Left, i16::MAX),
+            KeyCode::End => (Direction::Right, i16::MAX),
+            KeyCode::PageUp => (Direction::Up, i16::MAX),
+            KeyCode::PageDown => (Direction::Down, i16::MAX),
+            _ => return None,
+        };
+        Some(movement)
+    }
+}
+
+/// Maps mouse buttons on the board to edits and panning.
+#[derive(Debug)]
+pub struct Mousemap {
+    /// Clicking or dragging with this button brings cells to life.
+    pub draw: MouseButton,
+    /// Clicking or dragging with this button kills cells.
+    pub erase: MouseButton,
+    /// Dragging with this button pans the viewport.
+    pub pan: MouseButton,
+    /// The position a pan drag is measured from, moved along a whole cell at a time.
+    pan_from: Option<(u16, u16)>,
+}
+
+impl Default for Mousemap {
+    fn default() -> Mousemap {
+        Mousemap {
+            draw: MouseButton::Left,
+            erase: MouseButton::Right,
+            pan: MouseButton::Middle,
+            pan_from: None,
+        }
+    }
+}
+
+impl Mousemap {
+    /// The message for a press, drag or release of a mapped button on `model`'s board.
+    pub fn message(&mut self, mouse: &MouseEvent, model: &Model) -> Option<Message> {
+        let (column, row) = (mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(button) if button == self.pan => {
+                self.pan_from = Some((column, row));
+                None
+            }
+            MouseEventKind::Drag(button) if button == self.pan => {
+                let (from_column, from_row) = self.pan_from?;
+                let (block_width, block_height) = model.block_size();
+                // dragging the board right shows what's to its left
+                let columns = (from_column as i32 - column as i32) / block_width as i32;
+                let rows = (from_row as i32 - row as i32) / block_height as i32;
+                if columns == 0 && rows == 0 {
+                    return None;
+                }
+                self.pan_from = Some((
+                    (from_column as i32 - columns * block_width as i32) as u16,
+                    (from_row as i32 - rows * block_height as i32) as u16,
+                ));
+                Some(Message::Pan(columns as i16, rows as i16))
+            }
+            MouseEventKind::Up(button) if button == self.pan => {
+                self.pan_from = None;
+                None
+            }
+            MouseEventKind::Down(button) | MouseEventKind::Drag(button) => {
+                let alive = if button == self.draw {
+                    true
+                } else if button == self.erase {
+                    false
+                } else {
+                    return None;
+                };
+                Some(Message::SetCell(model.cell_at(column, row)?, alive))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether `event` is Ctrl-Z, which raw mode delivers as a key press rather than a SIGTSTP.
+pub fn is_suspend(event: &Event) -> bool {
+    match event {
+        Event::Key(key) if key.kind != KeyEventKind::Release => {
+            key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL)
+        }
+        _ => false,
+    }
+}
+
+/// Whether `event` is Ctrl-L, which sets the terminal up again and repaints the whole screen.
+pub fn is_redraw(event: &Event) -> bool {
+    match event {
+        // Windows reports releases as well as presses, which would set the terminal up twice
+        Event::Key(key) if key.kind != KeyEventKind::Release => {
+            key.code == KeyCode::Char('l') && key.modifiers.contains(KeyModifiers::CONTROL)
+        }
+        _ => false,
+    }
+}
+
+/// Merges runs of messages that add up, such as repeated moves in one direction, so a burst of
+/// input that queued up during a slow tick is applied in one go.
+#[derive(Debug, Default)]
+pub struct Coalescer {
+    pending: Option<Message>,
+}
+
+impl Coalescer {
+    /// Applies `msg` to `model`, or holds on to it in case the next message can be merged in.
+    pub fn push(&mut self, msg: Message, model: &mut Model) {
+        let msg = match self.pending.take() {
+            Some(pending) => match merge(&pending, &msg) {
+                Some(merged) => merged,
+                None => {
+                    model.update(pending);
+                    msg
+                }
+            },
+            None => msg,
+        };
+        if matches!(
+            msg,
+            Message::Move(..) | Message::Pan(..) | Message::Resize(.., Anchor::TopLeft)
+        ) {
+            self.pending = Some(msg);
+        } else {
+            model.update(msg);
+        }
+    }
+
+    /// Applies the message held back, if any.
+    pub fn flush(&mut self, model: &mut Model) {
+        if let Some(pending) = self.pending.take() {
+            model.update(pending);
+        }
+    }
+}
+
+/// The single message with the same effect as `first` followed by `second`, if there is one.
+fn merge(first: &Message, second: &Message) -> Option<Message> {
+    match (first, second) {
+        (Message::Move(dir, cells), Message::Move(next_dir, more)) if dir == next_dir => {
+            Some(Message::Move(*dir, cells.saturating_add(*more)))
+        }
+        (Message::Pan(columns, rows), Message::Pan(more_columns, more_rows)) => Some(Message::Pan(
+            columns.saturating_add(*more_columns),
+            rows.saturating_add(*more_rows),
+        )),
+        // the same but for the cells cropped off along the way, which is the point: dragging
+        // the terminal smaller and back shouldn't lose them
+        (Message::Resize(.., Anchor::TopLeft), Message::Resize(.., Anchor::TopLeft)) => {
+            Some(second.clone())
+        }
+        _ => None,
+    }
+}
+
+/// The message for a terminal focus change or a turn of the mouse wheel, if `event` is one.
+/// Scrolling up speeds the simulation up and scrolling down slows it down; with Ctrl held it
+/// zooms in and out instead.
+pub fn event_message(event: &Event) -> Option<Message> {
+    match event {
+        Event::FocusLost => Some(Message::FocusLost),
+        Event::FocusGained => Some(Message::FocusGained),
+        Event::Mouse(mouse) => {
+            let zoom = mouse.modifiers.contains(KeyModifiers::CONTROL);
+            match (mouse.kind, zoom) {
+                (MouseEventKind::ScrollUp, false) => Some(Message::Faster),
+                (MouseEventKind::ScrollDown, false) => Some(Message::Slower),
+                (MouseEventKind::ScrollUp, true) => Some(Message::Zoom(1)),
+                (MouseEventKind::ScrollDown, true) => Some(Message::Zoom(-1)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// How `key` is written in the help and in `--bind`.
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => String::from("Space"),
+        KeyCode::Char(ch) => ch.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
+        KeyCode::Tab => String::from("Tab"),
+        KeyCode::Esc => String::from("Esc"),
+        KeyCode::Enter => String::from("Enter"),
+        KeyCode::Backspace => String::from("Backspace"),
+        other => format!("{other:?}"),
+    }
+}
+
+/// Parses a `--bind` of an action to a key, such as `mutate=x` or `help=F1`. Keys for moving
+/// the cursor, choosing quick-save slots and saving and loading them can't be bound.
+pub fn parse_binding(input: &str) -> Result<(Action, KeyCode), String> {
+    let Some((name, key)) = input.split_once('=') else {
+        return Err(format!("expected ACTION=KEY, got \"{input}\""));
+    };
+    let action = Action::ALL
+        .into_iter()
+        .find(|action| action.name() == name.trim())
+        .ok_or_else(|| {
+            let names: Vec<&str> = Action::ALL.iter().map(|action| action.name()).collect();
+            format!(
+                "unknown action \"{name}\", expected one of {}",
+                names.join(", ")
+            )
+        })?;
+    let key = match key.to_lowercase().as_str() {
+        "space" => KeyCode::Char(' '),
+        "tab" => KeyCode::Tab,
+        "esc" => KeyCode::Esc,
+        "enter" => KeyCode::Enter,
+        "backspace" => KeyCode::Backspace,
+        lower => match (
+            lower.strip_prefix('f').map(str::parse::<u8>),
+            key.chars().count(),
+        ) {
+            (Some(Ok(n)), _) if (1..=12).contains(&n) => KeyCode::F(n),
+            (_, 1) => KeyCode::Char(key.chars().next().expect("one character")),
+            _ => return Err(format!("unknown key \"{key}\"")),
+        },
+    };
+    let reserved = match key {
+        KeyCode::Char(ch) => "wasd123456789".contains(ch.to_ascii_lowercase()),
+        KeyCode::F(n) => matches!(n, 5 | 6 | 9),
+        _ => false,
+    };
+    if reserved {
+        return Err(format!(
+            "{} is kept for moving the cursor, the quick-save slots or exporting",
+            key_name(key)
+        ));
+    }
+    Ok((action, key))
+}
+
+/// Parses a `--jump` size of at least one cell.
+pub fn parse_jump(input: &str) -> Result<i16, String> {
+    match input.trim().parse::<i16>() {
+        Ok(jump) if jump > 0 => Ok(jump),
+        _ => Err(format!(
+            "jump must be a positive number of cells, got \"{input}\""
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::app::{Area, Coords};
+
+    #[test]
+    fn accelerates_while_held() {
+        let mut accelerator = Accelerator::default();
+        let start = Instant::now();
+        let steps: Vec<i16> = (0..40)
+            .map(|i| {
+                accelerator.step(
+                    Direction::Right,
+                    start + Duration::from_millis(30 * i),
+                    false,
+                )
+            })
+            .collect();
+        assert_eq!(steps[0], 1);
+        assert_eq!(steps[8], 2);
+        assert_eq!(steps[16], 4);
+        assert_eq!(steps[39], MAX_STEP);
+
+        // a pause or a new direction starts over
+        let later = start + Duration::from_secs(5);
+        assert_eq!(accelerator.step(Direction::Right, later, false), 1);
+        assert_eq!(accelerator.step(Direction::Up, later, true), 1);
+    }
+
+    #[test]
+    fn movement_keys() {
+        let keymap = Keymap::new(10, &[]);
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert_eq!(
+            keymap.movement(&key(KeyCode::Char('a'))),
+            Some((Direction::Left, 1))
+        );
+        assert_eq!(
+            keymap.movement(&key(KeyCode::Char('S'))),
+            Some((Direction::Down, 10))
+        );
+        assert_eq!(
+            keymap.movement(&KeyEvent::new(KeyCode::Char('d'), KeyModifiers::SHIFT)),
+            Some((Direction::Right, 10))
+        );
+        assert_eq!(
+            keymap.movement(&key(KeyCode::PageUp)),
+            Some((Direction::Up, i16::MAX))
+        );
+        assert_eq!(keymap.movement(&key(KeyCode::Char('e'))), None);
+        assert!(is_suspend(&Event::Key(KeyEvent::new(
+            KeyCode::Char('z'),
+            KeyModifiers::CONTROL
+        ))));
+        assert!(!is_suspend(&Event::Key(key(KeyCode::Char('z')))));
+        assert!(is_redraw(&Event::Key(KeyEvent::new(
+            KeyCode::Char('l'),
+            KeyModifiers::CONTROL
+        ))));
+        assert!(!is_redraw(&Event::Key(key(KeyCode::Char('l')))));
+        assert!(!is_redraw(&Event::Key(KeyEvent::new_with_kind(
+            KeyCode::Char('l'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Release
+        ))));
+        assert!(parse_jump("0").is_err());
+    }
+
+    #[test]
+    fn mouse_buttons() {
+        let mut model = Model::new(20, 20, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.fit_viewport(Area::new(0, 0, 10, 10));
+        let mut mousemap = Mousemap::default();
+        let mut mouse = |kind, column, row| {
+            let event = MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            };
+            mousemap.message(&event, &model)
+        };
+
+        assert_eq!(
+            mouse(MouseEventKind::Down(MouseButton::Left), 2, 3),
+            Some(Message::SetCell(Coords { x: 2, y: 3 }, true))
+        );
+        assert_eq!(
+            mouse(MouseEventKind::Drag(MouseButton::Right), 4, 3),
+            Some(Message::SetCell(Coords { x: 4, y: 3 }, false))
+        );
+        assert_eq!(mouse(MouseEventKind::Down(MouseButton::Left), 15, 3), None);
+
+        assert_eq!(mouse(MouseEventKind::Down(MouseButton::Middle), 5, 5), None);
+        assert_eq!(
+            mouse(MouseEventKind::Drag(MouseButton::Middle), 3, 6),
+            Some(Message::Pan(2, -1))
+        );
+        assert_eq!(mouse(MouseEventKind::Drag(MouseButton::Middle), 3, 6), None);
+        assert_eq!(mouse(MouseEventKind::Up(MouseButton::Middle), 3, 6), None);
+        assert_eq!(mouse(MouseEventKind::Drag(MouseButton::Middle), 0, 0), None);
+    }
+
+    #[test]
+    fn coalesce_runs() {
+        let mut model = Model::new(20, 20, vec![3], vec![2, 3], Duration::from_millis(50));
+        let mut coalescer = Coalescer::default();
+        for _ in 0..3 {
+            coalescer.push(Message::Move(Direction::Right, 2), &mut model);
+        }
+        // nothing is applied until the run ends
+        assert_eq!(*model.current_coords(), Coords { x: 0, y: 0 });
+        coalescer.push(Message::ToggleCellState, &mut model);
+        assert_eq!(*model.current_coords(), Coords { x: 6, y: 0 });
+        assert_eq!(model.is_alive(6, 0), Some(true));
+
+        coalescer.push(Message::Move(Direction::Down, 1), &mut model);
+        coalescer.push(Message::Move(Direction::Left, 1), &mut model);
+        coalescer.flush(&mut model);
+        assert_eq!(*model.current_coords(), Coords { x: 5, y: 1 });
+
+        assert_eq!(
+            merge(&Message::Pan(1, -1), &Message::Pan(2, 0)),
+            Some(Message::Pan(3, -1))
+        );
+        assert_eq!(
+            merge(
+                &Message::Move(Direction::Up, 1),
+                &Message::Move(Direction::Down, 1)
+            ),
+            None
+        );
+
+        // a burst of terminal resizes only crops the board to the last size
+        coalescer.push(Message::Resize(4, 4, Anchor::TopLeft), &mut model);
+        coalescer.push(Message::Resize(30, 30, Anchor::TopLeft), &mut model);
+        coalescer.flush(&mut model);
+        assert_eq!(model.size(), (30, 30));
+        assert_eq!(model.is_alive(6, 0), Some(true));
+    }
+
+    #[test]
+    fn bindings() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action(KeyCode::Char('='), true),
+            Some(Action::Faster)
+        );
+        assert_eq!(keymap.action(KeyCode::Char('z'), true), None);
+        assert_eq!(keymap.action(KeyCode::Char('z'), false), Some(Action::Zen));
+
+        // binding a key moves the action there and takes the key from what had it
+        let binding = parse_binding("mutate=r").unwrap();
+        assert_eq!(binding, (Action::Mutate, KeyCode::Char('r')));
+        let keymap = Keymap::new(DEFAULT_JUMP, &[binding]);
+        assert_eq!(
+            keymap.action(KeyCode::Char('r'), true),
+            Some(Action::Mutate)
+        );
+        assert_eq!(keymap.action(KeyCode::Char('u'), true), None);
+        assert_eq!(keymap.keys(Action::Randomize), None);
+        assert!(keymap.hints(true).contains("(r) mutate rule"));
+        assert!(!keymap.hints(true).contains("randomize"));
+        assert!(!keymap.hints(false).contains("toggle cell"));
+        assert_eq!(keymap.fill("press {mutate}"), "press r");
+
+        assert_eq!(parse_binding("help=f1"), Ok((Action::Help, KeyCode::F(1))));
+        assert_eq!(
+            parse_binding("toggle-cell=space"),
+            Ok((Action::ToggleCell, KeyCode::Char(' ')))
+        );
+        assert!(parse_binding("mutate=W").is_err());
+        assert!(parse_binding("mutate=F5").is_err());
+        assert!(parse_binding("fly=x").is_err());
+        assert!(parse_binding("mutate").is_err());
+        assert!(parse_binding("mutate=xy").is_err());
+    }
+
+    #[test]
+    fn scroll_messages() {
+        let scroll = |kind, modifiers| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column: 0,
+                row: 0,
+                modifiers,
+            })
+        };
+        assert_eq!(
+            event_message(&scroll(MouseEventKind::ScrollUp, KeyModifiers::NONE)),
+            Some(Message::Faster)
+        );
+        assert_eq!(
+            event_message(&scroll(MouseEventKind::ScrollDown, KeyModifiers::CONTROL)),
+            Some(Message::Zoom(-1))
+        );
+        assert_eq!(
+            event_message(&scroll(MouseEventKind::Moved, KeyModifiers::NONE)),
+            None
+        );
+    }
+}
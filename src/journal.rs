@@ -0,0 +1,157 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{dirs::data_dir, stability::Stabilization};
+
+/// The first line of a new journal, naming its columns.
+const COLUMNS: &str = "started\tpreset\trule\tseed\tgenerations\tpopulation\tstabilized";
+
+/// One run of the board, from loading a pattern until the next load or quitting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub started: SystemTime,
+    pub preset: String,
+    pub rule: String,
+    pub seed: Option<u64>,
+    pub generations: u64,
+    /// The population when the run ended.
+    pub population: usize,
+    pub stabilization: Option<Stabilization>,
+}
+
+impl Run {
+    fn to_line(&self) -> String {
+        let seed = self.seed.map(|seed| seed.to_string()).unwrap_or_default();
+        let stabilized = self
+            .stabilization
+            .map(|s| format!("gen {} period {}", s.generation, s.period))
+            .unwrap_or_default();
+        format!(
+            "{}\t{}\t{}\t{seed}\t{}\t{}\t{stabilized}",
+            timestamp(self.started),
+            self.preset,
+            self.rule,
+            self.generations,
+            self.population,
+        )
+    }
+}
+
+/// A log of every run, one tab separated line each, kept with `--journal` as a record of what
+/// was tried.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Journal {
+        Journal { path }
+    }
+
+    /// Where the journal is kept: `journal.tsv` in the [`data_dir`].
+    pub fn path() -> Option<PathBuf> {
+        Some(data_dir()?.join("journal.tsv"))
+    }
+
+    /// Adds `run` to the end of the journal, starting the file if there isn't one.
+    pub fn append(&self, run: &Run) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{COLUMNS}")?;
+        }
+        writeln!(file, "{}", run.to_line())
+    }
+}
+
+/// `time` in UTC, as `YYYY-MM-DDTHH:MM:SSZ`.
+pub fn timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or(0);
+    let (days, secs) = (secs / 86400, secs % 86400);
+
+    // days since the epoch to a civil date, after Howard Hinnant's days_from_civil inverse
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn formats_timestamps() {
+        assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(951_782_400 + 3661);
+        assert_eq!(timestamp(leap_day), "2000-02-29T01:01:01Z");
+    }
+
+    #[test]
+    fn appends_runs() {
+        let path = env::temp_dir()
+            .join(format!("tui-ca-journal-{}", std::process::id()))
+            .join("journal.tsv");
+        let journal = Journal::new(path.clone());
+        let run = Run {
+            started: UNIX_EPOCH,
+            preset: String::from("Random"),
+            rule: String::from("B3/S23"),
+            seed: Some(7),
+            generations: 120,
+            population: 42,
+            stabilization: Some(Stabilization {
+                generation: 100,
+                period: 2,
+            }),
+        };
+        journal.append(&run).unwrap();
+        journal
+            .append(&Run {
+                seed: None,
+                stabilization: None,
+                ..run
+            })
+            .unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                COLUMNS,
+                "1970-01-01T00:00:00Z\tRandom\tB3/S23\t7\t120\t42\tgen 100 period 2",
+                "1970-01-01T00:00:00Z\tRandom\tB3/S23\t\t120\t42\t",
+            ]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use std::{fmt, fs, path::Path};
+
+/// The widest and tallest kernel accepted, which keeps a step at a few hundred additions per
+/// cell.
+pub const MAX_SIZE: usize = 31;
+
+/// A rule that weighs each neighbor by its offset from the cell instead of counting them, as
+/// in weighted Life variants. A cell is born or survives when the weighted sum of its live
+/// neighbors is in [`birth`](Kernel::birth) or [`survival`](Kernel::survival).
+///
+/// Kernels are written as text, with the sums on `birth` and `survival` lines and the weights
+/// as rows of integers centered on the cell; `#` starts a comment:
+///
+/// ```text
+/// # neighbors along the edges count double
+/// birth 5 6
+/// survival 4 5 6 7
+/// 1 2 1
+/// 2 0 2
+/// 1 2 1
+/// ```
+///
+/// A nonzero weight in the center counts the cell itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kernel {
+    weights: Vec<Vec<i32>>,
+    pub birth: Vec<i32>,
+    pub survival: Vec<i32>,
+}
+
+impl Kernel {
+    pub fn load(path: &Path) -> Result<Kernel, String> {
+        let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        Kernel::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Kernel, String> {
+        let (mut weights, mut birth, mut survival) = (vec![], None, None);
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let mut words = line.split_whitespace().peekable();
+            let list = match words.peek() {
+                None => continue,
+                Some(&"birth") => &mut birth,
+                Some(&"survival") => &mut survival,
+                Some(_) => {
+                    let row = words
+                        .map(|word| parse_weight(word, number))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    weights.push(row);
+                    continue;
+                }
+            };
+            if list.is_some() {
+                return Err(format!("line {}: {} given twice", number + 1, line));
+            }
+            words.next();
+            *list = Some(
+                words
+                    .map(|word| parse_weight(word, number))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+
+        let width = weights.first().map_or(0, Vec::len);
+        if let Some(row) = weights.iter().position(|row| row.len() != width) {
+            return Err(format!(
+                "row {} has {} weights, expected {width}",
+                row + 1,
+                weights[row].len()
+            ));
+        }
+        let height = weights.len();
+        if width % 2 == 0 || height % 2 == 0 {
+            return Err(format!(
+                "a {width}x{height} kernel has no center; it needs an odd width and height"
+            ));
+        }
+        if width > MAX_SIZE || height > MAX_SIZE {
+            return Err(format!(
+                "a {width}x{height} kernel is larger than {MAX_SIZE}x{MAX_SIZE}"
+            ));
+        }
+        let (Some(birth), Some(survival)) = (birth, survival) else {
+            return Err(String::from("expected a birth line and a survival line"));
+        };
+        if birth.contains(&0) {
+            return Err(String::from(
+                "a birth on a sum of 0 would fill the empty background",
+            ));
+        }
+
+        Ok(Kernel {
+            weights,
+            birth,
+            survival,
+        })
+    }
+
+    /// How many cells the kernel reaches from its center, across or down.
+    pub fn reach(&self) -> usize {
+        self.weights.len().max(self.weights[0].len()) / 2
+    }
+
+    /// The weighted sum of the live cells of `board` around the cell at `y`, `x`. Cells off the
+    /// board count as dead.
+    pub fn sum(&self, board: &[Vec<bool>], y: usize, x: usize) -> i32 {
+        let (reach_y, reach_x) = (self.weights.len() / 2, self.weights[0].len() / 2);
+        let mut sum = 0;
+        for (row, weights) in self.weights.iter().enumerate() {
+            let Some(line) = (y + row).checked_sub(reach_y).and_then(|ny| board.get(ny)) else {
+                continue;
+            };
+            for (column, weight) in weights.iter().enumerate() {
+                let alive = (x + column)
+                    .checked_sub(reach_x)
+                    .and_then(|nx| line.get(nx))
+                    .copied()
+                    .unwrap_or(false);
+                if alive {
+                    sum += weight;
+                }
+            }
+        }
+        sum
+    }
+
+    /// Whether the cell at `y`, `x` of `board` is alive in the next generation.
+    pub fn verdict(&self, board: &[Vec<bool>], y: usize, x: usize) -> bool {
+        let sum = self.sum(board, y, x);
+        if board[y][x] {
+            self.survival.contains(&sum)
+        } else {
+            self.birth.contains(&sum)
+        }
+    }
+}
+
+fn parse_weight(word: &str, number: usize) -> Result<i32, String> {
+    word.parse()
+        .map_err(|_| format!("line {}: \"{word}\" is not an integer", number + 1))
+}
+
+impl fmt::Display for Kernel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |sums: &[i32]| {
+            sums.iter()
+                .map(i32::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        write!(
+            f,
+            "Weighted {}x{} B{}/S{}",
+            self.weights[0].len(),
+            self.weights.len(),
+            join(&self.birth),
+            join(&self.survival)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_sum() {
+        let kernel = Kernel::parse(
+            "# edges count double\nbirth 5 6\nsurvival 4 5 6 7\n1 2 1\n2 0 2\n1 2 1\n",
+        )
+        .unwrap();
+        assert_eq!(kernel.to_string(), "Weighted 3x3 B5,6/S4,5,6,7");
+
+        let board = vec![
+            vec![true, true, false],
+            vec![false, false, true],
+            vec![false, false, false],
+        ];
+        assert_eq!(kernel.sum(&board, 1, 1), 1 + 2 + 2);
+        assert!(kernel.verdict(&board, 1, 1));
+        // the corner only sees its three neighbors on the board
+        assert_eq!(kernel.sum(&board, 0, 0), 2);
+        assert!(!kernel.verdict(&board, 0, 0));
+
+        assert_eq!(
+            Kernel::parse("birth 3\nsurvival 2\n1 1\n1 1\n"),
+            Err(String::from(
+                "a 2x2 kernel has no center; it needs an odd width and height"
+            ))
+        );
+        assert_eq!(
+            Kernel::parse("birth 3\nsurvival 2\n1 1 1\n1 0\n1 1 1\n"),
+            Err(String::from("row 2 has 2 weights, expected 3"))
+        );
+        assert!(Kernel::parse("birth 3\n1 1 1\n1 0 1\n1 1 1\n").is_err());
+        assert!(Kernel::parse("birth 0\nsurvival 2\n1\n").is_err());
+        assert!(Kernel::parse("birth 3\nsurvival x\n1\n").is_err());
+        assert!(Kernel::parse("birth 3\nsurvival 2\n").is_err());
+    }
+}
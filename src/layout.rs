@@ -0,0 +1,172 @@
+#[cfg(feature = "tui")]
+use ratatui::layout::{Constraint, Layout, Rect};
+
+/// Rows of the header and footer, borders included.
+#[cfg(feature = "tui")]
+const BAR_HEIGHT: u16 = 3;
+
+/// A part of the screen that `--layout` arranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    /// The rule, generation and seed.
+    Header,
+    Board,
+    /// The statistics otherwise shown in place of the board with Tab.
+    Stats,
+    /// The keys and notices.
+    Footer,
+}
+
+impl Pane {
+    fn parse(input: &str) -> Result<Pane, String> {
+        match input.trim().to_lowercase().as_str() {
+            "header" => Ok(Pane::Header),
+            "board" => Ok(Pane::Board),
+            "stats" => Ok(Pane::Stats),
+            "footer" => Ok(Pane::Footer),
+            _ => Err(format!(
+                "unknown pane \"{input}\", expected header, board, stats or footer"
+            )),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Pane::Header => "header",
+            Pane::Board => "board",
+            Pane::Stats => "stats",
+            Pane::Footer => "footer",
+        }
+    }
+
+    /// How much of a row's width the pane takes next to others.
+    #[cfg(feature = "tui")]
+    fn share(self) -> u16 {
+        match self {
+            Pane::Board => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Where each pane goes: rows from top to bottom, each split into panes from left to right.
+/// Written for `--layout` with commas between rows and `|` between panes in a row, so the
+/// default is `header,board,footer` and `header,board|stats` puts the stats on the right and
+/// leaves out the footer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutSpec {
+    rows: Vec<Vec<Pane>>,
+}
+
+impl Default for LayoutSpec {
+    fn default() -> LayoutSpec {
+        LayoutSpec {
+            rows: vec![vec![Pane::Header], vec![Pane::Board], vec![Pane::Footer]],
+        }
+    }
+}
+
+/// The screen split up by a [`LayoutSpec`]. Panes it leaves out have no area.
+#[cfg(feature = "tui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Areas {
+    pub header: Option<Rect>,
+    pub board: Rect,
+    pub stats: Option<Rect>,
+    pub footer: Option<Rect>,
+}
+
+impl LayoutSpec {
+    pub fn parse(input: &str) -> Result<LayoutSpec, String> {
+        let rows = input
+            .split(',')
+            .map(|row| row.split('|').map(Pane::parse).collect())
+            .collect::<Result<Vec<Vec<Pane>>, String>>()?;
+        let panes: Vec<Pane> = rows.iter().flatten().copied().collect();
+        if let Some(pane) = panes
+            .iter()
+            .enumerate()
+            .find_map(|(i, pane)| panes[..i].contains(pane).then_some(pane))
+        {
+            return Err(format!("the {} appears more than once", pane.name()));
+        }
+        if !panes.contains(&Pane::Board) {
+            return Err(String::from("the layout needs a board"));
+        }
+        Ok(LayoutSpec { rows })
+    }
+
+    /// Splits `area` up: rows with only a header or footer take their height, and the rest
+    /// share what's left, with the board twice as wide as panes beside it.
+    #[cfg(feature = "tui")]
+    pub fn areas(&self, area: Rect) -> Areas {
+        let heights = self.rows.iter().map(|row| {
+            if row
+                .iter()
+                .all(|pane| matches!(pane, Pane::Header | Pane::Footer))
+            {
+                Constraint::Length(BAR_HEIGHT)
+            } else {
+                Constraint::Fill(1)
+            }
+        });
+        let mut areas = Areas {
+            header: None,
+            board: Rect::default(),
+            stats: None,
+            footer: None,
+        };
+        for (row, row_area) in self
+            .rows
+            .iter()
+            .zip(Layout::vertical(heights).split(area).iter())
+        {
+            let widths = row.iter().map(|pane| Constraint::Fill(pane.share()));
+            for (pane, pane_area) in row
+                .iter()
+                .zip(Layout::horizontal(widths).split(*row_area).iter())
+            {
+                match pane {
+                    Pane::Header => areas.header = Some(*pane_area),
+                    Pane::Board => areas.board = *pane_area,
+                    Pane::Stats => areas.stats = Some(*pane_area),
+                    Pane::Footer => areas.footer = Some(*pane_area),
+                }
+            }
+        }
+        areas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn arranges_panes() {
+        let screen = Rect::new(0, 0, 90, 30);
+        let areas = LayoutSpec::default().areas(screen);
+        assert_eq!(areas.header, Some(Rect::new(0, 0, 90, 3)));
+        assert_eq!(areas.board, Rect::new(0, 3, 90, 24));
+        assert_eq!(areas.stats, None);
+        assert_eq!(areas.footer, Some(Rect::new(0, 27, 90, 3)));
+
+        let spec = LayoutSpec::parse("board | Stats, footer").unwrap();
+        let areas = spec.areas(screen);
+        assert_eq!(areas.header, None);
+        assert_eq!(areas.board, Rect::new(0, 0, 60, 27));
+        assert_eq!(areas.stats, Some(Rect::new(60, 0, 30, 27)));
+    }
+
+    #[test]
+    fn parses_layouts() {
+        assert_eq!(
+            LayoutSpec::parse(" Board | stats ").unwrap().rows,
+            [[Pane::Board, Pane::Stats]]
+        );
+        assert!(LayoutSpec::parse("header,footer").is_err());
+        assert!(LayoutSpec::parse("board,board").is_err());
+        assert!(LayoutSpec::parse("board,sidebar").is_err());
+    }
+}
@@ -0,0 +1,61 @@
+pub mod alert;
+pub mod app;
+pub mod boundary;
+pub mod checkpoints;
+pub mod color;
+pub mod continuous;
+#[cfg(feature = "tui")]
+pub mod crash;
+pub mod describe;
+pub mod dirs;
+#[cfg(feature = "tui")]
+pub mod doctor;
+pub mod errors;
+pub mod evolve;
+pub mod explorer;
+#[cfg(feature = "tui")]
+pub mod export;
+pub mod headless;
+pub mod history;
+#[cfg(feature = "tui")]
+pub mod input;
+pub mod journal;
+pub mod kernel;
+pub mod layout;
+#[cfg(feature = "tui")]
+pub mod logging;
+#[cfg(feature = "tui")]
+pub mod manifest;
+pub mod metrics;
+#[cfg(feature = "tui")]
+pub mod mirror;
+pub mod mnca;
+pub mod neighborhood;
+pub mod overlay;
+pub mod pattern;
+pub mod picker;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+pub mod replay;
+pub mod rng;
+pub mod rulebook;
+pub mod scenario;
+pub mod screensaver;
+pub mod session;
+pub mod soup;
+pub mod stability;
+pub mod stats;
+#[cfg(feature = "tui")]
+pub mod status;
+#[cfg(feature = "tui")]
+pub mod sweep;
+pub mod table;
+pub mod theme;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod tutorial;
+#[cfg(feature = "tui")]
+pub mod ui;
+#[cfg(feature = "tui")]
+pub mod wallpaper;
+pub mod workspace;
@@ -0,0 +1,52 @@
+use std::{
+    fs::{self, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use tracing::Level;
+
+use crate::dirs::log_dir;
+
+/// Where the log goes when `--verbose` is given without `--log-file`: `tui-cellular-automaton.log`
+/// in the [`log_dir`].
+pub fn default_path() -> Option<PathBuf> {
+    Some(log_dir()?.join("tui-cellular-automaton.log"))
+}
+
+/// The most detailed level logged at a `--verbose` count: info by default, then debug, then
+/// trace, which includes every generation stepped.
+pub fn level(verbosity: u8) -> Level {
+    match verbosity {
+        0 => Level::INFO,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+/// Sends log events up to `level` to the end of the file at `path`, since the terminal is
+/// taken up by the TUI.
+pub fn init(path: &Path, level: Level) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(Mutex::new(file))
+        .try_init()
+        .map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_levels() {
+        assert_eq!(level(0), Level::INFO);
+        assert_eq!(level(1), Level::DEBUG);
+        assert_eq!(level(3), Level::TRACE);
+    }
+}
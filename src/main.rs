@@ -1,155 +1,1040 @@
-use std::{error::Error, io, time::Duration};
+use std::{
+    env, fs,
+    io::{self, Write},
+    num::NonZeroUsize,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
-use app::{Cli, Config, Direction, Message, Model, Preset, State};
 use clap::Parser;
-use errors::install_hooks;
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result, Section,
+};
 use ratatui::{
     crossterm::{
-        event::{self, poll, read, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
-        execute,
-        terminal::{
-            self, disable_raw_mode, enable_raw_mode, size, EnterAlternateScreen,
-            LeaveAlternateScreen,
+        event::{
+            self, poll, read, DisableFocusChange, DisableMouseCapture, Event, KeyCode, KeyModifiers,
         },
+        execute,
+        terminal::{disable_raw_mode, size, LeaveAlternateScreen},
     },
-    prelude::{Backend, CrosstermBackend},
+    layout::Rect,
+    prelude::Backend,
     Terminal,
 };
-use tui::init;
-use ui::view;
-
-mod app;
-mod errors;
-mod tui;
-mod ui;
+#[cfg(feature = "prometheus")]
+use ratatui_cellular_automaton::prometheus;
+use ratatui_cellular_automaton::{
+    app::{
+        Anchor, Cli, CollisionPolicy, Command, Config, ConfigError, Direction, Message, Model,
+        Preset, State, MIN_BOARD_SIZE,
+    },
+    crash, dirs, doctor,
+    errors::{install_hooks, ErrorReport},
+    evolve::{self, Search},
+    export, headless,
+    history::History,
+    input::{
+        event_message, is_redraw, is_suspend, Accelerator, Action, Coalescer, Keymap, Mousemap,
+    },
+    journal::Journal,
+    layout::LayoutSpec,
+    logging,
+    manifest::Manifest,
+    metrics::CountingAllocator,
+    mirror::Mirror,
+    pattern::Transform,
+    replay::{replayable_args, Header, Recorder, Replay},
+    rng::RngSource,
+    scenario::{Scenario, Waiting},
+    screensaver::Screensaver,
+    session, status, sweep,
+    theme::Theme,
+    tui::{self, init, restore, FrameLimiter},
+    tutorial::{self, Lesson, Tutorial},
+    ui::view,
+    wallpaper,
+};
+use signal_hook::consts::{SIGINT, SIGTERM};
+use tracing::{debug, info, trace, warn};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    
-    let cli = Cli::parse();
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
-    let rulestring = {
-        if let Some(item) = cli.rulestring.as_deref() {
-            String::from(item)
-        } else {
-            String::from("B3/S23")
+fn main() -> Result<()> {
+    // installed first so that errors from the command line get suggestions too
+    install_hooks()?;
+    let args: Vec<String> = env::args().collect();
+    let cli = Cli::parse_from(&args);
+    if let Some(Command::Doctor) = cli.command {
+        doctor::run()?;
+        return Ok(());
+    }
+    if let Some(Command::Evolve {
+        fitness,
+        population,
+        rounds,
+        max_generations,
+        width,
+        height,
+    }) = cli.command
+    {
+        let search = Search {
+            fitness,
+            population: population as usize,
+            rounds,
+            max_generations,
+            width,
+            height,
+            density: cli.density,
+        };
+        let seed = cli.seed.unwrap_or_else(rand::random);
+        println!("searching with --seed {seed}");
+        for (rank, candidate) in evolve::run(&search, seed).iter().enumerate() {
+            println!("{}. {candidate}", rank + 1);
+        }
+        return Ok(());
+    }
+    if let Some(Command::Sweep {
+        rules,
+        seeds,
+        generations,
+        width,
+        height,
+        jobs,
+        output,
+    }) = &cli.command
+    {
+        let rules = sweep::expand(rules)
+            .map_err(|reason| eyre!(reason))
+            .suggestion("digits in square brackets may each be there or not, as in B3[678]/S23")?;
+        let jobs =
+            jobs.unwrap_or_else(|| thread::available_parallelism().unwrap_or(NonZeroUsize::MIN));
+        let sweep = sweep::Sweep {
+            seeds: *seeds,
+            seed: cli.seed.unwrap_or_else(rand::random),
+            generations: *generations,
+            width: *width,
+            height: *height,
+            density: cli.density,
+            jobs,
+        };
+        eprintln!(
+            "sweeping {} rules with --seed {} on {jobs} threads",
+            rules.len(),
+            sweep.seed
+        );
+        let summaries = sweep::run(&rules, &sweep, |done| {
+            eprint!("\r{done}/{} rules", rules.len());
+            let _ = io::stderr().flush();
+        });
+        eprintln!();
+        let mut csv = format!("{}\n", sweep::CSV_HEADER);
+        for summary in &summaries {
+            csv.push_str(&summary.csv());
+            csv.push('\n');
         }
+        match output {
+            Some(path) => fs::write(path, csv)
+                .wrap_err_with(|| format!("couldn't write the sweep to {}", path.display()))?,
+            None => print!("{csv}"),
+        }
+        return Ok(());
+    }
+    let (record, autosave_on_exit) = (cli.record.clone(), cli.autosave_on_exit);
+    if let Some(dir) = &cli.portable {
+        dirs::set_portable(dir.clone());
+    }
+    let log = if cli.log_file.is_some() || cli.verbose > 0 {
+        let path = cli
+            .log_file
+            .clone()
+            .or_else(logging::default_path)
+            .ok_or_else(|| eyre!("no log directory, as there's no home directory"))
+            .suggestion("give the log a path with --log-file")?;
+        logging::init(&path, logging::level(cli.verbose))
+            .wrap_err_with(|| format!("couldn't open the log file {}", path.display()))
+            .suggestion("check the directory exists and is writable, or pick another --log-file")?;
+        Some(path)
+    } else {
+        None
     };
-
-    let preset_string = {
-        if let Some(item) = cli.preset_string.as_deref() {
-            String::from(item)
-        } else {
-            String::from("None")
+    if cli.crash_report {
+        let dir = dirs::data_dir()
+            .ok_or_else(|| {
+                eyre!("no data directory for crash reports, as there's no home directory")
+            })
+            .suggestion("keep files in a directory of your choosing with --portable")?
+            .join("crashes");
+        crash::enable(dir, log);
+    }
+    info!(?args, "starting");
+    // a replay starts from the command line it was recorded with
+    let replay = match &cli.replay {
+        Some(path) => Some(
+            Replay::load(path)
+                .wrap_err_with(|| format!("couldn't read the replay {}", path.display()))
+                .suggestion("replays are written by running with --record FILE")?,
+        ),
+        None => None,
+    };
+    let mut cli = match &replay {
+        Some(replay) => Cli::try_parse_from(&replay.header.args)
+            .wrap_err("the replay's command line isn't understood by this version")
+            .suggestion("replay it with the version that recorded it")?,
+        None => cli,
+    };
+    let manifest = match cli.manifest.clone() {
+        Some(path) => {
+            let mut manifest = Manifest::load(&path)
+                .map_err(|reason| eyre!(reason))
+                .wrap_err_with(|| format!("couldn't read the manifest {}", path.display()))?;
+            // record the seed, so the completed manifest reproduces the run
+            manifest.seed = manifest.seed.or_else(|| Some(rand::random()));
+            manifest.apply(&mut cli);
+            Some((path, manifest))
         }
+        None => None,
     };
 
-    let tickrate = {
-        if let Some(item) = cli.tickrate {
-            item
-        } else {
-            100
+    let mut config = Config::from_cli(&cli);
+    if let Some(replay) = &replay {
+        config.seed = replay.header.seed;
+        config.autosave_on_exit = autosave_on_exit;
+    }
+    // nobody would see warnings without a terminal
+    let headless = matches!(
+        cli.command,
+        Some(Command::Headless { .. } | Command::Daemon { .. })
+    ) || manifest.is_some();
+    if (cli.strict || headless) && !config.errors.is_empty() {
+        exit_strict(&config.errors);
+    }
+
+    if cli.print_keys {
+        print_keys(&config.keymap);
+        return Ok(());
+    }
+
+    if cli.list_presets {
+        for preset in Preset::ALL {
+            println!("{}", preset.name());
+        }
+        for name in config.library.names() {
+            println!("{name}");
         }
+        return Ok(());
+    }
+
+    let scenario = match &cli.command {
+        Some(Command::Lesson { file }) => {
+            let scenario = Scenario::load(file)
+                .map_err(|reason| eyre!(reason))
+                .wrap_err_with(|| format!("couldn't read the lesson {}", file.display()))?;
+            if let Some(name) = scenario
+                .patterns()
+                .find(|name| config.library.resolve(name).is_none())
+            {
+                return Err(eyre!("the lesson loads the unknown pattern \"{name}\""))
+                    .suggestion("run with --list-presets to see the patterns there are");
+            }
+            Some(scenario)
+        }
+        _ => None,
     };
 
-    let config = Config::build(&preset_string, &rulestring, tickrate);
+    if let Some((path, manifest)) = manifest {
+        let (width, height) = (manifest.width as i16, manifest.height as i16);
+        let (mut model, errors) = build_model(config, (width - 1, height - 1));
+        if !errors.is_empty() {
+            exit_strict(&errors);
+        }
+        let outcome = headless::run(&mut model, manifest.generations);
+        info!(%outcome, "experiment over");
+        if let Some(board) = manifest.final_board_path(&path) {
+            let name = manifest.title.as_deref().unwrap_or("final board");
+            session::save(&model, name, &board).wrap_err_with(|| {
+                format!("couldn't save the final board to {}", board.display())
+            })?;
+        }
+        let output = manifest.output_path(&path);
+        fs::write(
+            &output,
+            manifest.completed(&outcome, headless::population(&model)),
+        )
+        .wrap_err_with(|| format!("couldn't write the results to {}", output.display()))?;
+        println!("{outcome}");
+        process::exit(outcome.exit_code());
+    }
 
-    install_hooks()?;
-    let mut terminal = init()?;
+    if let Some(Command::Headless {
+        max_generations,
+        width,
+        height,
+    }) = cli.command
+    {
+        let (mut model, errors) = build_model(config, (width as i16 - 1, height as i16 - 1));
+        if !errors.is_empty() {
+            exit_strict(&errors);
+        }
+        let outcome = headless::run(&mut model, max_generations);
+        info!(%outcome, "headless run over");
+        println!("{outcome}");
+        process::exit(outcome.exit_code());
+    }
 
-    let (columns, rows) = size()?;
+    // quit through the main loop on a signal, so the terminal is restored and the board saved
+    let shutdown = Arc::new(AtomicBool::new(false));
+    for signal in [SIGINT, SIGTERM] {
+        signal_hook::flag::register(signal, Arc::clone(&shutdown))?;
+    }
+    if let Some(addr) = cli.status_addr {
+        status::serve(addr)
+            .wrap_err_with(|| format!("couldn't serve the status on {addr}"))
+            .suggestion("pick another port, or 127.0.0.1:0 for any free one")?;
+    }
+    #[cfg(feature = "prometheus")]
+    if let Some(addr) = cli.metrics_addr {
+        prometheus::serve(addr)
+            .wrap_err_with(|| format!("couldn't serve metrics on {addr}"))
+            .suggestion("pick another port, or 127.0.0.1:0 for any free one")?;
+    }
 
-    let mut model = Model::new(
-        (rows as i16) - 6 - 1,
-        (columns as i16) - 1,
-        config.rule.birth_list,
-        config.rule.survival_list,
-        config.tickrate,
+    if let Some(Command::Daemon {
+        output,
+        resolution,
+        cell_size,
+        interval,
+        reseed,
+    }) = &cli.command
+    {
+        let (width, height) = resolution.cells(*cell_size);
+        let (mut model, errors) = build_model(config, (width as i16 - 1, height as i16 - 1));
+        if !errors.is_empty() {
+            exit_strict(&errors);
+        }
+        let settings = wallpaper::Settings {
+            resolution: *resolution,
+            cell_size: *cell_size,
+            interval: *interval,
+            reseed: *reseed,
+        };
+        return wallpaper::run(&mut model, &settings, output, &shutdown)
+            .wrap_err_with(|| format!("couldn't write the wallpaper {}", output.display()));
+    }
+
+    let screensaver = match config.screensaver {
+        true => Some(
+            Screensaver::new(&config.playlist, config.playlist_turn, &config.theme)
+                .map_err(|reason| eyre!(reason))
+                .suggestion("pick playlist themes with cells as wide as --theme's")?,
+        ),
+        false => None,
+    };
+
+    // a named pipe only opens once something reads from it, so wait before taking the screen
+    let mut mirror = match &cli.mirror {
+        Some(path) => Some(
+            Mirror::open(path)
+                .wrap_err_with(|| format!("couldn't mirror to {}", path.display()))?,
+        ),
+        None => None,
+    };
+    let mut terminal = init()?;
+
+    let tutorial = matches!(cli.command, Some(Command::Tutorial));
+    let (columns, rows) = board_cells(
+        &config.layout,
+        &config.theme,
+        tutorial || scenario.is_some(),
+        config.compare.is_some(),
+        size()?,
     );
+    let (max_x, max_y) = match &replay {
+        Some(replay) => replay.header.max_coords,
+        None => ((columns as i16) - 1, (rows as i16) - 1),
+    };
 
-    model.load_preset(config.preset);
-    run_model(&mut terminal, &mut model)?;
+    let mut limiter = FrameLimiter::new(config.frame_interval);
+    let (journal, autosave_on_exit, run_until_stable) = (
+        config.journal,
+        config.autosave_on_exit,
+        config.run_until_stable,
+    );
+    let (mut model, errors) = build_model(config, (max_x, max_y));
+    if cli.strict && !errors.is_empty() {
+        restore()?;
+        exit_strict(&errors);
+    }
+    for error in &errors {
+        warn!(%error, "configuration error");
+    }
+    model.set_config_errors(errors);
+    model.remember();
+    if tutorial {
+        model.set_tutorial(Some(Tutorial::default()));
+    }
+    model.set_scenario(scenario);
+    if run_until_stable {
+        model.update(Message::RunUntilStable);
+    }
+    if journal {
+        match Journal::path() {
+            Some(path) => model.set_journal(Journal::new(path)),
+            None => model.report(ErrorReport::message(
+                "No data directory for the journal (no home directory)",
+            )),
+        }
+    }
+    if let Some(path) = record {
+        let header = Header {
+            args: replayable_args(&args),
+            seed: model.rng().seed(),
+            max_coords: (max_x, max_y),
+        };
+        match Recorder::create(&path, &header) {
+            Ok(recorder) => model.set_recorder(recorder),
+            Err(error) => model.report(ErrorReport::new(
+                format!("Couldn't record to {}", path.display()),
+                &error,
+            )),
+        }
+    }
+    if let Some(replay) = replay {
+        play(
+            &mut terminal,
+            &mut model,
+            replay,
+            &mut limiter,
+            &mut mirror,
+            &shutdown,
+        )?;
+    }
+    match screensaver {
+        Some(screensaver) => run_screensaver(
+            &mut terminal,
+            &mut model,
+            screensaver,
+            limiter,
+            &mut mirror,
+            &shutdown,
+        )?,
+        None => run_model(&mut terminal, &mut model, limiter, &mut mirror, &shutdown)?,
+    }
+    info!(
+        generation = model.generation(),
+        signalled = shutdown.load(Ordering::Relaxed),
+        "quitting"
+    );
 
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
 
     terminal.show_cursor()?;
 
+    if let Err(error) = model.log_run() {
+        eprintln!("couldn't write the journal: {error}");
+    }
+    if let Some(path) = History::path() {
+        if let Err(error) = model.history().save(&path) {
+            eprintln!("couldn't save recent runs to {}: {error}", path.display());
+        }
+    }
+    if autosave_on_exit {
+        match session::autosave_path() {
+            Some(path) => match session::save(&model, "autosave", &path) {
+                Ok(()) => eprintln!("saved the board to {}", path.display()),
+                Err(error) => eprintln!("couldn't save the board to {}: {error}", path.display()),
+            },
+            None => eprintln!("couldn't save the board: no data directory (no home directory)"),
+        }
+    }
+
     Ok(())
 }
 
-fn run_model<B: Backend>(terminal: &mut Terminal<B>, model: &mut Model) -> io::Result<()> {
-    loop {
-        terminal.draw(|f| view(f, model))?;
-        match model.state() {
-            State::Running => {
-                terminal.draw(|f| view(f, model))?;
-                if poll(Duration::from_millis(model.tickrate() as u64))? {
-                    if let Event::Key(key) = read()? {
-                        if key.kind == event::KeyEventKind::Release {
-                            continue;
-                        }
-
-                        if let KeyCode::Char(ch) = key.code {
-                            match ch {
-                                'e' => {
-                                    model.update(Message::ToggleEditing);
-                                }
-                                'q' => {
-                                    model.update(Message::Quit);
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                } else {
-                    model.update(Message::Idle);
-                }
+/// The cells across and down that fit in the board's part of a `columns` by `rows` terminal
+/// laid out as `layout`, beside the tutorial or lesson's panel if there's one, and the
+/// comparison board if there's one.
+fn board_cells(
+    layout: &LayoutSpec,
+    theme: &Theme,
+    panel: bool,
+    compare: bool,
+    (columns, rows): (u16, u16),
+) -> (u16, u16) {
+    let board = layout.areas(Rect::new(0, 0, columns, rows)).board;
+    let (columns, rows) = (board.width, board.height);
+    // the tutorial and lessons share the panel beside the board
+    let columns = if panel {
+        columns.saturating_sub(tutorial::PANEL_WIDTH)
+    } else {
+        columns
+    };
+    // a comparison board takes the right half, after a one column gap
+    let columns = if compare {
+        columns.saturating_sub(1) / 2
+    } else {
+        columns
+    };
+    // a terminal too small to show the board still gets the smallest one there is
+    let min = MIN_BOARD_SIZE as u16;
+    ((columns / theme.cell_width()).max(min), rows.max(min))
+}
+
+/// Sets up a board of `max_coords` from the command line, returning it with any problems found
+/// while placing patterns on it, on top of those in `config`.
+fn build_model(config: Config, (max_x, max_y): (i16, i16)) -> (Model, Vec<ConfigError>) {
+    let mut model = Model::new(
+        max_y,
+        max_x,
+        config.rule.birth_list,
+        config.rule.survival_list,
+        config.tickrate,
+    );
+
+    model.set_theme(config.theme);
+    model.set_layout(config.layout);
+    model.set_density(config.density);
+    model.set_symmetry(config.symmetry);
+    model.set_pause_on_focus_loss(config.pause_on_focus_loss);
+    model.set_accessible(config.accessible);
+    model.set_fade(config.fade);
+    model.set_time_lapse(config.time_lapse);
+    model.set_alerts(config.alerts);
+    model.set_export_html(config.export_html);
+    model.set_overlay(config.overlay);
+    model.set_library(config.library);
+    model.set_continuous(config.continuous);
+    model.set_kernel(config.kernel);
+    if let Some(neighborhood) = config.neighborhood {
+        model.set_neighborhood(neighborhood);
+    }
+    model.set_table(config.table);
+    model.set_mnca(config.mnca);
+    model.set_boundary(config.boundary);
+    model.set_keymap(config.keymap);
+    if let Some(seed) = config.seed {
+        model.set_rng(RngSource::seeded(seed));
+    }
+
+    let mut errors = config.errors;
+    let pattern = config.preset.pattern();
+    let placement = match &pattern {
+        Some(pattern) => config
+            .placement
+            .for_pattern(pattern, config.transform, model.size()),
+        None => config.placement,
+    };
+    let pattern_size = pattern.map(|pattern| pattern.transformed(config.transform).size());
+    if let Some(pattern_size) = pattern_size {
+        if let Err(reason) = placement.check_fit(pattern_size, model.size()) {
+            errors.push(ConfigError::Placement {
+                preset: String::from(config.preset.name()),
+                reason,
+            });
+        }
+    }
+    let origin = placement.origin(pattern_size.unwrap_or_default(), model.size());
+    model.load_preset_at(config.preset, origin, config.transform);
+
+    for (spec, preset) in config.places {
+        let transform = Transform {
+            rotation: spec.rotation,
+            ..Transform::default()
+        };
+        let pattern = preset
+            .pattern()
+            .expect("only fixed patterns are accepted by --place");
+        let report = model
+            .place(&pattern, spec.at, transform, CollisionPolicy::Merge)
+            .unwrap_or_else(|report| report);
+        if report.clipped > 0 {
+            errors.push(ConfigError::Place {
+                spec: spec.to_string(),
+                reason: format!("{} live cells fell off the board", report.clipped),
+            });
+        }
+        if report.collisions > 0 {
+            errors.push(ConfigError::Place {
+                spec: spec.to_string(),
+                reason: format!(
+                    "collides with earlier patterns at {} cells",
+                    report.collisions
+                ),
+            });
+        }
+    }
+    if let Some(rule) = config.compare {
+        model.set_comparison(rule);
+    }
+    model.set_checkpoint_interval(config.checkpoint_interval);
+    model.set_mutation_interval(config.mutate_every);
+    model.set_history(config.history);
+    model.set_workspace(config.workspace);
+    (model, errors)
+}
+
+/// Prints the cheat sheet of keys for `--print-keys`, with the name of each action for `--bind`.
+fn print_keys(keymap: &Keymap) {
+    let help = keymap.help();
+    let width = help
+        .iter()
+        .map(|line| line.keys.len())
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{:width$}  {:<16} {:<17} WHAT IT DOES",
+        "KEYS", "ACTION", "WHEN"
+    );
+    for line in help {
+        let when = match (line.editing, line.running) {
+            (true, true) => "editing, running",
+            (true, false) => "editing",
+            _ => "running",
+        };
+        println!(
+            "{:width$}  {:<16} {when:<17} {}",
+            line.keys,
+            line.action.unwrap_or("-"),
+            line.description
+        );
+    }
+}
+
+/// Reports problems in the command line and exits with [`headless::CONFIG_ERROR`], when
+/// headless or under --strict.
+fn exit_strict(errors: &[ConfigError]) -> ! {
+    for error in errors {
+        eprintln!("error: {error}");
+    }
+    process::exit(headless::CONFIG_ERROR)
+}
+
+fn run_model<B: Backend>(
+    terminal: &mut Terminal<B>,
+    model: &mut Model,
+    mut limiter: FrameLimiter,
+    mirror: &mut Option<Mirror>,
+    shutdown: &AtomicBool,
+) -> io::Result<()> {
+    // how often a fade is redrawn, about 30 frames a second
+    const FADE_FRAME: Duration = Duration::from_millis(33);
+
+    let mut accelerator = Accelerator::default();
+    let mut mousemap = Mousemap::default();
+    while *model.state() != State::Done {
+        crash::remember(model);
+        status::publish(model);
+        if model.take_bell() {
+            io::stdout().write_all(b"\x07")?;
+        }
+        #[cfg(feature = "prometheus")]
+        prometheus::record(model);
+        // only a running or fast-forwarding simulation redraws often enough to need limiting
+        let busy = matches!(model.state(), State::Running | State::FastForwarding { .. });
+        if !busy || limiter.due(Instant::now()) {
+            draw(terminal, model, mirror)?;
+        }
+        // while cells fade, wake up for each frame of the fade as well as for the next tick
+        let fading = model.fade_progress().is_some();
+        let timeout = match model.state() {
+            State::Running if fading => Some(model.until_next_tick().min(FADE_FRAME)),
+            State::Running => Some(model.tickrate()),
+            // fast-forwarding steps its next batch as soon as the last one is drawn
+            State::FastForwarding { .. } => Some(Duration::ZERO),
+            _ => None,
+        };
+        let ready = wait_for_event(timeout, shutdown)?;
+        if shutdown.load(Ordering::Relaxed) {
+            break;
+        }
+        if !ready {
+            if !fading || model.until_next_tick().is_zero() {
+                model.update(Message::Idle);
             }
+            continue;
+        }
+        debug!("handling input");
 
-            State::Editing => {
-                if let Event::Key(key) = event::read()? {
+        // handle everything that queued up during the last tick or draw before drawing again,
+        // so a slow board doesn't replay keystrokes one frame at a time
+        let mut coalescer = Coalescer::default();
+        let mut depth = 0;
+        loop {
+            let event = read()?;
+            depth += 1;
+            trace!(?event, "event");
+            #[cfg(unix)]
+            if is_suspend(&event) {
+                tui::suspend(terminal)?;
+                break;
+            }
+            if is_redraw(&event) {
+                debug!("reinitializing the terminal");
+                tui::reinit(terminal)?;
+            } else if let Event::Resize(columns, rows) = event {
+                // the board follows the terminal, keeping its cells where they are on screen
+                let (width, height) = board_cells(
+                    model.layout(),
+                    model.theme(),
+                    model.tutorial().is_some() || model.scenario().is_some(),
+                    model.comparison().is_some(),
+                    (columns, rows),
+                );
+                coalescer.push(Message::Resize(width, height, Anchor::TopLeft), model);
+            } else if let Some(slot_key) = slot_key(&event, model) {
+                coalescer.flush(model);
+                quick_slot(model, slot_key);
+            } else if is_export(&event, model) {
+                coalescer.flush(model);
+                export_board(model);
+            } else if let Some(msg) = message_for(&event, model, &mut accelerator, &mut mousemap) {
+                coalescer.push(msg, model);
+            }
+            if *model.state() == State::Done || !poll(Duration::ZERO)? {
+                break;
+            }
+        }
+        coalescer.flush(model);
+        model.set_queue_depth(depth);
+    }
+
+    Ok(())
+}
+
+/// Draws `model`, and the same frame to `mirror` if there is one. A mirror that can't be
+/// written to any more, such as a pipe whose reader has gone, is reported and dropped.
+fn draw<B: Backend>(
+    terminal: &mut Terminal<B>,
+    model: &mut Model,
+    mirror: &mut Option<Mirror>,
+) -> io::Result<()> {
+    let frame = terminal.draw(|f| view(f, model))?;
+    if let Some(sink) = mirror {
+        if let Err(error) = sink.write(frame.buffer) {
+            warn!(%error, "mirroring failed");
+            model.report(ErrorReport::new(
+                format!("Stopped mirroring to {}", sink.path().display()),
+                &error,
+            ));
+            *mirror = None;
+        }
+    }
+    Ok(())
+}
+
+/// Feeds a recorded session to the model at the pace it was recorded, drawing as it goes. q or
+/// Ctrl-C quits; other keys are ignored until the replay is over.
+fn play<B: Backend>(
+    terminal: &mut Terminal<B>,
+    model: &mut Model,
+    replay: Replay,
+    limiter: &mut FrameLimiter,
+    mirror: &mut Option<Mirror>,
+    shutdown: &AtomicBool,
+) -> io::Result<()> {
+    let start = Instant::now();
+    draw(terminal, model, mirror)?;
+    for (at, msg) in replay.messages {
+        // leave out the recorded quit, so the session carries on from where it ended
+        if msg == Message::Quit {
+            break;
+        }
+        loop {
+            let wait = (start + at).saturating_duration_since(Instant::now());
+            if wait.is_zero() {
+                break;
+            }
+            if wait_for_event(Some(wait), shutdown)? {
+                if let Event::Key(key) = read()? {
                     if key.kind == event::KeyEventKind::Release {
                         continue;
                     }
-
-                    if let KeyCode::Char(ch) = key.code {
-                        match ch {
-                            'w' => {
-                                model.update(Message::Move(Direction::Up));
-                            }
-                            'a' => {
-                                model.update(Message::Move(Direction::Left));
-                            }
-                            's' => {
-                                model.update(Message::Move(Direction::Down));
-                            }
-                            'd' => {
-                                model.update(Message::Move(Direction::Right));
-                            }
-                            'e' => {
-                                model.update(Message::ToggleEditing);
-                            }
-                            'q' => {
-                                model.update(Message::Quit);
-                            }
-                            ' ' => {
-                                model.update(Message::ToggleCellState);
-                            }
-                            _ => {}
-                        }
+                    let interrupt = key.code == KeyCode::Char('c')
+                        && key.modifiers.contains(KeyModifiers::CONTROL);
+                    if key.code == KeyCode::Char('q') || interrupt {
+                        model.update(Message::Quit);
+                        return Ok(());
                     }
                 }
             }
-
-            State::Done => {
-                break;
+            if shutdown.load(Ordering::Relaxed) {
+                return Ok(());
             }
         }
+
+        model.update(msg);
+        if limiter.due(Instant::now()) {
+            draw(terminal, model, mirror)?;
+        }
     }
+    Ok(())
+}
 
+/// Runs the board full screen for `--screensaver`, kept going by `screensaver`, until any key
+/// is pressed. Mouse movement, focus changes and resizes don't count.
+fn run_screensaver<B: Backend>(
+    terminal: &mut Terminal<B>,
+    model: &mut Model,
+    mut screensaver: Screensaver,
+    mut limiter: FrameLimiter,
+    mirror: &mut Option<Mirror>,
+    shutdown: &AtomicBool,
+) -> io::Result<()> {
+    screensaver.tend(model, Instant::now());
+    while *model.state() != State::Done {
+        crash::remember(model);
+        status::publish(model);
+        if model.take_bell() {
+            io::stdout().write_all(b"\x07")?;
+        }
+        #[cfg(feature = "prometheus")]
+        prometheus::record(model);
+        if limiter.due(Instant::now()) {
+            draw(terminal, model, mirror)?;
+        }
+        if wait_for_event(Some(model.tickrate()), shutdown)? {
+            if let Event::Key(key) = read()? {
+                if key.kind != event::KeyEventKind::Release {
+                    model.update(Message::Quit);
+                }
+            }
+            continue;
+        }
+        if shutdown.load(Ordering::Relaxed) {
+            break;
+        }
+        model.update(Message::Idle);
+        screensaver.tend(model, Instant::now());
+    }
     Ok(())
 }
+
+/// Whether `event` is F5 (`true`) or F9 (`false`), which save to and load from the selected
+/// quick-save slot while editing or running.
+fn slot_key(event: &Event, model: &Model) -> Option<bool> {
+    if !matches!(model.state(), State::Editing | State::Running) {
+        return None;
+    }
+    match event {
+        Event::Key(key) if key.kind != event::KeyEventKind::Release => match key.code {
+            KeyCode::F(5) => Some(true),
+            KeyCode::F(9) => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Saves the board to the selected quick-save slot if `save` is set, or else loads it from
+/// there, leaving a notice saying how it went or reporting what went wrong.
+fn quick_slot(model: &mut Model, save: bool) {
+    let slot = model.slot();
+    let Some(path) = session::slot_path(slot) else {
+        model.report(ErrorReport::message(
+            "No data directory for quick saves (no home directory)",
+        ));
+        return;
+    };
+    if save {
+        match session::save(model, &format!("slot {slot}"), &path) {
+            Ok(()) => {
+                model.set_notice(format!("saved to slot {slot}"));
+                model.learn(Lesson::Save);
+            }
+            Err(error) => model.report(ErrorReport::new(
+                format!("Couldn't save to slot {slot} at {}", path.display()),
+                &error,
+            )),
+        }
+    } else {
+        match session::load(&path) {
+            Ok(pattern) => {
+                session::restore(model, &pattern);
+                model.set_notice(format!("loaded slot {slot}"));
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                model.set_notice(format!("slot {slot} is empty"));
+            }
+            Err(error) => model.report(ErrorReport::new(
+                format!("Couldn't load slot {slot} from {}", path.display()),
+                &error,
+            )),
+        }
+    }
+}
+
+/// Whether `event` is F6, which exports the board while editing or running.
+fn is_export(event: &Event, model: &Model) -> bool {
+    matches!(model.state(), State::Editing | State::Running)
+        && matches!(event, Event::Key(key) if key.kind != event::KeyEventKind::Release
+            && key.code == KeyCode::F(6))
+}
+
+/// Exports the board as it's shown to an ANSI art file, and an HTML page too with
+/// `--export-html`, leaving a notice saying where or reporting what went wrong.
+fn export_board(model: &mut Model) {
+    let generation = model.generation();
+    let extensions: &[&str] = match model.export_html() {
+        true => &["ans", "html"],
+        false => &["ans"],
+    };
+    let buffer = export::render(model);
+    let mut written = vec![];
+    for extension in extensions {
+        let Some(path) = export::path(generation, extension) else {
+            model.report(ErrorReport::message(
+                "No data directory for exports (no home directory)",
+            ));
+            return;
+        };
+        let text = match *extension {
+            "html" => export::html(&buffer, &model.rulestring()),
+            _ => export::ansi(&buffer),
+        };
+        if let Err(error) = export::write(&path, &text) {
+            model.report(ErrorReport::new(
+                format!("Couldn't export the board to {}", path.display()),
+                &error,
+            ));
+            return;
+        }
+        written.push(path.display().to_string());
+    }
+    model.set_notice(format!("exported to {}", written.join(" and ")));
+}
+
+/// Waits up to `timeout`, or for as long as it takes if there's none, for an event to be ready
+/// to read, returning early with `false` once `shutdown` is set.
+fn wait_for_event(timeout: Option<Duration>, shutdown: &AtomicBool) -> io::Result<bool> {
+    // signals don't interrupt polling, so wake up now and then to check for them
+    const SIGNAL_CHECK: Duration = Duration::from_millis(100);
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    while !shutdown.load(Ordering::Relaxed) {
+        let slice = deadline.map_or(SIGNAL_CHECK, |deadline| {
+            deadline
+                .saturating_duration_since(Instant::now())
+                .min(SIGNAL_CHECK)
+        });
+        if poll(slice)? {
+            return Ok(true);
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Ok(false);
+        }
+    }
+    Ok(false)
+}
+
+/// The message `event` stands for in the model's current state, if any.
+fn message_for(
+    event: &Event,
+    model: &Model,
+    accelerator: &mut Accelerator,
+    mousemap: &mut Mousemap,
+) -> Option<Message> {
+    match model.state() {
+        State::Running | State::Editing => {
+            if let Some(msg) = event_message(event) {
+                return Some(msg);
+            }
+            if let Event::Mouse(mouse) = event {
+                return mousemap.message(mouse, model);
+            }
+        }
+        _ => {}
+    }
+
+    let Event::Key(key) = event else {
+        return None;
+    };
+    if key.kind == event::KeyEventKind::Release {
+        return None;
+    }
+    // raw mode delivers Ctrl-C as a key press rather than a SIGINT
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return Some(Message::Quit);
+    }
+
+    match model.state() {
+        State::Running => {
+            // any key brings the chrome back
+            if model.is_zen() {
+                return Some(Message::ToggleZen);
+            }
+
+            match key.code {
+                KeyCode::Char(digit @ '1'..='9') => Some(Message::SelectSlot(digit as u8 - b'0')),
+                code => model.keymap().action(code, false).map(Action::message),
+            }
+        }
+
+        State::Editing => {
+            // a lesson waiting for the user takes Enter, or y and n for a prediction
+            match (model.scenario().and_then(Scenario::waiting), key.code) {
+                (Some(Waiting::Continue), KeyCode::Enter) => return Some(Message::Continue),
+                (Some(Waiting::Prediction), KeyCode::Char('y')) => {
+                    return Some(Message::Predict(true))
+                }
+                (Some(Waiting::Prediction), KeyCode::Char('n')) => {
+                    return Some(Message::Predict(false))
+                }
+                _ => {}
+            }
+            if let Some((dir, cells)) = model.keymap().movement(key) {
+                // only single steps speed up; jumps are already fast
+                let cells = if cells == 1 {
+                    let repeat = key.kind == event::KeyEventKind::Repeat;
+                    accelerator.step(dir, Instant::now(), repeat)
+                } else {
+                    cells
+                };
+                return Some(Message::Move(dir, cells));
+            }
+
+            match key.code {
+                KeyCode::Char(digit @ '1'..='9') => Some(Message::SelectSlot(digit as u8 - b'0')),
+                code => model.keymap().action(code, true).map(Action::message),
+            }
+        }
+
+        State::Prompting { .. } => match key.code {
+            KeyCode::Char(ch) => Some(Message::PromptInput(ch)),
+            KeyCode::Backspace => Some(Message::PromptBackspace),
+            KeyCode::Enter => Some(Message::PromptSubmit),
+            KeyCode::Esc => Some(Message::PromptCancel),
+            _ => None,
+        },
+
+        State::FastForwarding { .. } => match key.code {
+            KeyCode::Esc => Some(Message::StopFastForward),
+            KeyCode::Char('q') => Some(Message::Quit),
+            _ => None,
+        },
+
+        State::Picking { .. } => match key.code {
+            KeyCode::Char(ch) => Some(Message::PickerInput(ch)),
+            KeyCode::Backspace => Some(Message::PickerBackspace),
+            KeyCode::Up => Some(Message::PickerMove(Direction::Up)),
+            KeyCode::Down => Some(Message::PickerMove(Direction::Down)),
+            KeyCode::Enter => Some(Message::PickerSubmit),
+            KeyCode::Esc => Some(Message::PickerCancel),
+            _ => None,
+        },
+
+        State::Done => None,
+    }
+}
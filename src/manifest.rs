@@ -0,0 +1,339 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    app::{parse_density, Cli, Rule, MAX_BOARD_SIZE, MIN_BOARD_SIZE},
+    boundary::Boundary,
+    headless::Outcome,
+};
+
+/// Everything needed to run an experiment again, read from a `--manifest` file.
+///
+/// Manifests are written in a small subset of TOML: one `key = value` per line, with strings
+/// in double quotes, and `#` starting a comment. A `[results]` table, as written to the
+/// completed manifest, is skipped, so a completed manifest runs the same experiment again:
+///
+/// ```text
+/// title = "HighLife soup"
+/// rule = "B36/S23"
+/// preset = "random"
+/// seed = 42
+/// density = 0.35
+/// width = 128
+/// height = 64
+/// boundary = "wrap"
+/// generations = 5000
+/// output = "highlife.results.toml"
+/// final_board = "highlife.rle"
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest {
+    pub title: Option<String>,
+    pub rule: String,
+    pub preset: String,
+    /// The seed of the run, picked at random and recorded if the manifest has none.
+    pub seed: Option<u64>,
+    pub density: Option<f64>,
+    pub width: u16,
+    pub height: u16,
+    pub boundary: Boundary,
+    /// Generations to run for at most.
+    pub generations: u64,
+    /// Where the completed manifest goes, relative to the manifest: next to it, with its
+    /// extension replaced by `results.toml`, unless given.
+    pub output: Option<PathBuf>,
+    /// Where the final board is saved as RLE, if anywhere.
+    pub final_board: Option<PathBuf>,
+}
+
+impl Default for Manifest {
+    fn default() -> Manifest {
+        Manifest {
+            title: None,
+            rule: String::from("B3/S23"),
+            preset: String::from("random"),
+            seed: None,
+            density: None,
+            width: 80,
+            height: 24,
+            boundary: Boundary::default(),
+            generations: 10_000,
+            output: None,
+            final_board: None,
+        }
+    }
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Manifest, String> {
+        let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        Manifest::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Manifest, String> {
+        let mut manifest = Manifest::default();
+        let mut in_results = false;
+        for (number, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |reason: String| format!("line {}: {reason}", number + 1);
+            if let Some(table) = line.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+                in_results = match table.trim() {
+                    "results" => true,
+                    table => return Err(error(format!("unknown table [{table}]"))),
+                };
+                continue;
+            }
+            if in_results {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(String::from("expected key = value")))?;
+            manifest.set(key.trim(), value.trim()).map_err(error)?;
+        }
+        manifest
+            .rule
+            .parse::<Rule>()
+            .map_err(|reason| format!("rule: {reason}"))?;
+        Ok(manifest)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let number = |value: &str| {
+            value
+                .replace('_', "")
+                .parse::<u64>()
+                .map_err(|_| format!("{key} must be a whole number, got {value}"))
+        };
+        let size = |value: &str| {
+            let size = number(value)?;
+            if (MIN_BOARD_SIZE as u64..=MAX_BOARD_SIZE as u64).contains(&size) {
+                Ok(size as u16)
+            } else {
+                Err(format!(
+                    "{key} must be from {MIN_BOARD_SIZE} to {MAX_BOARD_SIZE} cells, got {size}"
+                ))
+            }
+        };
+        match key {
+            "title" => self.title = Some(string(value)?),
+            "rule" => self.rule = string(value)?,
+            "preset" => self.preset = string(value)?,
+            "seed" => self.seed = Some(number(value)?),
+            "density" => self.density = Some(parse_density(value)?),
+            "width" => self.width = size(value)?,
+            "height" => self.height = size(value)?,
+            "boundary" => self.boundary = Boundary::parse(&string(value)?)?,
+            "generations" => self.generations = number(value)?,
+            "output" => self.output = Some(PathBuf::from(string(value)?)),
+            "final_board" => self.final_board = Some(PathBuf::from(string(value)?)),
+            _ => return Err(format!("unknown key \"{key}\"")),
+        }
+        Ok(())
+    }
+
+    /// Runs the command line's board as the manifest describes, whatever it says itself.
+    pub fn apply(&self, cli: &mut Cli) {
+        cli.rulestring = Some(self.rule.clone());
+        cli.preset_string = Some(self.preset.clone());
+        cli.seed = self.seed;
+        cli.density = self.density;
+        cli.boundary = Some(self.boundary);
+    }
+
+    /// Where the completed manifest goes, for the manifest at `path`. Running a completed
+    /// manifest again replaces it.
+    pub fn output_path(&self, path: &Path) -> PathBuf {
+        let completed = path
+            .to_str()
+            .is_some_and(|path| path.ends_with(".results.toml"));
+        match &self.output {
+            Some(output) => relative_to(path, output),
+            None if completed => path.to_path_buf(),
+            None => path.with_extension("results.toml"),
+        }
+    }
+
+    /// Where the final board goes, if anywhere, for the manifest at `path`.
+    pub fn final_board_path(&self, path: &Path) -> Option<PathBuf> {
+        Some(relative_to(path, self.final_board.as_ref()?))
+    }
+
+    /// The manifest with everything filled in, followed by a `[results]` table saying how the
+    /// run ended.
+    pub fn completed(&self, outcome: &Outcome, population: usize) -> String {
+        let mut text = String::new();
+        let mut line = |key: &str, value: String| {
+            let _ = writeln!(text, "{key} = {value}");
+        };
+        if let Some(title) = &self.title {
+            line("title", quote(title));
+        }
+        line("rule", quote(&self.rule));
+        line("preset", quote(&self.preset));
+        if let Some(seed) = self.seed {
+            line("seed", seed.to_string());
+        }
+        if let Some(density) = self.density {
+            line("density", density.to_string());
+        }
+        line("width", self.width.to_string());
+        line("height", self.height.to_string());
+        line("boundary", quote(&self.boundary.to_string()));
+        line("generations", self.generations.to_string());
+        if let Some(output) = &self.output {
+            line("output", quote(&output.display().to_string()));
+        }
+        if let Some(final_board) = &self.final_board {
+            line("final_board", quote(&final_board.display().to_string()));
+        }
+
+        let (kind, generation, period) = match outcome {
+            Outcome::Stabilized(stabilization) => (
+                "stabilized",
+                stabilization.generation,
+                Some(stabilization.period),
+            ),
+            Outcome::Extinct(generation) => ("extinct", *generation, None),
+            Outcome::MaxGenerations(generation) => ("max-generations", *generation, None),
+        };
+        text.push_str("\n[results]\n");
+        let mut line = |key: &str, value: String| {
+            let _ = writeln!(text, "{key} = {value}");
+        };
+        line("version", quote(env!("CARGO_PKG_VERSION")));
+        line("outcome", quote(kind));
+        line("generation", generation.to_string());
+        if let Some(period) = period {
+            line("period", period.to_string());
+        }
+        line("population", population.to_string());
+        line("summary", quote(&outcome.to_string()));
+        text
+    }
+}
+
+/// `line` without a `#` comment, leaving `#`s inside strings.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, ch) in line.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// A double-quoted string's contents.
+fn string(value: &str) -> Result<String, String> {
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .filter(|_| value.len() >= 2)
+        .ok_or_else(|| format!("expected a string in double quotes, got {value}"))?;
+    let mut text = String::new();
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some('"') => text.push('"'),
+                Some('\\') => text.push('\\'),
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                other => return Err(format!("unknown escape \\{}", other.unwrap_or(' '))),
+            },
+            '"' => return Err(format!("a \" inside {value} isn't escaped")),
+            ch => text.push(ch),
+        }
+    }
+    Ok(text)
+}
+
+fn quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+    format!("\"{escaped}\"")
+}
+
+/// `path` taken as relative to the directory of `manifest`, unless it's absolute.
+fn relative_to(manifest: &Path, path: &Path) -> PathBuf {
+    match manifest.parent() {
+        Some(dir) => dir.join(path),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::stability::Stabilization;
+
+    #[test]
+    fn parses_manifests() {
+        let manifest = Manifest::parse(
+            "title = \"Life # soup\" # a comment\n\
+             rule = \"B3/S23\"\n\
+             seed = 1_000\n\
+             width = 32\n\
+             boundary = \"wrap\"\n\
+             \n\
+             [results]\n\
+             outcome = \"extinct\"\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.title.as_deref(), Some("Life # soup"));
+        assert_eq!(manifest.seed, Some(1000));
+        assert_eq!(manifest.width, 32);
+        assert_eq!(manifest.height, Manifest::default().height);
+        assert_eq!(manifest.boundary, Boundary::Wrap);
+        assert_eq!(
+            manifest.output_path(Path::new("runs/exp.toml")),
+            Path::new("runs/exp.results.toml")
+        );
+        assert_eq!(
+            manifest.output_path(Path::new("runs/exp.results.toml")),
+            Path::new("runs/exp.results.toml")
+        );
+
+        assert!(Manifest::parse("rule = B3/S23").is_err());
+        assert!(Manifest::parse("rule = \"B9\"").is_err());
+        assert!(Manifest::parse("width = 0").is_err());
+        assert!(Manifest::parse("width = 1\nheight = 10\ngenerations = 5").is_err());
+        assert_eq!(Manifest::parse("height = 2").unwrap().height, 2);
+        assert!(Manifest::parse("colour = \"red\"").is_err());
+        assert!(Manifest::parse("[outputs]").is_err());
+    }
+
+    #[test]
+    fn completes_manifests() {
+        let manifest = Manifest {
+            title: Some(String::from("Say \"hi\"")),
+            seed: Some(7),
+            ..Manifest::default()
+        };
+        let outcome = Outcome::Stabilized(Stabilization {
+            generation: 120,
+            period: 2,
+        });
+        let completed = manifest.completed(&outcome, 31);
+        assert!(completed.contains("title = \"Say \\\"hi\\\"\"\n"));
+        assert!(completed.contains("\n[results]\n"));
+        assert!(completed.contains("outcome = \"stabilized\"\ngeneration = 120\nperiod = 2\n"));
+        assert_eq!(Manifest::parse(&completed), Ok(manifest));
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread,
+};
+
+use ratatui::buffer::Buffer;
+use tracing::info;
+
+use crate::export;
+
+/// Moves the cursor home before each frame, which is cleared below once drawn so a smaller
+/// frame doesn't leave the last one showing.
+const HOME: &str = "\x1b[H";
+const CLEAR_BELOW: &str = "\x1b[J";
+
+/// A second place every frame drawn goes, chosen with `--mirror`: a file collecting the frames
+/// as ANSI text, a named pipe or another terminal's TTY, such as /dev/pts/3.
+///
+/// Frames are written by a thread of their own, so a reader that stops reading without
+/// closing the pipe doesn't freeze the app; frames drawn while the last one is still waiting
+/// to be written are dropped.
+#[derive(Debug)]
+pub struct Mirror {
+    path: PathBuf,
+    frames: SyncSender<String>,
+    /// The error that stopped the writing thread, once it has stopped.
+    failed: Receiver<io::Error>,
+    /// The last frame handed to the writing thread, so unchanged frames aren't written again.
+    last: String,
+}
+
+impl Mirror {
+    /// Opens `path` for writing, emptying it if it's a file. Opening a named pipe waits until
+    /// something reads from it.
+    pub fn open(path: &Path) -> io::Result<Mirror> {
+        info!(path = %path.display(), "mirroring frames");
+        Ok(Mirror::start(path, File::create(path)?))
+    }
+
+    /// Writes the frames to `sink` from a thread of its own.
+    fn start(path: &Path, mut sink: impl Write + Send + 'static) -> Mirror {
+        let (frames, queued) = mpsc::sync_channel::<String>(1);
+        let (fail, failed) = mpsc::channel();
+        thread::spawn(move || {
+            for frame in queued {
+                let written = write!(sink, "{HOME}{frame}{CLEAR_BELOW}").and_then(|_| sink.flush());
+                if let Err(error) = written {
+                    let _ = fail.send(error);
+                    return;
+                }
+            }
+        });
+        Mirror {
+            path: path.to_path_buf(),
+            frames,
+            failed,
+            last: String::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Hands the frame in `buffer` to the writing thread, unless it's the same as the last one
+    /// or the one before hasn't been written yet. Fails once writing has failed.
+    pub fn write(&mut self, buffer: &Buffer) -> io::Result<()> {
+        if let Ok(error) = self.failed.try_recv() {
+            return Err(error);
+        }
+        let frame = export::ansi(buffer);
+        if frame == self.last {
+            return Ok(());
+        }
+        match self.frames.try_send(frame.clone()) {
+            Ok(()) => self.last = frame,
+            // left for a later frame, which will be newer anyway
+            Err(TrySendError::Full(_)) => {}
+            Err(TrySendError::Disconnected(_)) => {
+                return Err(self
+                    .failed
+                    .try_recv()
+                    .unwrap_or_else(|_| io::Error::other("the mirror stopped writing")))
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use ratatui::{layout::Rect, style::Style};
+
+    use super::*;
+
+    /// A sink collecting what's written, which blocks while `stalled` is held.
+    #[derive(Clone, Default)]
+    struct Sink {
+        written: Arc<Mutex<Vec<u8>>>,
+        stalled: Arc<Mutex<()>>,
+    }
+
+    impl Write for Sink {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            let _stalled = self.stalled.lock().unwrap();
+            self.written.lock().unwrap().extend(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn frame(text: &str) -> Buffer {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 2, 1));
+        buffer.set_string(0, 0, text, Style::default());
+        buffer
+    }
+
+    /// Waits for the thread to have written `homes` frames.
+    fn wait_for(sink: &Sink, homes: usize) -> String {
+        let start = Instant::now();
+        loop {
+            let text = String::from_utf8(sink.written.lock().unwrap().clone()).unwrap();
+            if text.matches(HOME).count() >= homes || start.elapsed() > Duration::from_secs(5) {
+                return text;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn writes_changed_frames() {
+        let sink = Sink::default();
+        let mut mirror = Mirror::start(Path::new("sink"), sink.clone());
+        mirror.write(&frame("ab")).unwrap();
+        wait_for(&sink, 1);
+        mirror.write(&frame("ab")).unwrap();
+        mirror.write(&frame("cd")).unwrap();
+
+        let text = wait_for(&sink, 2);
+        assert_eq!(text.matches(HOME).count(), 2);
+        assert!(text.contains("ab") && text.contains("cd"));
+    }
+
+    #[test]
+    fn drops_frames_while_stalled() {
+        let sink = Sink::default();
+        let stalled = sink.stalled.lock().unwrap();
+        let mut mirror = Mirror::start(Path::new("sink"), sink.clone());
+        // none of these wait for the stalled reader
+        for text in ["ab", "cd", "ef", "gh"] {
+            mirror.write(&frame(text)).unwrap();
+        }
+        drop(stalled);
+        // the thread ends once it has written what was queued, dropping its copy of the sink
+        drop(mirror);
+        let start = Instant::now();
+        while Arc::strong_count(&sink.written) > 1 && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(1));
+        }
+        let text = String::from_utf8(sink.written.lock().unwrap().clone()).unwrap();
+        assert!(text.starts_with(&format!("{HOME}\x1b[0mab")));
+        assert!(!text.contains("gh"));
+    }
+}
@@ -0,0 +1,218 @@
+use std::{fmt, fs, path::Path};
+
+/// The largest outer radius accepted for a ring, which keeps a step at a few thousand
+/// lookups per cell.
+pub const MAX_RADIUS: u16 = 15;
+
+/// A multiple-neighborhood cellular automaton: several rings around each cell, each giving
+/// the fraction of its cells alive, and rules that set the cell's next state when a ring's
+/// fraction falls inside an interval. Rules are applied in order starting from the cell's
+/// current state, so later rules win where they overlap.
+///
+/// MNCAs are written as text: a `ring INNER OUTER` line for each ring, numbered from 1, of
+/// the cells whose distance rounds to between `INNER` and `OUTER` inclusive; a
+/// `rule RING LOW HIGH NEXT` line for each rule; and an optional `name` line. `#` starts a
+/// comment:
+///
+/// ```text
+/// name Membranes
+/// ring 1 3
+/// ring 4 7
+/// rule 1 0.00 0.15 0
+/// rule 1 0.40 0.55 1
+/// rule 2 0.10 0.25 1
+/// rule 2 0.45 1.00 0
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mnca {
+    name: Option<String>,
+    /// Each ring's cells as row and column offsets.
+    rings: Vec<Vec<(isize, isize)>>,
+    rules: Vec<MncaRule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MncaRule {
+    /// Index into [`Mnca::rings`].
+    ring: usize,
+    low: f32,
+    high: f32,
+    next: bool,
+}
+
+impl Mnca {
+    pub fn load(path: &Path) -> Result<Mnca, String> {
+        let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        Mnca::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Mnca, String> {
+        let (mut name, mut radii, mut rules) = (None, vec![], vec![]);
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let error = |what: &str| format!("line {}: {what}, got \"{line}\"", number + 1);
+            match words[..] {
+                [] => {}
+                ["name", mnca_name] => name = Some(mnca_name.to_string()),
+                ["ring", inner, outer] => {
+                    let (Ok(inner), Ok(outer)) = (inner.parse::<u16>(), outer.parse::<u16>())
+                    else {
+                        return Err(error("expected ring INNER OUTER"));
+                    };
+                    if inner > outer || outer == 0 || outer > MAX_RADIUS {
+                        return Err(error(&format!(
+                            "radii must go up from INNER to an OUTER of 1 to {MAX_RADIUS}"
+                        )));
+                    }
+                    radii.push((inner, outer));
+                }
+                ["rule", ring, low, high, next] => {
+                    let ring = match ring.parse::<usize>() {
+                        Ok(ring) if (1..=radii.len()).contains(&ring) => ring - 1,
+                        _ => return Err(error("rules must name a ring defined above them")),
+                    };
+                    let (Ok(low), Ok(high)) = (low.parse::<f32>(), high.parse::<f32>()) else {
+                        return Err(error("expected fractions LOW and HIGH"));
+                    };
+                    if !(0.0..=1.0).contains(&low) || !(low..=1.0).contains(&high) {
+                        return Err(error("expected 0 <= LOW <= HIGH <= 1"));
+                    }
+                    let next = match next {
+                        "0" => false,
+                        "1" => true,
+                        _ => return Err(error("expected NEXT to be 0 or 1")),
+                    };
+                    rules.push(MncaRule {
+                        ring,
+                        low,
+                        high,
+                        next,
+                    });
+                }
+                _ => return Err(error("expected a name, ring or rule line")),
+            }
+        }
+
+        if rules.is_empty() {
+            return Err(String::from("expected at least one ring and one rule"));
+        }
+        let rings = radii
+            .into_iter()
+            .map(|(inner, outer)| ring(inner, outer))
+            .collect();
+        let mnca = Mnca { name, rings, rules };
+        if mnca.next_state(false, |_| 0.0) {
+            return Err(String::from(
+                "a birth with no live neighbors would fill the empty background",
+            ));
+        }
+        Ok(mnca)
+    }
+
+    /// How many cells the widest ring reaches from the center, across or down.
+    pub fn reach(&self) -> usize {
+        self.rings
+            .iter()
+            .flatten()
+            .map(|(dy, dx)| dy.unsigned_abs().max(dx.unsigned_abs()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether the cell at `y`, `x` of `board` is alive in the next generation. Cells off the
+    /// board count as dead.
+    pub fn verdict(&self, board: &[Vec<bool>], y: usize, x: usize) -> bool {
+        self.next_state(board[y][x], |ring| {
+            let offsets = &self.rings[ring];
+            let alive = offsets
+                .iter()
+                .filter(|&&(dy, dx)| {
+                    y.checked_add_signed(dy)
+                        .zip(x.checked_add_signed(dx))
+                        .and_then(|(ny, nx)| board.get(ny)?.get(nx))
+                        .copied()
+                        .unwrap_or(false)
+                })
+                .count();
+            alive as f32 / offsets.len() as f32
+        })
+    }
+
+    /// Runs the rules over a cell that's `alive`, with `fraction` giving how full each ring is.
+    fn next_state(&self, alive: bool, mut fraction: impl FnMut(usize) -> f32) -> bool {
+        let mut fractions = vec![None; self.rings.len()];
+        self.rules.iter().fold(alive, |state, rule| {
+            let fraction = *fractions[rule.ring].get_or_insert_with(|| fraction(rule.ring));
+            if (rule.low..=rule.high).contains(&fraction) {
+                rule.next
+            } else {
+                state
+            }
+        })
+    }
+}
+
+/// The offsets of the cells whose distance rounds to between `inner` and `outer` inclusive,
+/// leaving out the cell itself.
+fn ring(inner: u16, outer: u16) -> Vec<(isize, isize)> {
+    let reach = outer as isize;
+    let (inner, outer) = (f32::from(inner), f32::from(outer));
+    let mut offsets = vec![];
+    for dy in -reach..=reach {
+        for dx in -reach..=reach {
+            let distance = ((dx * dx + dy * dy) as f32).sqrt();
+            if (dy, dx) != (0, 0) && (inner..=outer).contains(&distance.round()) {
+                offsets.push((dy, dx));
+            }
+        }
+    }
+    offsets
+}
+
+impl fmt::Display for Mnca {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MNCA")?;
+        if let Some(name) = &self.name {
+            write!(f, " {name}")?;
+        }
+        write!(
+            f,
+            " ({} rings, {} rules)",
+            self.rings.len(),
+            self.rules.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_step() {
+        let mnca = Mnca::parse(
+            "name Test\nring 1 1\nring 2 2\n# crowded inner rings die\nrule 1 0.3 0.5 1\nrule 1 0.6 1 0\n",
+        )
+        .unwrap();
+        assert_eq!(mnca.to_string(), "MNCA Test (2 rings, 2 rules)");
+        assert_eq!(mnca.rings[0].len(), 8);
+
+        let mut board = vec![vec![false; 5]; 5];
+        for (y, x) in [(1, 1), (1, 2), (1, 3)] {
+            board[y][x] = true;
+        }
+        // 3 of the 8 cells around (2, 2) are alive, inside the first rule's interval
+        assert!(mnca.verdict(&board, 2, 2));
+        // (0, 2) sees the same 3, though 3 of its ring are off the board
+        assert!(mnca.verdict(&board, 0, 2));
+        // 1 of 8 matches no rule, so (1, 1) stays alive
+        assert!(mnca.verdict(&board, 1, 1));
+
+        assert!(Mnca::parse("ring 3 1\nrule 1 0 1 0\n").is_err());
+        assert!(Mnca::parse("ring 1 3\nrule 2 0 1 0\n").is_err());
+        assert!(Mnca::parse("ring 1 3\nrule 1 0.5 0.2 0\n").is_err());
+        assert!(Mnca::parse("ring 1 3\nrule 1 0 0.1 1\n").is_err());
+        assert!(Mnca::parse("ring 1 3\n").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use std::{fmt, sync::Arc};
+
+/// Neighbors a custom neighborhood may have at most, as rulestrings only count up to 8.
+const MAX_NEIGHBORS: usize = 8;
+
+/// How far a custom neighborhood may reach from each cell, in cells.
+const MAX_REACH: i16 = 8;
+
+/// The cells around each cell whose live count the rule looks up, chosen with
+/// `--neighborhood`. Stepping, the inspector and second-order rules all count through it.
+pub trait Neighborhood: fmt::Debug + fmt::Display + Send + Sync {
+    /// Where the neighbors are, in columns right and rows down of the cell. The cell itself
+    /// isn't among them.
+    fn offsets(&self) -> &[(i16, i16)];
+
+    /// How many neighbors each cell has.
+    fn size(&self) -> usize {
+        self.offsets().len()
+    }
+
+    /// How far the furthest neighbor is, in cells along either axis.
+    fn reach(&self) -> usize {
+        self.offsets()
+            .iter()
+            .map(|(dx, dy)| dx.unsigned_abs().max(dy.unsigned_abs()))
+            .max()
+            .unwrap_or(0) as usize
+    }
+}
+
+/// The eight cells around a cell, as in Life.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Moore;
+
+impl Neighborhood for Moore {
+    fn offsets(&self) -> &[(i16, i16)] {
+        &[
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ]
+    }
+}
+
+/// The four cells sharing an edge with a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VonNeumann;
+
+impl Neighborhood for VonNeumann {
+    fn offsets(&self) -> &[(i16, i16)] {
+        &[(0, -1), (-1, 0), (1, 0), (0, 1)]
+    }
+}
+
+/// The six cells around a cell of a hexagonal grid, with the grid sheared onto the square one
+/// as Golly does: the Moore neighborhood without the top-right and bottom-left corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hex;
+
+impl Neighborhood for Hex {
+    fn offsets(&self) -> &[(i16, i16)] {
+        &[(-1, -1), (0, -1), (-1, 0), (1, 0), (0, 1), (1, 1)]
+    }
+}
+
+/// Neighbors at offsets of the user's choosing, given as `DX,DY;DX,DY;...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Custom {
+    offsets: Vec<(i16, i16)>,
+}
+
+impl Custom {
+    pub fn new(offsets: Vec<(i16, i16)>) -> Result<Custom, String> {
+        if offsets.is_empty() || offsets.len() > MAX_NEIGHBORS {
+            return Err(format!(
+                "a neighborhood needs between 1 and {MAX_NEIGHBORS} neighbors, got {}",
+                offsets.len()
+            ));
+        }
+        for (index, &(dx, dy)) in offsets.iter().enumerate() {
+            if (dx, dy) == (0, 0) {
+                return Err(String::from("a cell can't be its own neighbor"));
+            }
+            if dx.abs() > MAX_REACH || dy.abs() > MAX_REACH {
+                return Err(format!(
+                    "{dx},{dy} is more than {MAX_REACH} cells from the cell"
+                ));
+            }
+            if offsets[..index].contains(&(dx, dy)) {
+                return Err(format!("{dx},{dy} is listed twice"));
+            }
+        }
+        Ok(Custom { offsets })
+    }
+}
+
+impl Neighborhood for Custom {
+    fn offsets(&self) -> &[(i16, i16)] {
+        &self.offsets
+    }
+}
+
+/// Parses `moore`, `von-neumann`, `hex`, or a list of offsets such as `1,0;0,1;-1,0;0,-1`.
+pub fn parse(input: &str) -> Result<Arc<dyn Neighborhood>, String> {
+    match input.trim().to_lowercase().as_str() {
+        "moore" => return Ok(Arc::new(Moore)),
+        "von-neumann" | "vonneumann" => return Ok(Arc::new(VonNeumann)),
+        "hex" | "hexagonal" => return Ok(Arc::new(Hex)),
+        _ => {}
+    }
+    let offsets = input
+        .split(';')
+        .map(|offset| {
+            let (dx, dy) = offset.split_once(',').ok_or_else(|| {
+                format!(
+                    "unknown neighborhood \"{input}\", expected moore, von-neumann, hex or \
+                     offsets such as 1,0;0,1"
+                )
+            })?;
+            let parse = |n: &str| {
+                n.trim()
+                    .parse::<i16>()
+                    .map_err(|_| format!("\"{}\" isn't a whole number of cells", n.trim()))
+            };
+            Ok((parse(dx)?, parse(dy)?))
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Arc::new(Custom::new(offsets)?))
+}
+
+impl fmt::Display for Moore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "moore")
+    }
+}
+
+impl fmt::Display for VonNeumann {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "von-neumann")
+    }
+}
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hex")
+    }
+}
+
+impl fmt::Display for Custom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offsets: Vec<String> = self
+            .offsets
+            .iter()
+            .map(|(dx, dy)| format!("{dx},{dy}"))
+            .collect();
+        write!(f, "{}", offsets.join(";"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_neighborhoods() {
+        assert_eq!(parse("Moore").unwrap().size(), 8);
+        assert_eq!(parse("von-neumann").unwrap().size(), 4);
+        assert_eq!(parse("hex").unwrap().to_string(), "hex");
+
+        let custom = parse("2,0; -2,0;0,2").unwrap();
+        assert_eq!(custom.offsets(), [(2, 0), (-2, 0), (0, 2)]);
+        assert_eq!(custom.reach(), 2);
+        assert_eq!(custom.to_string(), "2,0;-2,0;0,2");
+        assert_eq!(Moore.reach(), 1);
+
+        assert!(parse("triangle").is_err());
+        assert!(parse("0,0").is_err());
+        assert!(parse("1,0;1,0").is_err());
+        assert!(parse("9,0").is_err());
+        assert!(parse("1,x").is_err());
+    }
+}
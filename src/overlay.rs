@@ -0,0 +1,109 @@
+use std::time::SystemTime;
+
+use crate::{
+    app::{Area, Model},
+    journal,
+};
+
+/// The corner of the board the recording overlay sits in, chosen with `--overlay`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    pub fn parse(input: &str) -> Result<Corner, String> {
+        match input.trim().to_lowercase().as_str() {
+            "top-left" => Ok(Corner::TopLeft),
+            "top-right" => Ok(Corner::TopRight),
+            "bottom-left" => Ok(Corner::BottomLeft),
+            "bottom-right" => Ok(Corner::BottomRight),
+            _ => Err(format!(
+                "unknown corner \"{input}\", expected top-left, top-right, bottom-left or \
+                 bottom-right"
+            )),
+        }
+    }
+
+    /// A line `width` cells wide in this corner of `area`, cut down to fit.
+    pub fn area(self, area: Area, width: u16) -> Area {
+        let width = width.min(area.width);
+        let height = area.height.min(1);
+        let x = match self {
+            Corner::TopLeft | Corner::BottomLeft => area.x,
+            Corner::TopRight | Corner::BottomRight => area.right() - width,
+        };
+        let y = match self {
+            Corner::TopLeft | Corner::TopRight => area.y,
+            Corner::BottomLeft | Corner::BottomRight => area.bottom() - height,
+        };
+        Area::new(x, y, width, height)
+    }
+}
+
+/// The overlay's line at `now`: the rule, the generation, the seed if there is one and the
+/// time in UTC, so a frame of a recording says what it shows and when it was taken.
+pub fn line(model: &Model, now: SystemTime) -> String {
+    let mut parts = vec![model.rulestring(), format!("gen {}", model.generation())];
+    if let Some(seed) = model.rng().seed() {
+        parts.push(format!("seed {seed}"));
+    }
+    parts.push(journal::timestamp(now));
+    format!(" {} ", parts.join(" · "))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[cfg(feature = "tui")]
+    use clap::Parser;
+
+    use super::*;
+    #[cfg(feature = "tui")]
+    use crate::app::{Cli, Config};
+    use crate::{app::Message, rng::RngSource};
+
+    #[test]
+    fn overlay_line() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_rng(RngSource::seeded(42));
+        assert_eq!(
+            line(&model, UNIX_EPOCH + Duration::from_secs(3661)),
+            " B3/S23 · gen 0 · seed 42 · 1970-01-01T01:01:01Z "
+        );
+
+        assert_eq!(Corner::parse("Top-Right"), Ok(Corner::TopRight));
+        assert!(Corner::parse("middle").is_err());
+        let area = Area::new(2, 3, 40, 10);
+        assert_eq!(Corner::TopLeft.area(area, 10), Area::new(2, 3, 10, 1));
+        assert_eq!(Corner::BottomRight.area(area, 10), Area::new(32, 12, 10, 1));
+        assert_eq!(Corner::BottomLeft.area(area, 50), Area::new(2, 12, 40, 1));
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn overlay_flag() {
+        let overlay = |args: &[&str]| {
+            let cli = Cli::parse_from([&["tui-ca"], args].concat());
+            Config::from_cli(&cli).overlay
+        };
+        assert_eq!(overlay(&[]), None);
+        assert_eq!(overlay(&["--overlay"]), Some(Corner::BottomRight));
+        assert_eq!(overlay(&["--overlay", "top-left"]), Some(Corner::TopLeft));
+    }
+
+    #[test]
+    fn toggles_overlay() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_overlay(Some(Corner::TopLeft));
+        model.update(Message::ToggleOverlay);
+        assert_eq!(model.overlay(), None);
+        model.update(Message::ToggleOverlay);
+        assert_eq!(model.overlay(), Some(Corner::TopLeft));
+    }
+}
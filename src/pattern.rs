@@ -0,0 +1,576 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use tracing::{info, warn};
+
+use crate::{app::Preset, dirs::data_dir};
+
+/// A finite arrangement of cells, as read from an RLE file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub cells: Vec<Vec<bool>>,
+    /// The rule named in the RLE header, if any.
+    pub rule: Option<String>,
+    pub metadata: Metadata,
+}
+
+/// Descriptive information carried in an RLE file's `#N`, `#O` and `#C` comment lines, and
+/// Golly's extended `#CXRLE` line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    /// The first plausible year mentioned in the author or comment lines.
+    pub year: Option<u16>,
+    pub comments: Vec<String>,
+    /// Where the top-left cell sits in the universe, from `#CXRLE Pos=X,Y`.
+    pub position: Option<(i64, i64)>,
+    /// The generation the pattern was saved at, from `#CXRLE Gen=N`.
+    pub generation: Option<u64>,
+}
+
+impl Pattern {
+    /// Parses a pattern in the run length encoded format used by Golly and LifeWiki.
+    ///
+    /// `#N`, `#O` and `#C` comment lines are collected into the pattern's [`Metadata`], as are
+    /// the position and generation of Golly's `#CXRLE` line; other comment lines are skipped. Any cell state other than `b` or `.` counts as alive, so
+    /// multi-state patterns load as their live envelope.
+    pub fn from_rle(rle: &str) -> Result<Pattern, String> {
+        let mut width = None;
+        let mut height = None;
+        let mut rule = None;
+        let mut metadata = Metadata::default();
+        let mut cells = vec![vec![]];
+        let mut run = String::new();
+        let mut header_seen = false;
+
+        'lines: for line in rle.lines() {
+            let line = line.trim();
+            if let Some(comment) = line.strip_prefix('#') {
+                metadata.add_comment_line(comment);
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+
+            if !header_seen && line.starts_with('x') {
+                header_seen = true;
+                for field in line.split(',') {
+                    let Some((key, value)) = field.split_once('=') else {
+                        return Err(format!("malformed header field \"{}\"", field.trim()));
+                    };
+                    let value = value.trim();
+                    match key.trim() {
+                        "x" => width = Some(parse_dimension(value)?),
+                        "y" => height = Some(parse_dimension(value)?),
+                        "rule" => rule = Some(String::from(value)),
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+
+            for ch in line.chars() {
+                if ch.is_ascii_digit() {
+                    run.push(ch);
+                    continue;
+                }
+
+                let count = if run.is_empty() {
+                    1
+                } else {
+                    run.parse::<usize>()
+                        .map_err(|_| format!("run length {run} is too large"))?
+                };
+                run.clear();
+
+                match ch {
+                    'b' | '.' => cells.last_mut().unwrap().extend((0..count).map(|_| false)),
+                    '$' => {
+                        for _ in 0..count {
+                            cells.push(vec![]);
+                        }
+                    }
+                    '!' => break 'lines,
+                    ch if ch.is_ascii_alphabetic() => {
+                        cells.last_mut().unwrap().extend((0..count).map(|_| true))
+                    }
+                    ch if ch.is_whitespace() => {}
+                    ch => return Err(format!("unexpected character '{ch}'")),
+                }
+            }
+        }
+
+        let width = width.unwrap_or_else(|| cells.iter().map(Vec::len).max().unwrap_or(0));
+        let height = height.unwrap_or(cells.len());
+        if cells.len() > height || cells.iter().any(|line| line.len() > width) {
+            return Err(format!(
+                "pattern is larger than its {width}x{height} header"
+            ));
+        }
+
+        cells.resize(height, vec![]);
+        for line in &mut cells {
+            line.resize(width, false);
+        }
+
+        Ok(Pattern {
+            cells,
+            rule,
+            metadata,
+        })
+    }
+
+    /// Writes the pattern in the run length encoded format, metadata included, so that
+    /// [`Pattern::from_rle`] reads back the same pattern.
+    pub fn to_rle(&self) -> String {
+        let mut rle = String::new();
+        // Golly expects the extended line first
+        if self.metadata.position.is_some() || self.metadata.generation.is_some() {
+            rle.push_str("#CXRLE");
+            if let Some((x, y)) = self.metadata.position {
+                rle.push_str(&format!(" Pos={x},{y}"));
+            }
+            if let Some(generation) = self.metadata.generation {
+                rle.push_str(&format!(" Gen={generation}"));
+            }
+            rle.push('\n');
+        }
+        if let Some(name) = &self.metadata.name {
+            rle.push_str(&format!("#N {name}\n"));
+        }
+        if let Some(author) = &self.metadata.author {
+            rle.push_str(&format!("#O {author}\n"));
+        }
+        for comment in &self.metadata.comments {
+            rle.push_str(&format!("#C {comment}\n"));
+        }
+        let (width, height) = self.size();
+        rle.push_str(&format!("x = {width}, y = {height}"));
+        if let Some(rule) = &self.rule {
+            rle.push_str(&format!(", rule = {rule}"));
+        }
+        rle.push('\n');
+
+        // (count, tag) runs, leaving out dead cells at the ends of lines and blank lines at the
+        // end of the pattern
+        let mut runs: Vec<(usize, char)> = vec![];
+        let mut push = |count: usize, tag: char| match runs.last_mut() {
+            Some((run, last)) if *last == tag => *run += count,
+            _ => runs.push((count, tag)),
+        };
+        let last_line = self.cells.iter().rposition(|line| line.contains(&true));
+        for (y, line) in self
+            .cells
+            .iter()
+            .enumerate()
+            .take(last_line.map_or(0, |y| y + 1))
+        {
+            if y > 0 {
+                push(1, '$');
+            }
+            let end = line.iter().rposition(|alive| *alive).map_or(0, |x| x + 1);
+            for alive in &line[..end] {
+                push(1, if *alive { 'o' } else { 'b' });
+            }
+        }
+        push(1, '!');
+
+        // lines are kept under 70 characters, as Golly does
+        let mut line = String::new();
+        for (count, tag) in runs {
+            let run = match count {
+                1 => tag.to_string(),
+                count => format!("{count}{tag}"),
+            };
+            if line.len() + run.len() > 70 {
+                rle.push_str(&line);
+                rle.push('\n');
+                line.clear();
+            }
+            line.push_str(&run);
+        }
+        rle.push_str(&line);
+        rle.push('\n');
+        rle
+    }
+
+    /// Width and height in cells.
+    pub fn size(&self) -> (usize, usize) {
+        (self.cells.first().map_or(0, Vec::len), self.cells.len())
+    }
+
+    /// Returns a copy flipped and then rotated as described by `transform`.
+    pub fn transformed(&self, transform: Transform) -> Pattern {
+        let mut cells = self.cells.clone();
+        if transform.flip_h {
+            for line in &mut cells {
+                line.reverse();
+            }
+        }
+        if transform.flip_v {
+            cells.reverse();
+        }
+
+        for _ in 0..transform.rotation.quarter_turns() {
+            // rotate a quarter turn clockwise: the bottom row becomes the first column
+            let (width, height) = (cells.first().map_or(0, Vec::len), cells.len());
+            cells = (0..width)
+                .map(|x| (0..height).rev().map(|y| cells[y][x]).collect())
+                .collect();
+        }
+
+        Pattern {
+            cells,
+            rule: self.rule.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+impl Metadata {
+    /// Records one comment line, given without its leading `#`.
+    fn add_comment_line(&mut self, line: &str) {
+        if let Some(fields) = line.strip_prefix("CXRLE") {
+            self.add_extended_line(fields);
+            return;
+        }
+        let mut chars = line.chars();
+        let kind = chars.next();
+        let text = chars.as_str().trim();
+        if text.is_empty() {
+            return;
+        }
+
+        match kind {
+            Some('N') => self.name = Some(String::from(text)),
+            Some('O') => self.author = Some(String::from(text)),
+            Some('C' | 'c') => self.comments.push(String::from(text)),
+            _ => return,
+        }
+
+        if self.year.is_none() && kind != Some('N') {
+            self.year = find_year(text);
+        }
+    }
+
+    /// Records the `Pos=X,Y` and `Gen=N` fields of a `#CXRLE` line, skipping any others.
+    fn add_extended_line(&mut self, fields: &str) {
+        for field in fields.split_whitespace() {
+            match field.split_once('=') {
+                Some(("Pos", value)) => {
+                    self.position = value
+                        .split_once(',')
+                        .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)));
+                }
+                Some(("Gen", value)) => self.generation = value.parse().ok(),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Finds a standalone four digit number that looks like a year in the era of cellular automata.
+fn find_year(text: &str) -> Option<u16> {
+    text.split(|ch: char| !ch.is_ascii_digit())
+        .filter(|word| word.len() == 4)
+        .filter_map(|word| word.parse().ok())
+        .find(|year| (1900..2100).contains(year))
+}
+
+/// Clockwise rotation applied to a pattern.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rotation {
+    #[default]
+    None,
+    Quarter,
+    Half,
+    ThreeQuarters,
+}
+
+/// How to orient a pattern before placing it: flips are applied first, then the rotation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub flip_h: bool,
+    pub flip_v: bool,
+}
+
+impl Rotation {
+    /// Parses a rotation in degrees; only multiples of 90 are accepted.
+    pub fn parse(input: &str) -> Result<Rotation, String> {
+        match input
+            .trim()
+            .parse::<i32>()
+            .map(|degrees| degrees.rem_euclid(360))
+        {
+            Ok(0) => Ok(Rotation::None),
+            Ok(90) => Ok(Rotation::Quarter),
+            Ok(180) => Ok(Rotation::Half),
+            Ok(270) => Ok(Rotation::ThreeQuarters),
+            _ => Err(format!(
+                "rotation must be 0, 90, 180 or 270 degrees, got \"{input}\""
+            )),
+        }
+    }
+
+    fn quarter_turns(&self) -> usize {
+        match self {
+            Rotation::None => 0,
+            Rotation::Quarter => 1,
+            Rotation::Half => 2,
+            Rotation::ThreeQuarters => 3,
+        }
+    }
+}
+
+/// Patterns loaded from the user's presets directory, looked up by file name alongside the
+/// built-in presets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Library {
+    entries: Vec<(String, Pattern)>,
+}
+
+impl Library {
+    /// Loads every `.rle` file in `dir`. Files that can't be read or parsed are skipped and
+    /// returned alongside the library with the reason they were rejected.
+    pub fn load_dir(dir: &Path) -> (Library, Vec<(PathBuf, String)>) {
+        info!(dir = %dir.display(), "loading pattern library");
+        let mut library = Library::default();
+        let mut errors = vec![];
+
+        let read_dir = match fs::read_dir(dir) {
+            Ok(read_dir) => read_dir,
+            Err(error) => {
+                errors.push((dir.to_path_buf(), error.to_string()));
+                return (library, errors);
+            }
+        };
+
+        let mut paths: Vec<PathBuf> = read_dir
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rle"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let pattern = fs::read_to_string(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|rle| Pattern::from_rle(&rle));
+            match pattern {
+                Ok(pattern) => library.insert(String::from(name), pattern),
+                Err(reason) => {
+                    warn!(path = %path.display(), reason, "skipping pattern");
+                    errors.push((path, reason))
+                }
+            }
+        }
+
+        (library, errors)
+    }
+
+    pub fn insert(&mut self, name: String, pattern: Pattern) {
+        self.entries.push((name, pattern));
+    }
+
+    /// Names of the user's patterns, in the order they were loaded.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _)| &name[..])
+    }
+
+    /// Looks up a preset by name: built-ins first, then the user's patterns, matching names
+    /// the same lenient way as [`Preset::parse`].
+    pub fn resolve(&self, name: &str) -> Option<Preset> {
+        Preset::parse(name).or_else(|| {
+            let wanted = normalize_name(name);
+            self.entries
+                .iter()
+                .find(|(entry, _)| normalize_name(entry) == wanted)
+                .map(|(name, pattern)| Preset::User {
+                    name: name.clone(),
+                    pattern: pattern.clone(),
+                })
+        })
+    }
+}
+
+/// The presets directory used when none is given: `patterns` in the [`data_dir`].
+pub fn default_presets_dir() -> Option<PathBuf> {
+    Some(data_dir()?.join("patterns"))
+}
+
+/// Lowercases a pattern name and strips spaces, dashes and underscores, so `R-pentomino`,
+/// `r_pentomino` and `RPentomino` all match.
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|ch| !matches!(ch, ' ' | '-' | '_'))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn parse_dimension(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("\"{value}\" is not a valid dimension"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn glider() {
+        let pattern =
+            Pattern::from_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+        assert_eq!(
+            pattern.cells,
+            vec![
+                vec![false, true, false],
+                vec![false, false, true],
+                vec![true, true, true],
+            ]
+        );
+        assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(pattern.metadata.name.as_deref(), Some("Glider"));
+    }
+
+    #[test]
+    fn metadata() {
+        let pattern = Pattern::from_rle(
+            "#N Acorn\n#O Charles Corderman\n#C A methuselah found in 1971.\n\
+             #c Takes 5206 generations to stabilize.\n#r 23/3\nx = 7, y = 3\nbo$3bo$2o2b3o!",
+        )
+        .unwrap();
+        assert_eq!(
+            pattern.metadata,
+            Metadata {
+                name: Some(String::from("Acorn")),
+                author: Some(String::from("Charles Corderman")),
+                year: Some(1971),
+                comments: vec![
+                    String::from("A methuselah found in 1971."),
+                    String::from("Takes 5206 generations to stabilize."),
+                ],
+                ..Metadata::default()
+            }
+        );
+        assert_eq!(find_year("p30, 36x9"), None);
+    }
+
+    #[test]
+    fn write_rle() {
+        let rle = "#N Acorn\n#C A methuselah.\nx = 7, y = 4, rule = B3/S23\nbo$3bo$2o2b3o!\n";
+        let pattern = Pattern::from_rle(rle).unwrap();
+        assert_eq!(pattern.to_rle(), rle);
+
+        let wide = Pattern {
+            cells: vec![(0..100).map(|x| x % 3 == 0).collect(); 2],
+            rule: None,
+            metadata: Metadata::default(),
+        };
+        let rle = wide.to_rle();
+        assert!(rle.lines().all(|line| line.len() <= 70));
+        assert_eq!(Pattern::from_rle(&rle).unwrap(), wide);
+    }
+
+    #[test]
+    fn extended_rle() {
+        let rle = "#CXRLE Pos=-1000,25 Gen=1234\n#N Glider\nx = 3, y = 3, rule = B3/S23\n\
+                   bo$2bo$3o!\n";
+        let pattern = Pattern::from_rle(rle).unwrap();
+        assert_eq!(pattern.metadata.position, Some((-1000, 25)));
+        assert_eq!(pattern.metadata.generation, Some(1234));
+        assert!(pattern.metadata.comments.is_empty());
+        assert_eq!(pattern.to_rle(), rle);
+
+        let pattern = Pattern::from_rle("#CXRLE Gen=7 Pos=x,1\nx = 1, y = 1\no!").unwrap();
+        assert_eq!(pattern.metadata.position, None);
+        assert_eq!(pattern.metadata.generation, Some(7));
+    }
+
+    #[test]
+    fn blank_rows_and_padding() {
+        let pattern = Pattern::from_rle("x = 4, y = 4\n2o2$3bo!").unwrap();
+        assert_eq!(pattern.cells.len(), 4);
+        assert_eq!(pattern.cells[0], vec![true, true, false, false]);
+        assert_eq!(pattern.cells[1], vec![false; 4]);
+        assert_eq!(pattern.cells[2], vec![false, false, false, true]);
+        assert_eq!(pattern.cells[3], vec![false; 4]);
+    }
+
+    #[test]
+    fn load_library_dir() {
+        let dir = env::temp_dir().join(format!("tui-ca-library-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("my-ship.rle"), "x = 3, y = 1\n3o!").unwrap();
+        fs::write(dir.join("broken.rle"), "x = 1, y = 1\n5o!").unwrap();
+        fs::write(dir.join("notes.txt"), "not a pattern").unwrap();
+
+        let (library, errors) = Library::load_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(library.names().collect::<Vec<_>>(), vec!["my-ship"]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].0.ends_with("broken.rle"));
+
+        let Some(Preset::User { name, pattern }) = library.resolve("My Ship") else {
+            panic!("user pattern not found");
+        };
+        assert_eq!(name, "my-ship");
+        assert_eq!(pattern.size(), (3, 1));
+        assert_eq!(library.resolve("glider"), Some(Preset::Glider));
+        assert_eq!(library.resolve("missing"), None);
+    }
+
+    #[test]
+    fn transforms() {
+        // an L: three cells down the left, one to the right at the bottom
+        let l = Pattern::from_rle("x = 2, y = 3\no$o$2o!").unwrap();
+
+        let rotated = l.transformed(Transform {
+            rotation: Rotation::Quarter,
+            ..Transform::default()
+        });
+        assert_eq!(
+            rotated.cells,
+            vec![vec![true, true, true], vec![true, false, false]]
+        );
+
+        let flipped = l.transformed(Transform {
+            flip_h: true,
+            ..Transform::default()
+        });
+        assert_eq!(
+            flipped.cells,
+            vec![vec![false, true], vec![false, true], vec![true, true]]
+        );
+
+        let full_turn = l
+            .transformed(Transform {
+                rotation: Rotation::Half,
+                ..Transform::default()
+            })
+            .transformed(Transform {
+                rotation: Rotation::Half,
+                ..Transform::default()
+            });
+        assert_eq!(full_turn, l);
+
+        assert_eq!(Rotation::parse("-90"), Ok(Rotation::ThreeQuarters));
+        assert!(Rotation::parse("45").is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_body() {
+        assert!(Pattern::from_rle("x = 2, y = 1\n3o!").is_err());
+        assert!(Pattern::from_rle("x = 2, y = 1\no?o!").is_err());
+    }
+}
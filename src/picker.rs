@@ -0,0 +1,143 @@
+/// Incremental fuzzy search over pattern names, in the style of fzf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picker {
+    pub query: String,
+    candidates: Vec<String>,
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl Picker {
+    pub fn new(candidates: Vec<String>) -> Picker {
+        let mut picker = Picker {
+            query: String::new(),
+            matches: vec![],
+            candidates,
+            selected: 0,
+        };
+        picker.refresh();
+        picker
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.query.push(ch);
+        self.refresh();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.refresh();
+    }
+
+    /// Moves the selection by `delta` entries, wrapping around the list of matches.
+    pub fn select_relative(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let len = self.matches.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+    }
+
+    /// Names matching the query, best match first.
+    pub fn matches(&self) -> impl Iterator<Item = &str> {
+        self.matches.iter().map(|i| &self.candidates[*i][..])
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.matches
+            .get(self.selected)
+            .map(|i| &self.candidates[*i][..])
+    }
+
+    fn refresh(&mut self) {
+        let mut scored: Vec<(i32, usize)> = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, candidate)| Some((fuzzy_score(&self.query, candidate)?, i)))
+            .collect();
+        // best score first, ties keep their original order
+        scored.sort_by_key(|(score, i)| (-score, *i));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+    }
+}
+
+/// Scores how well `query` matches `candidate`, or returns `None` if the query's characters
+/// don't all appear in order. Matching is case-insensitive; consecutive characters and
+/// characters at the start of a word score higher.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut candidate_chars = candidate.chars().enumerate().peekable();
+    let mut previous_match: Option<usize> = None;
+    let mut previous_char: Option<char> = None;
+
+    for wanted in query.chars().filter(|ch| !ch.is_whitespace()) {
+        let wanted = wanted.to_ascii_lowercase();
+        loop {
+            let (index, ch) = candidate_chars.next()?;
+            let word_start = index == 0
+                || ch.is_uppercase()
+                || previous_char.is_some_and(|prev| !prev.is_alphanumeric());
+            previous_char = Some(ch);
+            if ch.to_ascii_lowercase() != wanted {
+                continue;
+            }
+
+            score += 1;
+            if previous_match.is_some_and(|prev| prev + 1 == index) {
+                score += 5;
+            }
+            if word_start {
+                score += 3;
+            }
+            previous_match = Some(index);
+            break;
+        }
+    }
+
+    // prefer shorter names when everything else is equal
+    Some(score * 100 - candidate.chars().count() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores() {
+        assert!(fuzzy_score("ggg", "GosperGliderGun").is_some());
+        assert!(fuzzy_score("xyz", "GosperGliderGun").is_none());
+        assert!(fuzzy_score("", "Glider").is_some());
+        assert!(fuzzy_score("gli", "Glider") > fuzzy_score("gli", "GosperGliderGun"));
+    }
+
+    #[test]
+    fn picker_narrows_and_selects() {
+        let names = ["Blinker", "Glider", "GosperGliderGun", "Pulsar"]
+            .map(String::from)
+            .to_vec();
+        let mut picker = Picker::new(names);
+        assert_eq!(picker.matches().count(), 4);
+
+        picker.push('g');
+        picker.push('l');
+        assert_eq!(
+            picker.matches().collect::<Vec<_>>(),
+            ["Glider", "GosperGliderGun"]
+        );
+        picker.select_relative(1);
+        assert_eq!(picker.selected(), Some("GosperGliderGun"));
+        picker.select_relative(1);
+        assert_eq!(picker.selected(), Some("Glider"));
+
+        picker.push('q');
+        assert_eq!(picker.selected(), None);
+        picker.pop();
+        assert_eq!(picker.selected(), Some("Glider"));
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    fmt::Write as _,
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use tracing::info;
+
+use crate::{
+    app::Model,
+    headless::population,
+    status::{self, Response},
+};
+
+/// The board's numbers as of the last time they were recorded.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Recorded {
+    rule: String,
+    generation: u64,
+    population: usize,
+    tick: Duration,
+    /// Births and deaths over every generation recorded, for the counters.
+    births: u64,
+    deaths: u64,
+    /// The [`Stats::recorded`](crate::stats::Stats::recorded) count already added to the
+    /// counters.
+    counted: u64,
+}
+
+static RECORDED: OnceLock<Mutex<Recorded>> = OnceLock::new();
+
+/// Parses an address to serve metrics on, such as `0.0.0.0:9898`, or a port alone to serve
+/// them on loopback only.
+pub fn parse_addr(input: &str) -> Result<SocketAddr, String> {
+    let input = input.trim();
+    if let Ok(port) = input.parse::<u16>() {
+        return Ok(SocketAddr::from((Ipv4Addr::LOCALHOST, port)));
+    }
+    input.parse().map_err(|_| {
+        format!("expected an address such as 127.0.0.1:9898 or a port, got \"{input}\"")
+    })
+}
+
+/// Serves Prometheus metrics at `/metrics` on `addr`, from now on.
+pub fn serve(addr: SocketAddr) -> io::Result<SocketAddr> {
+    let _ = RECORDED.set(Mutex::new(Recorded::default()));
+    let addr = status::listen(addr, route)?;
+    info!(%addr, "serving Prometheus metrics");
+    Ok(addr)
+}
+
+/// Records the board's numbers for the next scrape, if metrics are being served, counting
+/// the births and deaths of the generations since the last time. Called before every frame.
+pub fn record(model: &Model) {
+    if let Some(recorded) = RECORDED.get() {
+        if let Ok(mut recorded) = recorded.lock() {
+            recorded.update(model);
+        }
+    }
+}
+
+impl Recorded {
+    fn update(&mut self, model: &Model) {
+        // count each generation stepped once, by when it was recorded rather than by its
+        // number, which goes back on stepping back or rolling back to a checkpoint
+        let stats = model.stats();
+        let new = stats.recorded() - self.counted;
+        let kept = stats.samples().count();
+        for sample in stats.samples().skip(kept.saturating_sub(new as usize)) {
+            self.births += sample.births as u64;
+            self.deaths += sample.deaths as u64;
+        }
+        self.counted = stats.recorded();
+        self.rule = model.rulestring();
+        self.generation = model.generation();
+        self.population = population(model);
+        self.tick = model.metrics().tick;
+    }
+
+    /// The numbers in Prometheus's text format.
+    fn exposition(&self) -> String {
+        let rule = self.rule.replace('\\', "\\\\").replace('"', "\\\"");
+        let metrics = [
+            (
+                "generation",
+                "gauge",
+                "The generation the board has reached.",
+                String::new(),
+                self.generation.to_string(),
+            ),
+            (
+                "population",
+                "gauge",
+                "Live cells on the board.",
+                String::new(),
+                self.population.to_string(),
+            ),
+            (
+                "tick_seconds",
+                "gauge",
+                "How long the last generation took to step.",
+                String::new(),
+                self.tick.as_secs_f64().to_string(),
+            ),
+            (
+                "births_total",
+                "counter",
+                "Cells born since the app started.",
+                String::new(),
+                self.births.to_string(),
+            ),
+            (
+                "deaths_total",
+                "counter",
+                "Cells that died since the app started.",
+                String::new(),
+                self.deaths.to_string(),
+            ),
+            (
+                "info",
+                "gauge",
+                "The rule being run, as a label.",
+                format!("{{rule=\"{rule}\"}}"),
+                String::from("1"),
+            ),
+        ];
+        let mut text = String::new();
+        for (name, kind, help, labels, value) in metrics {
+            let name = format!("cellular_automaton_{name}");
+            let _ = write!(
+                text,
+                "# HELP {name} {help}\n# TYPE {name} {kind}\n{name}{labels} {value}\n"
+            );
+        }
+        text
+    }
+}
+
+fn route(path: &str) -> Response {
+    match (
+        path,
+        RECORDED.get().and_then(|recorded| recorded.lock().ok()),
+    ) {
+        ("/metrics", Some(recorded)) => Response::ok(
+            "text/plain; version=0.0.4",
+            recorded.exposition().into_bytes(),
+        ),
+        _ => Response::text("404 Not Found", "metrics are at /metrics"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{Message, Preset, Rule};
+
+    #[test]
+    fn exposes_metrics() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        let mut recorded = Recorded::default();
+        model.update(Message::ToggleEditing);
+        for _ in 0..2 {
+            model.update(Message::Idle);
+            recorded.update(&model);
+        }
+        assert_eq!((recorded.generation, recorded.population), (2, 3));
+        assert_eq!((recorded.births, recorded.deaths), (4, 4));
+
+        let text = recorded.exposition();
+        assert!(text.contains("# TYPE cellular_automaton_births_total counter\n"));
+        assert!(text.contains("\ncellular_automaton_population 3\n"));
+        assert!(text.ends_with("cellular_automaton_info{rule=\"B3/S23\"} 1\n"));
+
+        // stepping back takes the generation down without anything being born or dying, and
+        // the generations stepped again afterwards are counted once each
+        model.set_rule("B3/S23Reversible".parse::<Rule>().unwrap());
+        for _ in 0..2 {
+            model.update(Message::Idle);
+        }
+        recorded.update(&model);
+        let counted = (recorded.births, recorded.deaths);
+        model.update(Message::ToggleEditing);
+        model.update(Message::StepBack);
+        model.update(Message::StepBack);
+        recorded.update(&model);
+        assert_eq!(recorded.generation, 2);
+        assert_eq!((recorded.births, recorded.deaths), counted);
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        recorded.update(&model);
+        let latest = *model.stats().latest().unwrap();
+        assert_eq!(
+            (recorded.births, recorded.deaths),
+            (
+                counted.0 + latest.births as u64,
+                counted.1 + latest.deaths as u64
+            )
+        );
+    }
+
+    #[test]
+    fn parses_addresses() {
+        assert_eq!(
+            parse_addr("9898"),
+            Ok(SocketAddr::from((Ipv4Addr::LOCALHOST, 9898)))
+        );
+        assert_eq!(
+            parse_addr("0.0.0.0:80"),
+            Ok(SocketAddr::from(([0, 0, 0, 0], 80)))
+        );
+        assert!(parse_addr("[::1]:9898").unwrap().ip().is_loopback());
+        assert!(parse_addr("localhost").is_err());
+    }
+}
@@ -0,0 +1,444 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use tracing::info;
+
+use crate::app::{Anchor, Coords, Direction, Message};
+
+/// The first line of every replay file.
+const MAGIC: &str = "# tui-cellular-automaton replay";
+
+/// The longest run of ticks written as a single line.
+const IDLE_RUN: Duration = Duration::from_secs(1);
+
+/// What a recorded session started from: the command line it was run with, minus `--record`,
+/// the seed it ended up using and the size of its board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub args: Vec<String>,
+    pub seed: Option<u64>,
+    /// The largest `(x, y)` coordinates on the board.
+    pub max_coords: (i16, i16),
+}
+
+/// `args` without the `--record` and `--replay` options, which shouldn't happen again when a
+/// recorded session is replayed.
+pub fn replayable_args(args: &[String]) -> Vec<String> {
+    let mut replayable = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--record" | "--replay" => {
+                args.next();
+            }
+            arg if arg.starts_with("--record=") || arg.starts_with("--replay=") => {}
+            arg => replayable.push(String::from(arg)),
+        }
+    }
+    replayable
+}
+
+/// A recorded session: its [`Header`] and every message the model received, each with the
+/// time since the start at which it arrived.
+///
+/// Replaying the messages against a model built from the header reaches the same boards, as
+/// long as the session didn't depend on files that may since have changed: quick-save slots,
+/// the recent runs and the presets directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub header: Header,
+    pub messages: Vec<(Duration, Message)>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> io::Result<Replay> {
+        info!(path = %path.display(), "loading replay");
+        let text = fs::read_to_string(path)?;
+        Replay::parse(&text).map_err(|reason| io::Error::new(io::ErrorKind::InvalidData, reason))
+    }
+
+    fn parse(text: &str) -> Result<Replay, String> {
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, line)| line) != Some(MAGIC) {
+            return Err(String::from("not a replay file"));
+        }
+
+        let mut args = None;
+        let mut seed = None;
+        let mut max_coords = None;
+        let mut messages = vec![];
+        for (number, line) in lines {
+            let error = |reason: &str| format!("line {}: {reason}", number + 1);
+            let mut fields = line.split('\t');
+            match fields.next() {
+                Some("args") => args = Some(fields.map(String::from).collect()),
+                Some("seed") => {
+                    seed = fields.next().and_then(|seed| seed.parse().ok());
+                }
+                Some("size") => {
+                    let mut size = fields.filter_map(|field| field.parse().ok());
+                    max_coords = size.next().zip(size.next());
+                }
+                Some("") | None => {}
+                Some(millis) => {
+                    let millis = millis
+                        .parse()
+                        .map_err(|_| error("expected a time in milliseconds"))?;
+                    let msg = fields.next().ok_or_else(|| error("missing message"))?;
+                    let (msg, count) = match msg.split_once('*') {
+                        Some((msg, count)) => (
+                            msg,
+                            count.parse().map_err(|_| error("invalid repeat count"))?,
+                        ),
+                        None => (msg, 1),
+                    };
+                    let msg = decode(msg).map_err(|reason| error(&reason))?;
+                    // repeats are spread evenly up to the time of the last one
+                    let last: u64 = match fields.next() {
+                        Some(last) => last.parse().map_err(|_| error("invalid end time"))?,
+                        None => millis,
+                    };
+                    for i in 0..count {
+                        let offset = (last.saturating_sub(millis)) * i / (count - 1).max(1);
+                        messages.push((Duration::from_millis(millis + offset), msg.clone()));
+                    }
+                }
+            }
+        }
+
+        Ok(Replay {
+            header: Header {
+                args: args.ok_or("missing args line")?,
+                seed,
+                max_coords: max_coords.ok_or("missing size line")?,
+            },
+            messages,
+        })
+    }
+}
+
+/// Writes every message the model receives to a replay file as it happens. Runs of `Idle`
+/// messages, one per tick, are written as one line per [`IDLE_RUN`], with the times of the
+/// first and the last.
+#[derive(Debug)]
+pub struct Recorder {
+    out: BufWriter<File>,
+    start: Instant,
+    /// When the current run of `Idle` messages began and its last message arrived, and how
+    /// long it is.
+    idles: Option<(Duration, Duration, u64)>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, header: &Header) -> io::Result<Recorder> {
+        info!(path = %path.display(), "recording");
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{MAGIC}")?;
+        writeln!(out, "args\t{}", header.args.join("\t"))?;
+        if let Some(seed) = header.seed {
+            writeln!(out, "seed\t{seed}")?;
+        }
+        let (max_x, max_y) = header.max_coords;
+        writeln!(out, "size\t{max_x}\t{max_y}")?;
+        Ok(Recorder {
+            out,
+            start: Instant::now(),
+            idles: None,
+        })
+    }
+
+    pub fn record(&mut self, msg: &Message) -> io::Result<()> {
+        let elapsed = self.start.elapsed();
+        if *msg == Message::Idle {
+            if self
+                .idles
+                .is_some_and(|(first, _, _)| elapsed - first >= IDLE_RUN)
+            {
+                self.write_idles()?;
+            }
+            let (_, last, count) = self.idles.get_or_insert((elapsed, elapsed, 0));
+            *last = elapsed;
+            *count += 1;
+            return Ok(());
+        }
+        self.write_idles()?;
+        writeln!(self.out, "{}\t{}", elapsed.as_millis(), encode(msg))
+    }
+
+    fn write_idles(&mut self) -> io::Result<()> {
+        match self.idles.take() {
+            Some((first, last, count)) => writeln!(
+                self.out,
+                "{}\tIdle*{count}\t{}",
+                first.as_millis(),
+                last.as_millis()
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        // errors can't be reported from here, and the messages before them are already written
+        let _ = self.write_idles().and_then(|()| self.out.flush());
+    }
+}
+
+fn encode(msg: &Message) -> String {
+    match msg {
+        Message::Move(dir, cells) => format!("Move {} {cells}", encode_direction(*dir)),
+        Message::ToggleCellState => String::from("ToggleCellState"),
+        Message::Randomize => String::from("Randomize"),
+        Message::RandomizeView => String::from("RandomizeView"),
+        Message::ToggleGhost => String::from("ToggleGhost"),
+        Message::ToggleMark => String::from("ToggleMark"),
+        Message::StepBack => String::from("StepBack"),
+        Message::Mutate => String::from("Mutate"),
+        Message::ToggleZen => String::from("ToggleZen"),
+        Message::Faster => String::from("Faster"),
+        Message::Slower => String::from("Slower"),
+        Message::Zoom(steps) => format!("Zoom {steps}"),
+        Message::SetCell(Coords { x, y }, alive) => format!("SetCell {x} {y} {alive}"),
+        Message::Pan(columns, rows) => format!("Pan {columns} {rows}"),
+        Message::SwitchScreen => String::from("SwitchScreen"),
+        Message::ToggleInset => String::from("ToggleInset"),
+        Message::ToggleInsetPin => String::from("ToggleInsetPin"),
+        Message::FocusLost => String::from("FocusLost"),
+        Message::FocusGained => String::from("FocusGained"),
+        Message::ToggleEditing => String::from("ToggleEditing"),
+        Message::OpenPrompt => String::from("OpenPrompt"),
+        // characters are written as code points, since they may be spaces or tabs
+        Message::PromptInput(ch) => format!("PromptInput {}", *ch as u32),
+        Message::PromptBackspace => String::from("PromptBackspace"),
+        Message::PromptSubmit => String::from("PromptSubmit"),
+        Message::PromptCancel => String::from("PromptCancel"),
+        Message::OpenPicker => String::from("OpenPicker"),
+        Message::OpenRecent => String::from("OpenRecent"),
+        Message::OpenCheckpoints => String::from("OpenCheckpoints"),
+        Message::OpenWorkspace => String::from("OpenWorkspace"),
+        Message::DismissError => String::from("DismissError"),
+        Message::ToggleDebug => String::from("ToggleDebug"),
+        Message::ToggleOverlay => String::from("ToggleOverlay"),
+        Message::CycleTimeLapse => String::from("CycleTimeLapse"),
+        Message::OpenResize => String::from("OpenResize"),
+        Message::Resize(width, height, anchor) => {
+            let anchor = match anchor {
+                Anchor::Center => "center",
+                Anchor::TopLeft => "top-left",
+            };
+            format!("Resize {width} {height} {anchor}")
+        }
+        Message::ToggleHelp => String::from("ToggleHelp"),
+        Message::ToggleInspector => String::from("ToggleInspector"),
+        Message::FastForward => String::from("FastForward"),
+        Message::RunUntilStable => String::from("RunUntilStable"),
+        Message::StopFastForward => String::from("StopFastForward"),
+        Message::Continue => String::from("Continue"),
+        Message::Predict(predicted) => format!("Predict {predicted}"),
+        Message::Describe => String::from("Describe"),
+        Message::SelectSlot(slot) => format!("SelectSlot {slot}"),
+        Message::PickerInput(ch) => format!("PickerInput {}", *ch as u32),
+        Message::PickerBackspace => String::from("PickerBackspace"),
+        Message::PickerMove(dir) => format!("PickerMove {}", encode_direction(*dir)),
+        Message::PickerSubmit => String::from("PickerSubmit"),
+        Message::PickerCancel => String::from("PickerCancel"),
+        Message::Idle => String::from("Idle"),
+        Message::Quit => String::from("Quit"),
+    }
+}
+
+fn decode(text: &str) -> Result<Message, String> {
+    let mut words = text.split(' ');
+    let name = words.next().unwrap_or_default();
+    let mut arg = || {
+        words
+            .next()
+            .ok_or_else(|| format!("{name} is missing an argument"))
+    };
+    let number = |word: &str| {
+        word.parse::<i16>()
+            .map_err(|_| format!("\"{word}\" is not a number"))
+    };
+    let ch = |word: &str| {
+        word.parse()
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| format!("\"{word}\" is not a character code"))
+    };
+
+    let msg = match name {
+        "Move" => Message::Move(decode_direction(arg()?)?, number(arg()?)?),
+        "ToggleCellState" => Message::ToggleCellState,
+        "Randomize" => Message::Randomize,
+        "RandomizeView" => Message::RandomizeView,
+        "ToggleGhost" => Message::ToggleGhost,
+        "ToggleMark" => Message::ToggleMark,
+        "StepBack" => Message::StepBack,
+        "Mutate" => Message::Mutate,
+        "ToggleZen" => Message::ToggleZen,
+        "Faster" => Message::Faster,
+        "Slower" => Message::Slower,
+        "Zoom" => Message::Zoom(number(arg()?)?),
+        "SetCell" => {
+            let coords = Coords {
+                x: number(arg()?)?,
+                y: number(arg()?)?,
+            };
+            let alive = arg()?
+                .parse()
+                .map_err(|_| String::from("expected true or false"))?;
+            Message::SetCell(coords, alive)
+        }
+        "Pan" => Message::Pan(number(arg()?)?, number(arg()?)?),
+        "SwitchScreen" => Message::SwitchScreen,
+        "ToggleInset" => Message::ToggleInset,
+        "ToggleInsetPin" => Message::ToggleInsetPin,
+        "FocusLost" => Message::FocusLost,
+        "FocusGained" => Message::FocusGained,
+        "ToggleEditing" => Message::ToggleEditing,
+        "OpenPrompt" => Message::OpenPrompt,
+        "PromptInput" => Message::PromptInput(ch(arg()?)?),
+        "PromptBackspace" => Message::PromptBackspace,
+        "PromptSubmit" => Message::PromptSubmit,
+        "PromptCancel" => Message::PromptCancel,
+        "OpenPicker" => Message::OpenPicker,
+        "OpenRecent" => Message::OpenRecent,
+        "OpenCheckpoints" => Message::OpenCheckpoints,
+        "OpenWorkspace" => Message::OpenWorkspace,
+        "DismissError" => Message::DismissError,
+        "ToggleDebug" => Message::ToggleDebug,
+        "ToggleOverlay" => Message::ToggleOverlay,
+        "CycleTimeLapse" => Message::CycleTimeLapse,
+        "OpenResize" => Message::OpenResize,
+        "Resize" => {
+            let size = |word: &str| {
+                word.parse::<u16>()
+                    .map_err(|_| format!("\"{word}\" is not a size"))
+            };
+            Message::Resize(size(arg()?)?, size(arg()?)?, Anchor::parse(arg()?)?)
+        }
+        "ToggleHelp" => Message::ToggleHelp,
+        "ToggleInspector" => Message::ToggleInspector,
+        "FastForward" => Message::FastForward,
+        "RunUntilStable" => Message::RunUntilStable,
+        "StopFastForward" => Message::StopFastForward,
+        "Continue" => Message::Continue,
+        "Predict" => Message::Predict(
+            arg()?
+                .parse()
+                .map_err(|_| String::from("invalid prediction"))?,
+        ),
+        "Describe" => Message::Describe,
+        "SelectSlot" => {
+            Message::SelectSlot(arg()?.parse().map_err(|_| String::from("invalid slot"))?)
+        }
+        "PickerInput" => Message::PickerInput(ch(arg()?)?),
+        "PickerBackspace" => Message::PickerBackspace,
+        "PickerMove" => Message::PickerMove(decode_direction(arg()?)?),
+        "PickerSubmit" => Message::PickerSubmit,
+        "PickerCancel" => Message::PickerCancel,
+        "Idle" => Message::Idle,
+        "Quit" => Message::Quit,
+        _ => return Err(format!("unknown message \"{text}\"")),
+    };
+    Ok(msg)
+}
+
+fn encode_direction(dir: Direction) -> &'static str {
+    match dir {
+        Direction::Up => "Up",
+        Direction::Down => "Down",
+        Direction::Left => "Left",
+        Direction::Right => "Right",
+    }
+}
+
+fn decode_direction(word: &str) -> Result<Direction, String> {
+    match word {
+        "Up" => Ok(Direction::Up),
+        "Down" => Ok(Direction::Down),
+        "Left" => Ok(Direction::Left),
+        "Right" => Ok(Direction::Right),
+        _ => Err(format!("\"{word}\" is not a direction")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, time::Duration};
+
+    use super::*;
+    use crate::app::{Model, Preset};
+
+    #[test]
+    fn record_and_replay() {
+        let path = env::temp_dir().join(format!("tui-ca-replay-{}", std::process::id()));
+        let header = Header {
+            args: vec![String::from("tui-cellular-automaton"), String::from("-r")],
+            seed: Some(3),
+            max_coords: (9, 9),
+        };
+        let messages = [
+            Message::Move(Direction::Right, 2),
+            Message::ToggleCellState,
+            Message::SetCell(Coords { x: 3, y: 1 }, true),
+            Message::Resize(12, 8, Anchor::TopLeft),
+            Message::PromptInput(' '),
+            Message::ToggleEditing,
+            Message::Idle,
+            Message::Idle,
+            Message::Idle,
+            Message::Quit,
+        ];
+
+        let mut recorder = Recorder::create(&path, &header).unwrap();
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Glider);
+        for msg in &messages {
+            recorder.record(msg).unwrap();
+            model.update(msg.clone());
+        }
+        drop(recorder);
+
+        let replay = Replay::load(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().matches("Idle").count(),
+            1
+        );
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(replay.header, header);
+        let replayed: Vec<&Message> = replay.messages.iter().map(|(_, msg)| msg).collect();
+        assert_eq!(replayed, messages.iter().collect::<Vec<_>>());
+
+        let mut again = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        again.load_preset(Preset::Glider);
+        for (_, msg) in replay.messages {
+            again.update(msg);
+        }
+        assert_eq!(again.cells(), model.cells());
+        assert!(Replay::parse("hello").is_err());
+
+        let ticks = Replay::parse(&format!(
+            "{MAGIC}\nargs\tca\nsize\t9\t9\n100\tIdle*3\t300\n"
+        ))
+        .unwrap()
+        .messages;
+        let times: Vec<u128> = ticks.iter().map(|(at, _)| at.as_millis()).collect();
+        assert_eq!(times, vec![100, 200, 300]);
+    }
+
+    #[test]
+    fn replayable_command_line() {
+        let args: Vec<String> = ["ca", "--record", "a.replay", "-p", "glider", "--replay=b"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(replayable_args(&args), vec!["ca", "-p", "glider"]);
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+
+use rand::{thread_rng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// The source of randomness held by a [`Model`](crate::app::Model). Normally a seeded ChaCha
+/// generator, so any run can be reproduced from its seed, but any [`RngCore`] can be injected
+/// in tests.
+pub struct RngSource {
+    rng: Box<dyn RngCore>,
+    seed: Option<u64>,
+}
+
+impl RngSource {
+    pub fn seeded(seed: u64) -> RngSource {
+        RngSource {
+            rng: Box::new(ChaCha8Rng::seed_from_u64(seed)),
+            seed: Some(seed),
+        }
+    }
+
+    /// A generator with a freshly drawn seed, which is still recorded and available through
+    /// [`RngSource::seed`].
+    pub fn from_entropy() -> RngSource {
+        RngSource::seeded(thread_rng().gen())
+    }
+
+    /// Wraps an arbitrary generator. The resulting source has no seed.
+    #[cfg(test)]
+    pub fn from_rng(rng: impl RngCore + 'static) -> RngSource {
+        RngSource {
+            rng: Box::new(rng),
+            seed: None,
+        }
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
+
+impl Default for RngSource {
+    fn default() -> RngSource {
+        RngSource::from_entropy()
+    }
+}
+
+impl RngCore for RngSource {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl fmt::Debug for RngSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RngSource")
+            .field("seed", &self.seed)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::mock::StepRng;
+
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = RngSource::seeded(42);
+        let mut b = RngSource::seeded(42);
+        for _ in 0..16 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_eq!(a.seed(), Some(42));
+    }
+
+    #[test]
+    fn injected_rng() {
+        let mut source = RngSource::from_rng(StepRng::new(7, 1));
+        assert_eq!(source.next_u64(), 7);
+        assert_eq!(source.next_u64(), 8);
+        assert_eq!(source.seed(), None);
+    }
+}
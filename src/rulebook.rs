@@ -0,0 +1,164 @@
+use crate::app::Rule;
+
+/// A well-known outer-totalistic rule, for the header to name.
+#[derive(Debug, PartialEq, Eq)]
+pub struct KnownRule {
+    pub name: &'static str,
+    /// What the rule is known for, in a line.
+    pub description: &'static str,
+    birth: &'static [u8],
+    survival: &'static [u8],
+}
+
+const fn known(
+    name: &'static str,
+    birth: &'static [u8],
+    survival: &'static [u8],
+    description: &'static str,
+) -> KnownRule {
+    KnownRule {
+        name,
+        description,
+        birth,
+        survival,
+    }
+}
+
+/// The rules named in the header, by their birth and survival counts in ascending order.
+const RULES: [KnownRule; 20] = [
+    known(
+        "Life",
+        &[3],
+        &[2, 3],
+        "gliders, oscillators and still lifes",
+    ),
+    known("HighLife", &[3, 6], &[2, 3], "Life with a small replicator"),
+    known(
+        "Seeds",
+        &[2],
+        &[],
+        "every cell dies at once, so almost anything explodes",
+    ),
+    known(
+        "Day & Night",
+        &[3, 6, 7, 8],
+        &[3, 4, 6, 7, 8],
+        "the same with live and dead cells swapped",
+    ),
+    known("Maze", &[3], &[1, 2, 3, 4, 5], "grows into mazes"),
+    known(
+        "Mazectric",
+        &[3],
+        &[1, 2, 3, 4],
+        "grows into mazes with long, straight corridors",
+    ),
+    known(
+        "Replicator",
+        &[1, 3, 5, 7],
+        &[1, 3, 5, 7],
+        "every pattern turns into copies of itself",
+    ),
+    known(
+        "Fredkin",
+        &[1, 3, 5, 7],
+        &[0, 2, 4, 6, 8],
+        "every pattern turns into copies of itself",
+    ),
+    known(
+        "Life without Death",
+        &[3],
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8],
+        "cells never die, so patterns grow ladders",
+    ),
+    known(
+        "2x2",
+        &[3, 6],
+        &[1, 2, 5],
+        "blocks of 2x2 cells behave like cells of their own",
+    ),
+    known(
+        "Move",
+        &[3, 6, 8],
+        &[2, 4, 5],
+        "small spaceships and puffers",
+    ),
+    known("Coral", &[3], &[4, 5, 6, 7, 8], "slow, coral-like growth"),
+    known(
+        "Diamoeba",
+        &[3, 5, 6, 7, 8],
+        &[5, 6, 7, 8],
+        "large diamond-shaped blobs",
+    ),
+    known(
+        "Anneal",
+        &[4, 6, 7, 8],
+        &[3, 5, 6, 7, 8],
+        "a majority vote that smooths soups into blobs",
+    ),
+    known(
+        "34 Life",
+        &[3, 4],
+        &[3, 4],
+        "oscillators and spaceships from small seeds",
+    ),
+    known(
+        "DotLife",
+        &[3],
+        &[0, 2, 3],
+        "Life where a lone cell survives",
+    ),
+    known(
+        "Pedestrian Life",
+        &[3, 8],
+        &[2, 3],
+        "Life with a rare replicator",
+    ),
+    known(
+        "Gnarl",
+        &[1],
+        &[1],
+        "a single cell grows into a gnarled crystal",
+    ),
+    known(
+        "Long Life",
+        &[3, 4, 5],
+        &[5],
+        "oscillators with very long periods",
+    ),
+    known(
+        "Stains",
+        &[3, 6, 7, 8],
+        &[2, 3, 5, 6, 7, 8],
+        "soups settle into stains",
+    ),
+];
+
+/// The well-known rule `rule` is, whatever order its counts are written in. History and
+/// Reversible variants are named after the rule they vary.
+pub fn lookup(rule: &Rule) -> Option<&'static KnownRule> {
+    let sorted = |counts: &[u8]| {
+        let mut counts = counts.to_vec();
+        counts.sort_unstable();
+        counts.dedup();
+        counts
+    };
+    let (birth, survival) = (sorted(&rule.birth_list), sorted(&rule.survival_list));
+    RULES
+        .iter()
+        .find(|known| known.birth == birth && known.survival == survival)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_known_rules() {
+        let name = |rulestring: &str| lookup(&rulestring.parse().unwrap()).map(|known| known.name);
+        assert_eq!(name("B3/S23"), Some("Life"));
+        assert_eq!(name("B63/S32"), Some("HighLife"));
+        assert_eq!(name("B2/S"), Some("Seeds"));
+        assert_eq!(name("B3/S23History"), Some("Life"));
+        assert_eq!(name("B3/S2"), None);
+    }
+}
@@ -0,0 +1,215 @@
+use std::{fs, path::Path};
+
+use crate::app::{parse_coords, Coords, Rule};
+
+/// A step of a [`Scenario`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    /// Loads a pattern by name with its top-left corner at `at`.
+    Load { name: String, at: Coords },
+    /// Switches to another rule.
+    Rule(Rule),
+    /// Shows text until the user goes on.
+    Say(String),
+    /// Asks whether the cell at the given coordinates is alive next generation, then shows
+    /// the answer and why until the user goes on.
+    Predict(Coords),
+    /// Steps the board this many generations.
+    Advance(u64),
+}
+
+/// A lesson played through beside the board with `lesson`, a step at a time.
+///
+/// Lessons are written as text, one step per line: `load NAME [X,Y]` loads a pattern,
+/// `rule RULESTRING` changes the rule, `say TEXT` shows text, `predict X,Y` asks whether a
+/// cell will be alive next generation and `advance N` steps the board. An optional `title`
+/// line names the lesson, and `#` starts a comment:
+///
+/// ```text
+/// title Blinkers
+/// load blinker 2,2
+/// say Three cells in a row: the middle has two live neighbors, the ends one each.
+/// predict 3,3
+/// advance 1
+/// say The ends died and two cells were born, turning the row on its side.
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub title: Option<String>,
+    pub steps: Vec<Step>,
+    /// Steps done so far.
+    done: usize,
+    /// The answer to the current `predict` step, once it's given.
+    answer: Option<Answer>,
+}
+
+/// A prediction and how it turned out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub predicted: bool,
+    /// Whether the cell really is alive next generation, unless a continuous rule decides.
+    pub actual: Option<bool>,
+    /// Why, in a line.
+    pub reason: String,
+}
+
+/// What a scenario is waiting for from the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waiting {
+    /// Enter, to go on to the next step.
+    Continue,
+    /// A yes or no prediction.
+    Prediction,
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Scenario, String> {
+        let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        Scenario::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Scenario, String> {
+        let mut title = None;
+        let mut steps = vec![];
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            match line.split_once(' ').unwrap_or((line, "")) {
+                ("", _) => {}
+                ("title", rest) => title = Some(String::from(rest.trim())),
+                (directive, rest) => steps.push(
+                    parse_step(directive, rest.trim())
+                        .map_err(|reason| format!("line {}: {reason}", number + 1))?,
+                ),
+            }
+        }
+        if steps.is_empty() {
+            return Err(String::from("the lesson has no steps"));
+        }
+        Ok(Scenario {
+            title,
+            steps,
+            done: 0,
+            answer: None,
+        })
+    }
+
+    /// The names of the patterns the lesson loads, to check they exist before it starts.
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.steps.iter().filter_map(|step| match step {
+            Step::Load { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// The step the lesson is at, or `None` once it's over.
+    pub fn current(&self) -> Option<&Step> {
+        self.steps.get(self.done)
+    }
+
+    /// How many steps have been done.
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    pub fn answer(&self) -> Option<&Answer> {
+        self.answer.as_ref()
+    }
+
+    /// What the current step waits for, if anything.
+    pub fn waiting(&self) -> Option<Waiting> {
+        match (self.current()?, &self.answer) {
+            (Step::Say(_), _) | (Step::Predict(_), Some(_)) => Some(Waiting::Continue),
+            (Step::Predict(_), None) => Some(Waiting::Prediction),
+            _ => None,
+        }
+    }
+
+    /// Records the answer to the current `predict` step.
+    pub fn answer_with(&mut self, answer: Answer) {
+        if self.waiting() == Some(Waiting::Prediction) {
+            self.answer = Some(answer);
+        }
+    }
+
+    /// Moves on to the next step.
+    pub fn next(&mut self) {
+        if self.done < self.steps.len() {
+            self.done += 1;
+            self.answer = None;
+        }
+    }
+}
+
+fn parse_step(directive: &str, rest: &str) -> Result<Step, String> {
+    let step = match directive {
+        "load" => {
+            let (name, at) = match rest.rsplit_once(' ') {
+                Some((name, at)) if parse_coords(at).is_ok() => (name.trim(), parse_coords(at)?),
+                _ => (rest, Coords { x: 0, y: 0 }),
+            };
+            Step::Load {
+                name: String::from(name),
+                at,
+            }
+        }
+        "rule" => Step::Rule(rest.parse()?),
+        "say" => Step::Say(String::from(rest)),
+        "predict" => Step::Predict(parse_coords(rest)?),
+        "advance" => Step::Advance(
+            rest.parse()
+                .map_err(|_| format!("\"{rest}\" is not a number of generations"))?,
+        ),
+        _ => {
+            return Err(format!(
+                "expected load, rule, say, predict or advance, got \"{directive}\""
+            ))
+        }
+    };
+    match step {
+        Step::Load { ref name, .. } | Step::Say(ref name) if name.is_empty() => {
+            Err(format!("{directive} needs text"))
+        }
+        step => Ok(step),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_steps() {
+        let scenario = Scenario::parse(
+            "title Blinkers\n\
+             # a comment\n\
+             load blinker 2,2\n\
+             rule B3/S23\n\
+             say Watch the middle.\n\
+             predict 2,2\n\
+             advance 1\n",
+        )
+        .unwrap();
+        assert_eq!(scenario.title.as_deref(), Some("Blinkers"));
+        assert_eq!(
+            scenario.steps[0],
+            Step::Load {
+                name: String::from("blinker"),
+                at: Coords { x: 2, y: 2 }
+            }
+        );
+        assert_eq!(scenario.steps[4], Step::Advance(1));
+        assert_eq!(scenario.patterns().collect::<Vec<_>>(), ["blinker"]);
+
+        assert_eq!(
+            Scenario::parse("load r pentomino").unwrap().steps[0],
+            Step::Load {
+                name: String::from("r pentomino"),
+                at: Coords { x: 0, y: 0 }
+            }
+        );
+        assert!(Scenario::parse("advance many").is_err());
+        assert!(Scenario::parse("dance").is_err());
+        assert!(Scenario::parse("say").is_err());
+        assert!(Scenario::parse("# nothing").is_err());
+    }
+}
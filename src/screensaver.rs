@@ -0,0 +1,159 @@
+use std::time::{Duration, Instant};
+
+use tracing::info;
+
+use crate::{
+    app::{Message, Model, Rule, State},
+    headless::population,
+    theme::Theme,
+};
+
+/// A rule for `--screensaver` to take a turn with, and the theme to draw it in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub rule: Rule,
+    pub theme: Option<String>,
+}
+
+impl Entry {
+    /// Parses `RULE` or `RULE@THEME`, such as `B36/S23@dots`.
+    pub fn parse(input: &str) -> Result<Entry, String> {
+        let (rule, theme) = match input.trim().split_once('@') {
+            Some((rule, theme)) => (rule, Some(theme.trim())),
+            None => (input.trim(), None),
+        };
+        let rule = rule
+            .parse::<Rule>()
+            .map_err(|reason| format!("{input}: {reason}"))?;
+        if let Some(theme) = theme.filter(|theme| Theme::named(theme).is_none()) {
+            return Err(format!("{input}: unknown theme \"{theme}\""));
+        }
+        Ok(Entry {
+            rule,
+            theme: theme.map(String::from),
+        })
+    }
+}
+
+/// Keeps a board going unattended: a new soup whenever it dies out or stops changing, and the
+/// next rule of the playlist whenever its turn is up.
+#[derive(Debug)]
+pub struct Screensaver {
+    playlist: Vec<(Rule, Option<Theme>)>,
+    turn: Duration,
+    /// The entry playing, and when it started, once the playlist has started.
+    playing: Option<(usize, Instant)>,
+}
+
+impl Screensaver {
+    /// A screensaver taking turns of `turn` with each entry of `playlist`, if there is one. Its
+    /// themes must have cells as wide as `theme`'s, which the board was sized for.
+    pub fn new(playlist: &[Entry], turn: Duration, theme: &Theme) -> Result<Screensaver, String> {
+        let playlist = playlist
+            .iter()
+            .map(|entry| {
+                let Some(name) = &entry.theme else {
+                    return Ok((entry.rule.clone(), None));
+                };
+                let named =
+                    Theme::named(name).ok_or_else(|| format!("unknown theme \"{name}\""))?;
+                if named.cell_width() != theme.cell_width() {
+                    return Err(format!(
+                        "the {name} theme's cells are {} columns wide, but the board is sized \
+                         for cells {} wide",
+                        named.cell_width(),
+                        theme.cell_width()
+                    ));
+                }
+                Ok((entry.rule.clone(), Some(named)))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Screensaver {
+            playlist,
+            turn,
+            playing: None,
+        })
+    }
+
+    /// Keeps `model` running in zen mode, starting the next entry of the playlist if its turn
+    /// is up at `now` or a new soup if the board died out or repeats. Called after every step.
+    pub fn tend(&mut self, model: &mut Model, now: Instant) {
+        let due = self
+            .playing
+            .is_none_or(|(_, since)| now.duration_since(since) >= self.turn);
+        if !self.playlist.is_empty() && due {
+            let next = self
+                .playing
+                .map_or(0, |(index, _)| (index + 1) % self.playlist.len());
+            let (rule, theme) = &self.playlist[next];
+            info!(rule = rule.to_string(), "screensaver playing the next rule");
+            model.set_rule(rule.clone());
+            if let Some(theme) = theme {
+                model.set_theme(theme.clone());
+            }
+            model.update(Message::Randomize);
+            self.playing = Some((next, now));
+        } else if population(model) == 0 || model.stabilization().is_some() {
+            info!(generation = model.generation(), "screensaver reseeding");
+            model.update(Message::Randomize);
+        }
+
+        if *model.state() == State::Editing {
+            model.update(Message::ToggleEditing);
+        }
+        if !model.is_zen() {
+            model.update(Message::ToggleZen);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_entries() {
+        let entry = Entry::parse("B36/S23@dots").unwrap();
+        assert_eq!(entry.rule.to_string(), "B36/S23");
+        assert_eq!(entry.theme.as_deref(), Some("dots"));
+        assert_eq!(Entry::parse("B3/S23").unwrap().theme, None);
+        assert!(Entry::parse("B9/S23").is_err());
+        assert!(Entry::parse("B3/S23@plaid").is_err());
+
+        let classic = Theme::default();
+        let square = [Entry::parse("B3/S23@square").unwrap()];
+        assert!(Screensaver::new(&square, Duration::from_secs(60), &classic).is_err());
+    }
+
+    #[test]
+    fn cycles_and_reseeds() {
+        // an empty board gets a soup, under the rule it has without a playlist
+        let turn = Duration::from_secs(60);
+        let mut screensaver = Screensaver::new(&[], turn, &Theme::default()).unwrap();
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        assert_eq!(population(&model), 0);
+        screensaver.tend(&mut model, Instant::now());
+        assert!(population(&model) > 0);
+        assert_eq!(*model.state(), State::Running);
+        assert!(model.is_zen());
+
+        let playlist = [
+            Entry::parse("B36/S23@dots").unwrap(),
+            Entry::parse("B3/S23").unwrap(),
+        ];
+        let mut screensaver = Screensaver::new(&playlist, turn, &Theme::default()).unwrap();
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        let start = Instant::now();
+
+        screensaver.tend(&mut model, start);
+        assert_eq!(model.rulestring(), "B36/S23");
+        assert_eq!(model.theme(), &Theme::named("dots").unwrap());
+        screensaver.tend(&mut model, start + turn / 2);
+        assert_eq!(model.rulestring(), "B36/S23");
+        screensaver.tend(&mut model, start + turn);
+        assert_eq!(model.rulestring(), "B3/S23");
+        assert_eq!(model.theme(), &Theme::named("dots").unwrap());
+        screensaver.tend(&mut model, start + turn * 2);
+        assert_eq!(model.rulestring(), "B36/S23");
+    }
+}
@@ -0,0 +1,253 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use tracing::info;
+
+use crate::{
+    app::{Coords, Model, Screen, View},
+    dirs::data_dir,
+    pattern::{Metadata, Pattern},
+    theme::{parse_intensity, CellStyle, Palette, Shading, Theme},
+};
+
+/// How many quick-save slots there are, numbered from 1.
+pub const SLOTS: u8 = 9;
+
+/// The file backing quick-save slot `slot`: `slots/slot-N.rle` in the [`data_dir`].
+pub fn slot_path(slot: u8) -> Option<PathBuf> {
+    Some(data_dir()?.join("slots").join(format!("slot-{slot}.rle")))
+}
+
+/// Where the board is saved on exit with `--autosave-on-exit`: `autosave.rle` in the
+/// [`data_dir`].
+pub fn autosave_path() -> Option<PathBuf> {
+    Some(data_dir()?.join("autosave.rle"))
+}
+
+/// The board as a pattern named `name`, with the rule, generation and seed it was reached
+/// with, so it can be saved as an RLE file and loaded again like any other pattern. How the
+/// board was being viewed is kept in further comments; see [`view`].
+pub fn snapshot(model: &Model, name: &str) -> Pattern {
+    let cells = model
+        .cells()
+        .iter()
+        .enumerate()
+        .map(|(y, line)| {
+            (0..line.len())
+                .map(|x| model.is_alive(x, y).unwrap_or(false))
+                .collect()
+        })
+        .collect();
+
+    let mut comments = vec![format!("generation {}", model.generation())];
+    if let Some(seed) = model.rng().seed() {
+        comments.push(format!("seed {seed}"));
+    }
+    comments.extend(view_comments(&model.view()));
+    Pattern {
+        cells,
+        rule: Some(model.rulestring()),
+        metadata: Metadata {
+            name: Some(String::from(name)),
+            comments,
+            ..Metadata::default()
+        },
+    }
+}
+
+/// Writes a [`snapshot`] of the board to `path`, creating its directory if needed.
+pub fn save(model: &Model, name: &str, path: &Path) -> io::Result<()> {
+    info!(path = %path.display(), "saving the board");
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, snapshot(model, name).to_rle())
+}
+
+/// Reads a board written by [`save`].
+pub fn load(path: &Path) -> io::Result<Pattern> {
+    info!(path = %path.display(), "loading a saved board");
+    let rle = fs::read_to_string(path)?;
+    Pattern::from_rle(&rle).map_err(|reason| io::Error::new(io::ErrorKind::InvalidData, reason))
+}
+
+/// Puts a saved board back, looking at it the way it was looked at when saved.
+pub fn restore(model: &mut Model, pattern: &Pattern) {
+    model.restore(pattern, generation(pattern));
+    model.set_view(view(pattern, model.view()));
+}
+
+/// One comment per view setting: `glyph`, `palette`, `cell-style`, `shading` with its
+/// intensity, `zoom`, `viewport` and `cursor`, and the overlays that are on.
+fn view_comments(view: &View) -> Vec<String> {
+    let theme = &view.theme;
+    let mut comments = vec![
+        format!("glyph {}", theme.alive_glyph),
+        format!("palette {}", theme.palette.name()),
+        format!("cell-style {}", theme.cell_style.name()),
+        format!(
+            "shading {} {}",
+            theme.shading.name(),
+            theme.shading_intensity
+        ),
+        format!("zoom {}", view.zoom),
+        format!("viewport {} {}", view.viewport.x, view.viewport.y),
+        format!("cursor {} {}", view.cursor.x, view.cursor.y),
+    ];
+    if view.ghost {
+        comments.push(String::from("ghosts"));
+    }
+    if view.inset {
+        comments.push(String::from("inset"));
+    }
+    if let Some(pin) = view.inset_pin {
+        comments.push(format!("inset-pin {} {}", pin.x, pin.y));
+    }
+    if view.screen == Screen::Stats {
+        comments.push(String::from("stats"));
+    }
+    if view.zen {
+        comments.push(String::from("zen"));
+    }
+    comments
+}
+
+/// The view a saved board was looked at with. Settings it doesn't mention, as in files
+/// saved before views were, are taken from `current`; overlays it doesn't mention are off.
+pub fn view(pattern: &Pattern, current: View) -> View {
+    let comments = &pattern.metadata.comments;
+    if !comments.iter().any(|comment| comment.starts_with("zoom ")) {
+        return current;
+    }
+    let mut view = View {
+        ghost: false,
+        inset: false,
+        inset_pin: None,
+        screen: Screen::Board,
+        zen: false,
+        ..current
+    };
+    let coords = |value: &str| {
+        let (x, y) = value.split_once(' ')?;
+        Some(Coords {
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+        })
+    };
+    for comment in comments {
+        let (key, value) = comment.split_once(' ').unwrap_or((comment, ""));
+        match key {
+            "glyph" => {
+                if let Ok(custom) = Theme::with_glyph(value) {
+                    view.theme.alive_glyph = custom.alive_glyph;
+                    view.theme.dead_glyph = custom.dead_glyph;
+                }
+            }
+            "palette" => view.theme.palette = Palette::parse(value).unwrap_or_default(),
+            "cell-style" => view.theme.cell_style = CellStyle::parse(value).unwrap_or_default(),
+            "shading" => {
+                let (shading, intensity) = value.split_once(' ').unwrap_or((value, ""));
+                view.theme.shading = Shading::parse(shading).unwrap_or_default();
+                if let Ok(intensity) = parse_intensity(intensity) {
+                    view.theme.shading_intensity = intensity;
+                }
+            }
+            "zoom" => view.zoom = value.parse().unwrap_or(view.zoom),
+            "viewport" => view.viewport = coords(value).unwrap_or(view.viewport),
+            "cursor" => view.cursor = coords(value).unwrap_or(view.cursor),
+            "ghosts" => view.ghost = true,
+            "inset" => view.inset = true,
+            "inset-pin" => view.inset_pin = coords(value),
+            "stats" => view.screen = Screen::Stats,
+            "zen" => view.zen = true,
+            _ => {}
+        }
+    }
+    view
+}
+
+/// The generation a saved board had reached, from its comments or, for a board saved by
+/// Golly, its `#CXRLE` line, or 0 if it doesn't say.
+pub fn generation(pattern: &Pattern) -> u64 {
+    pattern
+        .metadata
+        .comments
+        .iter()
+        .find_map(|comment| comment.strip_prefix("generation ")?.parse().ok())
+        .or(pattern.metadata.generation)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, time::Duration};
+
+    use super::*;
+    use crate::{
+        app::{Direction, Message, Preset},
+        rng::RngSource,
+    };
+
+    #[test]
+    fn save_and_reload() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_rng(RngSource::seeded(42));
+        model.load_preset(Preset::Glider);
+
+        let path = env::temp_dir()
+            .join(format!("tui-ca-session-{}", std::process::id()))
+            .join("autosave.rle");
+        save(&model, "autosave", &path).unwrap();
+        let pattern = load(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(pattern.size(), (10, 10));
+        assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(pattern.metadata.comments[..2], ["generation 0", "seed 42"]);
+        let glider = Preset::Glider.pattern().unwrap();
+        assert_eq!(pattern.cells[2][..3], glider.cells[2][..]);
+
+        model.update(Message::ToggleEditing);
+        for _ in 0..3 {
+            model.update(Message::Idle);
+        }
+        let pattern = snapshot(&model, "slot 1");
+        assert_eq!(generation(&pattern), 3);
+
+        let mut restored = Model::new(9, 9, vec![], vec![], Duration::from_millis(50));
+        restored.restore(&pattern, generation(&pattern));
+        assert_eq!(restored.rulestring(), "B3/S23");
+        assert_eq!(restored.generation(), 3);
+        assert_eq!(snapshot(&restored, "slot 1").cells, pattern.cells);
+    }
+
+    #[test]
+    fn view_round_trips() {
+        let mut model = Model::new(19, 19, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_theme(Theme::named("emoji").unwrap());
+        model.update(Message::Zoom(1));
+        model.update(Message::Move(Direction::Right, 6));
+        model.update(Message::ToggleGhost);
+        model.update(Message::ToggleInset);
+        model.update(Message::ToggleInsetPin);
+        model.update(Message::SwitchScreen);
+        let path = env::temp_dir()
+            .join(format!("tui-ca-view-{}", std::process::id()))
+            .join("slot-1.rle");
+        save(&model, "slot 1", &path).unwrap();
+        let pattern = load(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        let mut restored = Model::new(19, 19, vec![3], vec![2, 3], Duration::from_millis(50));
+        restore(&mut restored, &pattern);
+        assert_eq!(restored.view(), model.view());
+
+        // boards saved before views were keep the current view
+        let mut old = pattern.clone();
+        old.metadata.comments.truncate(1);
+        let current = Model::new(19, 19, vec![], vec![], Duration::from_millis(50)).view();
+        assert_eq!(view(&old, current.clone()), current);
+    }
+}
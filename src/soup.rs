@@ -0,0 +1,151 @@
+use rand::Rng;
+
+/// A map on coordinates measured from the board's center.
+type Map = fn(i32, i32) -> (i32, i32);
+
+/// A symmetry enforced on random soups, named as on Catagolue.
+///
+/// Symmetries act about the center of the board. On a board that isn't square, rotations and
+/// diagonal reflections only pair up the cells whose images land on the board; the rest stay
+/// independent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Unchanged by a half turn.
+    C2,
+    /// Unchanged by a quarter turn.
+    C4,
+    /// Mirrored left to right.
+    D2,
+    /// Unchanged by every rotation and reflection of the square.
+    D8,
+}
+
+impl Symmetry {
+    pub fn parse(input: &str) -> Result<Symmetry, String> {
+        match input.trim().to_ascii_uppercase().as_str() {
+            "NONE" | "C1" => Ok(Symmetry::None),
+            "C2" => Ok(Symmetry::C2),
+            "C4" => Ok(Symmetry::C4),
+            "D2" => Ok(Symmetry::D2),
+            "D8" => Ok(Symmetry::D8),
+            _ => Err(format!(
+                "unknown symmetry \"{input}\", expected one of none, C2, C4, D2 or D8"
+            )),
+        }
+    }
+
+    /// The maps making up the symmetry group.
+    fn maps(&self) -> &'static [Map] {
+        match self {
+            Symmetry::None => &[|u, v| (u, v)],
+            Symmetry::C2 => &[|u, v| (u, v), |u, v| (-u, -v)],
+            Symmetry::C4 => &[
+                |u, v| (u, v),
+                |u, v| (-v, u),
+                |u, v| (-u, -v),
+                |u, v| (v, -u),
+            ],
+            Symmetry::D2 => &[|u, v| (u, v), |u, v| (-u, v)],
+            Symmetry::D8 => &[
+                |u, v| (u, v),
+                |u, v| (-v, u),
+                |u, v| (-u, -v),
+                |u, v| (v, -u),
+                |u, v| (-u, v),
+                |u, v| (u, -v),
+                |u, v| (v, u),
+                |u, v| (-v, -u),
+            ],
+        }
+    }
+}
+
+/// A `width` by `height` soup in which each cell is alive with probability `density`, made
+/// symmetric by copying each cell's state from the first cell of its orbit.
+pub fn soup(
+    rng: &mut impl Rng,
+    width: usize,
+    height: usize,
+    density: f64,
+    symmetry: Symmetry,
+) -> Vec<Vec<bool>> {
+    let noise: Vec<Vec<bool>> = (0..height)
+        .map(|_| (0..width).map(|_| rng.gen_bool(density)).collect())
+        .collect();
+    if symmetry == Symmetry::None {
+        return noise;
+    }
+
+    // doubled coordinates keep the center on the grid for boards of either parity
+    let (w, h) = (width as i32, height as i32);
+    let to_board = |(u, v): (i32, i32)| {
+        let (x, y) = (u + w - 1, v + h - 1);
+        let on_board =
+            x % 2 == 0 && y % 2 == 0 && (0..2 * w).contains(&x) && (0..2 * h).contains(&y);
+        on_board.then_some(((y / 2) as usize, (x / 2) as usize))
+    };
+
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let (u, v) = (2 * x as i32 - (w - 1), 2 * y as i32 - (h - 1));
+                    let (y, x) = symmetry
+                        .maps()
+                        .iter()
+                        .filter_map(|map| to_board(map(u, v)))
+                        .min()
+                        .expect("the identity keeps every cell on the board");
+                    noise[y][x]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn transpose(cells: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        (0..cells[0].len())
+            .map(|x| cells.iter().map(|line| line[x]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn symmetric_soups() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+
+        let cells = soup(&mut rng, 9, 6, 0.5, Symmetry::D2);
+        for line in &cells {
+            assert!(line.iter().eq(line.iter().rev()));
+        }
+
+        let cells = soup(&mut rng, 8, 5, 0.5, Symmetry::C2);
+        let mut turned = cells.clone();
+        turned.reverse();
+        turned.iter_mut().for_each(|line| line.reverse());
+        assert_eq!(cells, turned);
+
+        let cells = soup(&mut rng, 7, 7, 0.5, Symmetry::D8);
+        assert_eq!(cells, transpose(&cells));
+        for line in &cells {
+            assert!(line.iter().eq(line.iter().rev()));
+        }
+        assert!(cells.iter().flatten().any(|alive| *alive));
+        assert!(cells.iter().flatten().any(|alive| !*alive));
+    }
+
+    #[test]
+    fn parse_symmetry() {
+        assert_eq!(Symmetry::parse("d8"), Ok(Symmetry::D8));
+        assert_eq!(Symmetry::parse("none"), Ok(Symmetry::None));
+        assert!(Symmetry::parse("D4").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+/// Boards remembered before the detector gives up, so a long chaotic run doesn't grow without
+/// bound.
+const MAX_TRACKED: usize = 1 << 20;
+
+/// The point at which a board started repeating itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stabilization {
+    /// Generations from the start of tracking until the first board of the cycle.
+    pub generation: u64,
+    /// Length of the cycle; 1 for a still life or an empty board.
+    pub period: u64,
+}
+
+/// Detects when the board becomes periodic by remembering a hash of every board since the last
+/// [`reset`](StabilityDetector::reset).
+#[derive(Debug, Default)]
+pub struct StabilityDetector {
+    start: u64,
+    seen: HashMap<u64, u64>,
+    stabilization: Option<Stabilization>,
+}
+
+impl StabilityDetector {
+    /// Forgets every board seen so far and starts counting from `generation`.
+    pub fn reset(&mut self, generation: u64) {
+        self.start = generation;
+        self.seen.clear();
+        self.stabilization = None;
+    }
+
+    /// Whether the detector still needs to see boards.
+    pub fn is_tracking(&self) -> bool {
+        self.stabilization.is_none() && self.seen.len() < MAX_TRACKED
+    }
+
+    /// Records the board at `generation`, identified by `hash`.
+    pub fn observe(&mut self, generation: u64, hash: u64) {
+        if !self.is_tracking() {
+            return;
+        }
+
+        let generation = generation - self.start;
+        match self.seen.get(&hash) {
+            Some(first) => {
+                self.stabilization = Some(Stabilization {
+                    generation: *first,
+                    period: generation - first,
+                });
+                self.seen.clear();
+            }
+            None => {
+                self.seen.insert(hash, generation);
+            }
+        }
+    }
+
+    pub fn stabilization(&self) -> Option<Stabilization> {
+        self.stabilization
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_cycles() {
+        let mut detector = StabilityDetector::default();
+        detector.reset(10);
+        for (generation, hash) in [(10, 1), (11, 2), (12, 3), (13, 4), (14, 3)] {
+            detector.observe(generation, hash);
+        }
+        assert_eq!(
+            detector.stabilization(),
+            Some(Stabilization {
+                generation: 2,
+                period: 2
+            })
+        );
+        assert!(!detector.is_tracking());
+
+        detector.reset(20);
+        detector.observe(20, 3);
+        assert_eq!(detector.stabilization(), None);
+    }
+}
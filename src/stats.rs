@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+
+/// Generations of history kept for the statistics screen.
+const HISTORY: usize = 1000;
+
+/// What happened on the board in one generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    /// The generation the board reached.
+    pub generation: u64,
+    pub population: usize,
+    pub births: usize,
+    pub deaths: usize,
+}
+
+/// A rolling history of [`Sample`]s, recorded as the model steps.
+#[derive(Debug, Default)]
+pub struct Stats {
+    samples: VecDeque<Sample>,
+    /// Samples recorded since the app started, including those dropped or cleared since.
+    recorded: u64,
+}
+
+impl Stats {
+    pub fn record(&mut self, sample: Sample) {
+        if self.samples.len() == HISTORY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+        self.recorded += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Recorded samples, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &Sample> {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<&Sample> {
+        self.samples.back()
+    }
+
+    /// How many samples have ever been recorded, which only goes up, so a reader can tell
+    /// which of the [`samples`](Stats::samples) are new since it last looked.
+    pub fn recorded(&self) -> u64 {
+        self.recorded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_recent_history() {
+        let mut stats = Stats::default();
+        for generation in 0..HISTORY as u64 + 5 {
+            stats.record(Sample {
+                generation,
+                population: 1,
+                births: 0,
+                deaths: 0,
+            });
+        }
+        assert_eq!(stats.samples().count(), HISTORY);
+        assert_eq!(stats.samples().next().unwrap().generation, 5);
+        assert_eq!(stats.latest().unwrap().generation, HISTORY as u64 + 4);
+    }
+}
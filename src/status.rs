@@ -0,0 +1,309 @@
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use tracing::{debug, info, warn};
+
+use crate::{
+    app::{Model, State},
+    headless::population,
+    wallpaper::{self, Resolution},
+};
+
+/// Pixels across and down each cell of `/frame.png`.
+const FRAME_CELL_SIZE: u16 = 4;
+
+/// The shortest time between copies of the board for the server, so a fast board isn't slowed
+/// down by them.
+const PUBLISH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long the server waits for a client to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long the server waits for a client to take its answer.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What the server answers with, as of the last time the board was published. The picture
+/// for `/frame.png` is only drawn from the cells' colors when it's asked for.
+#[derive(Debug, Default)]
+struct Published {
+    state: String,
+    stats: String,
+    /// Cells across the board.
+    columns: usize,
+    /// The [`wallpaper::colors`] of the board's cells.
+    colors: Vec<(u8, u8, u8)>,
+    at: Option<Instant>,
+}
+
+static PUBLISHED: OnceLock<Mutex<Published>> = OnceLock::new();
+
+/// Serves the board read-only over HTTP on `addr` from a thread of its own, from now on:
+/// `/state` sums it up as JSON, `/stats` gives the recent generations' populations, births
+/// and deaths as JSON, and `/frame.png` is a picture of it.
+pub fn serve(addr: SocketAddr) -> io::Result<SocketAddr> {
+    let _ = PUBLISHED.set(Mutex::new(Published::default()));
+    let addr = listen(addr, route)?;
+    info!(%addr, "serving the board's status");
+    Ok(addr)
+}
+
+/// An answer to a request.
+pub struct Response {
+    pub status: &'static str,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn ok(content_type: &'static str, body: Vec<u8>) -> Response {
+        Response {
+            status: "200 OK",
+            content_type,
+            body,
+        }
+    }
+
+    pub fn text(status: &'static str, text: &str) -> Response {
+        Response {
+            status,
+            content_type: "text/plain",
+            body: format!("{text}\n").into_bytes(),
+        }
+    }
+}
+
+/// Answers GET and HEAD requests on `addr` with what `route` gives for their path, from a
+/// thread of its own, returning the address it's listening on. Each request is answered on
+/// a thread of its own too, so a slow client doesn't hold up the others.
+pub fn listen(addr: SocketAddr, route: fn(&str) -> Response) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let addr = listener.local_addr()?;
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    debug!(%error, "couldn't accept an HTTP connection");
+                    continue;
+                }
+            };
+            thread::spawn(move || {
+                let result = stream
+                    .set_read_timeout(Some(READ_TIMEOUT))
+                    .and_then(|_| stream.set_write_timeout(Some(WRITE_TIMEOUT)))
+                    .and_then(|_| respond(stream, route));
+                if let Err(error) = result {
+                    debug!(%error, "HTTP request failed");
+                }
+            });
+        }
+    });
+    Ok(addr)
+}
+
+/// Copies the board for the server to answer with, if it's serving and hasn't had a copy in
+/// the last [`PUBLISH_INTERVAL`]. Called before every frame.
+pub fn publish(model: &Model) {
+    let Some(published) = PUBLISHED.get() else {
+        return;
+    };
+    let Ok(mut published) = published.lock() else {
+        return;
+    };
+    if published
+        .at
+        .is_some_and(|at| at.elapsed() < PUBLISH_INTERVAL)
+    {
+        return;
+    }
+    *published = Published {
+        state: state_json(model),
+        stats: stats_json(model),
+        columns: model.size().0,
+        colors: wallpaper::colors(model),
+        at: Some(Instant::now()),
+    };
+}
+
+/// The board summed up as a JSON object.
+fn state_json(model: &Model) -> String {
+    let (width, height) = model.size();
+    let state = match model.state() {
+        State::Editing => "editing",
+        State::Running => "running",
+        State::Prompting { .. } | State::Picking { .. } => "paused",
+        State::FastForwarding { .. } => "fast-forwarding",
+        State::Done => "done",
+    };
+    let seed = model
+        .rng()
+        .seed()
+        .map_or(String::from("null"), |seed| seed.to_string());
+    format!(
+        "{{\"generation\":{},\"population\":{},\"rule\":{},\"state\":\"{state}\",\
+         \"width\":{width},\"height\":{height},\"tickrate_ms\":{},\"seed\":{seed}}}",
+        model.generation(),
+        population(model),
+        json_string(&model.rulestring()),
+        model.tickrate().as_secs_f64() * 1000.0,
+    )
+}
+
+/// The recent generations as a JSON object with a `samples` array, oldest first.
+fn stats_json(model: &Model) -> String {
+    let mut json = String::from("{\"samples\":[");
+    for (index, sample) in model.stats().samples().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        let _ = write!(
+            json,
+            "{{\"generation\":{},\"population\":{},\"births\":{},\"deaths\":{}}}",
+            sample.generation, sample.population, sample.births, sample.deaths
+        );
+    }
+    json.push_str("]}");
+    json
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            ch if ch.is_control() => {
+                let _ = write!(json, "\\u{:04x}", ch as u32);
+            }
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Answers the request on `stream`.
+fn respond(mut stream: TcpStream, route: fn(&str) -> Response) -> io::Result<()> {
+    let mut request = String::new();
+    BufReader::new(&stream).read_line(&mut request)?;
+    let mut parts = request.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    // ignore any query string
+    let path = path.split('?').next().unwrap_or(path);
+    debug!(method, path, "HTTP request");
+
+    let response = match method {
+        "GET" | "HEAD" => route(path),
+        _ => Response::text("405 Method Not Allowed", "the server is read-only"),
+    };
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(header.as_bytes())?;
+    if method != "HEAD" {
+        stream.write_all(&response.body)?;
+    }
+    stream
+        .flush()
+        .inspect_err(|error| warn!(%error, "couldn't answer an HTTP request"))
+}
+
+/// What the status server answers with for `path`.
+fn route(path: &str) -> Response {
+    let Some(published) = PUBLISHED.get().and_then(|published| published.lock().ok()) else {
+        return Response::text("503 Service Unavailable", "the status isn't being served");
+    };
+    match path {
+        "/state" => Response::ok("application/json", published.state.clone().into_bytes()),
+        "/stats" => Response::ok("application/json", published.stats.clone().into_bytes()),
+        "/frame.png" if published.colors.is_empty() => Response::text(
+            "503 Service Unavailable",
+            "the board hasn't been published yet",
+        ),
+        "/frame.png" => {
+            let (columns, colors) = (published.columns, published.colors.clone());
+            // draw without holding the lock, so the board isn't held up
+            drop(published);
+            let cell_size = u32::from(FRAME_CELL_SIZE);
+            let frame = Resolution {
+                width: columns as u32 * cell_size,
+                height: (colors.len() / columns) as u32 * cell_size,
+            };
+            let pixels = wallpaper::pixels(&colors, columns, frame, FRAME_CELL_SIZE);
+            Response::ok(
+                "image/png",
+                wallpaper::encode(frame.width, frame.height, &pixels),
+            )
+        }
+        _ => Response::text("404 Not Found", "try /state, /stats or /frame.png"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::Ipv4Addr, time::Duration};
+
+    use super::*;
+    use crate::app::Preset;
+
+    fn get(addr: SocketAddr, path: &str) -> Vec<u8> {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = vec![];
+        stream.read_to_end(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn answers_around_a_silent_client() {
+        let addr = listen((Ipv4Addr::LOCALHOST, 0).into(), |path| {
+            Response::text("200 OK", path)
+        })
+        .unwrap();
+        // connects and never sends its request
+        let _silent = TcpStream::connect(addr).unwrap();
+        let start = Instant::now();
+        let response = get(addr, "/state");
+        assert!(response.starts_with(b"HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(b"\r\n\r\n/state\n"));
+        assert!(start.elapsed() < READ_TIMEOUT);
+    }
+
+    #[test]
+    fn draws_frames_when_asked() {
+        let _ = PUBLISHED.set(Mutex::new(Published::default()));
+        let mut model = Model::new(2, 4, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Glider);
+        publish(&model);
+        let published = PUBLISHED.get().unwrap().lock().unwrap();
+        assert_eq!(published.columns, 5);
+        assert_eq!(published.colors.len(), 5 * 3);
+        drop(published);
+
+        let response = route("/frame.png");
+        assert_eq!(response.content_type, "image/png");
+        // the IHDR chunk's width and height, in pixels
+        assert_eq!(response.body[16..24], [0, 0, 0, 20, 0, 0, 0, 12]);
+    }
+
+    #[test]
+    fn summarizes_the_board() {
+        let mut model = Model::new(9, 9, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Glider);
+        let state = state_json(&model);
+        assert!(state.starts_with("{\"generation\":0,\"population\":5,\"rule\":\"B3/S23\""));
+        assert!(state.contains("\"state\":\"editing\",\"width\":10,\"height\":10"));
+        assert_eq!(stats_json(&model), "{\"samples\":[]}");
+        assert_eq!(json_string("a \"b\"\n"), "\"a \\\"b\\\"\\u000a\"");
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    fmt::Write as _,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    app::{Model, Preset, Rule},
+    headless::{self, Outcome},
+    rng::RngSource,
+    rulebook,
+};
+
+/// The most rules one sweep may enumerate, to catch templates that would run for days.
+const MAX_RULES: usize = 4096;
+
+/// The CSV header, naming the columns of [`Summary::csv`].
+pub const CSV_HEADER: &str = "rule,name,seeds,extinct,stabilized,still_changing,\
+                              mean_generations,mean_period,mean_growth,mean_density";
+
+/// Expands rule templates into the rules they stand for, each once, in order. A template is
+/// a rulestring where digits in square brackets may each be there or not, so
+/// `B3[678]/S23` stands for the 8 rules from `B3/S23` to `B3678/S23`.
+pub fn expand(templates: &[String]) -> Result<Vec<Rule>, String> {
+    let mut rules: Vec<Rule> = vec![];
+    for template in templates {
+        for rulestring in expand_template(template)? {
+            let rule = rulestring
+                .parse::<Rule>()
+                .map_err(|reason| format!("{template}: {reason}"))?;
+            if rule.history || rule.second_order {
+                return Err(format!(
+                    "{template}: sweeps only cover plain rules, without History or Reversible"
+                ));
+            }
+            if !rules.contains(&rule) {
+                rules.push(rule);
+            }
+            if rules.len() > MAX_RULES {
+                return Err(format!("the sweep covers more than {MAX_RULES} rules"));
+            }
+        }
+    }
+    Ok(rules)
+}
+
+fn expand_template(template: &str) -> Result<Vec<String>, String> {
+    let mut expansions = vec![String::new()];
+    let mut rest = template.trim();
+    while let Some(open) = rest.find('[') {
+        let close = rest[open..]
+            .find(']')
+            .map(|close| open + close)
+            .ok_or_else(|| format!("{template}: a [ isn't closed"))?;
+        let (fixed, optional) = (&rest[..open], &rest[open + 1..close]);
+        if let Some(ch) = optional.chars().find(|ch| !ch.is_ascii_digit()) {
+            return Err(format!(
+                "{template}: only digits go in brackets, got '{ch}'"
+            ));
+        }
+        let mut next = vec![];
+        for expansion in &expansions {
+            for subset in 0..1usize << optional.len() {
+                if next.len() + expansions.len() > MAX_RULES * 2 {
+                    return Err(format!("the sweep covers more than {MAX_RULES} rules"));
+                }
+                let mut expanded = format!("{expansion}{fixed}");
+                for (index, digit) in optional.chars().enumerate() {
+                    if subset & (1 << index) != 0 {
+                        expanded.push(digit);
+                    }
+                }
+                next.push(expanded);
+            }
+        }
+        expansions = next;
+        rest = &rest[close + 1..];
+    }
+    if rest.contains(']') {
+        return Err(format!("{template}: a ] isn't opened"));
+    }
+    Ok(expansions
+        .into_iter()
+        .map(|expansion| expansion + rest)
+        .collect())
+}
+
+/// What each rule of a sweep is run on.
+#[derive(Debug, Clone, Copy)]
+pub struct Sweep {
+    /// Soups per rule, seeded `seed`, `seed + 1` and so on, so every rule gets the same ones.
+    pub seeds: u64,
+    pub seed: u64,
+    /// Generations each soup runs for at most.
+    pub generations: u64,
+    pub width: u16,
+    pub height: u16,
+    /// Fraction of each soup alive, or the rule's suggested density.
+    pub density: Option<f64>,
+    /// Threads running soups at once.
+    pub jobs: NonZeroUsize,
+}
+
+/// How a rule's soups went, averaged over them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub rule: Rule,
+    pub seeds: u64,
+    pub extinct: u64,
+    pub stabilized: u64,
+    pub still_changing: u64,
+    /// Generations before each soup died out, became periodic or was stopped.
+    pub mean_generations: f64,
+    /// The period of the soups that became periodic, 0 if none did.
+    pub mean_period: f64,
+    /// How many times over each soup's population grew.
+    pub mean_growth: f64,
+    /// The fraction of the board alive at the end.
+    pub mean_density: f64,
+}
+
+impl Summary {
+    /// The summary as a CSV row, in the order of [`CSV_HEADER`].
+    pub fn csv(&self) -> String {
+        let name = rulebook::lookup(&self.rule).map_or("", |known| known.name);
+        let mut row = format!("{},{},", self.rule, csv_field(name));
+        let _ = write!(
+            row,
+            "{},{},{},{},{:.1},{:.2},{:.3},{:.4}",
+            self.seeds,
+            self.extinct,
+            self.stabilized,
+            self.still_changing,
+            self.mean_generations,
+            self.mean_period,
+            self.mean_growth,
+            self.mean_density
+        );
+        row
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => String::from(field),
+    }
+}
+
+/// Runs every rule on the same soups, a rule per thread at a time, returning their summaries
+/// in the order of `rules`. `done` is called with the number of rules finished as each one
+/// finishes.
+pub fn run(rules: &[Rule], sweep: &Sweep, done: impl Fn(usize) + Sync) -> Vec<Summary> {
+    let next = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    let summaries = Mutex::new(vec![None; rules.len()]);
+    thread::scope(|scope| {
+        for _ in 0..sweep.jobs.get().min(rules.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(rule) = rules.get(index) else {
+                    break;
+                };
+                let summary = summarize(rule, sweep);
+                summaries.lock().expect("no sweep thread panics")[index] = Some(summary);
+                done(finished.fetch_add(1, Ordering::Relaxed) + 1);
+            });
+        }
+    });
+    summaries
+        .into_inner()
+        .expect("no sweep thread panics")
+        .into_iter()
+        .map(|summary| summary.expect("every rule is run"))
+        .collect()
+}
+
+fn summarize(rule: &Rule, sweep: &Sweep) -> Summary {
+    let mut summary = Summary {
+        rule: rule.clone(),
+        seeds: sweep.seeds,
+        extinct: 0,
+        stabilized: 0,
+        still_changing: 0,
+        mean_generations: 0.0,
+        mean_period: 0.0,
+        mean_growth: 0.0,
+        mean_density: 0.0,
+    };
+    let area = f64::from(sweep.width) * f64::from(sweep.height);
+    for offset in 0..sweep.seeds {
+        let mut model = Model::new(
+            sweep.height as i16 - 1,
+            sweep.width as i16 - 1,
+            rule.birth_list.clone(),
+            rule.survival_list.clone(),
+            Duration::ZERO,
+        );
+        model.set_rng(RngSource::seeded(sweep.seed.wrapping_add(offset)));
+        model.set_density(sweep.density);
+        model.load_preset(Preset::Random);
+        let start = headless::population(&model).max(1);
+        let generations = match headless::run(&mut model, sweep.generations) {
+            Outcome::Extinct(generation) => {
+                summary.extinct += 1;
+                generation
+            }
+            Outcome::Stabilized(stabilization) => {
+                summary.stabilized += 1;
+                summary.mean_period += stabilization.period as f64;
+                stabilization.generation
+            }
+            Outcome::MaxGenerations(generation) => {
+                summary.still_changing += 1;
+                generation
+            }
+        };
+        let population = headless::population(&model) as f64;
+        summary.mean_generations += generations as f64;
+        summary.mean_growth += population / start as f64;
+        summary.mean_density += population / area;
+    }
+    let seeds = sweep.seeds.max(1) as f64;
+    summary.mean_generations /= seeds;
+    summary.mean_growth /= seeds;
+    summary.mean_density /= seeds;
+    summary.mean_period /= summary.stabilized.max(1) as f64;
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_templates() {
+        let rulestrings = |templates: &[&str]| {
+            let templates: Vec<String> = templates.iter().map(|t| String::from(*t)).collect();
+            expand(&templates).map(|rules| rules.iter().map(Rule::to_string).collect::<Vec<_>>())
+        };
+        assert_eq!(
+            rulestrings(&["B3[6]/S2[3]"]).unwrap(),
+            ["B3/S2", "B3/S23", "B36/S2", "B36/S23"]
+        );
+        assert_eq!(rulestrings(&["B3/S23", "B3/S23"]).unwrap(), ["B3/S23"]);
+        assert_eq!(rulestrings(&["B3[5678]/S23"]).unwrap().len(), 16);
+        assert!(rulestrings(&["B[12345678]/S[012345678]"]).is_err());
+        assert!(rulestrings(&["B3[6/S23"]).is_err());
+        assert!(rulestrings(&["B3[x]/S23"]).is_err());
+        assert!(rulestrings(&["B3/S23History"]).is_err());
+    }
+
+    #[test]
+    fn sweeps_rules() {
+        let rules = expand(&[String::from("B3/S23"), String::from("B2/S")]).unwrap();
+        let sweep = Sweep {
+            seeds: 3,
+            seed: 7,
+            generations: 40,
+            width: 16,
+            height: 16,
+            density: None,
+            jobs: NonZeroUsize::new(2).unwrap(),
+        };
+        let done = AtomicUsize::new(0);
+        let summaries = run(&rules, &sweep, |_| {
+            done.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(done.into_inner(), 2);
+        assert_eq!(summaries.len(), 2);
+        for summary in &summaries {
+            assert_eq!(
+                summary.extinct + summary.stabilized + summary.still_changing,
+                3
+            );
+        }
+        assert_eq!(summaries[0].rule.to_string(), "B3/S23");
+        assert!(summaries[0].csv().starts_with("B3/S23,Life,3,"));
+        assert_eq!(
+            summaries[0].csv().split(',').count(),
+            CSV_HEADER.split(',').count()
+        );
+        assert_eq!(run(&rules, &sweep, |_| {}), summaries);
+    }
+}
@@ -0,0 +1,189 @@
+use std::{fmt, fs, path::Path};
+
+/// The neighbors a table line lists, in order, as row and column offsets.
+const NEIGHBORS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// An automaton given as an explicit transition table, for rules no rulestring can express,
+/// such as ones that tell neighbors apart by direction.
+///
+/// Tables are written as text, one transition per line: the cell's state, its eight neighbors
+/// in the order NW N NE W E SW S SE, and the state it turns into, with `*` matching either
+/// state. The first matching line wins and cells no line matches keep their state. An
+/// optional `name` line names the table, and `#` starts a comment:
+///
+/// ```text
+/// name DiagonalLife
+/// # born with a live neighbor to the NW and SE and nothing else
+/// 0 10000001 1
+/// # live cells with a live N neighbor survive, the rest die
+/// 1 *1****** 1
+/// 1 ******** 0
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransitionTable {
+    name: Option<String>,
+    transitions: usize,
+    /// The next state of a cell, indexed by its state then its neighbors as a bit mask with
+    /// NW as the most significant bit.
+    next: [[bool; 256]; 2],
+}
+
+impl TransitionTable {
+    pub fn load(path: &Path) -> Result<TransitionTable, String> {
+        let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        TransitionTable::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<TransitionTable, String> {
+        let mut next = [[false; 256], [true; 256]];
+        let mut matched = [[false; 256]; 2];
+        let (mut name, mut transitions) = (None, 0);
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let (state, neighbors, to) = match words[..] {
+                [] => continue,
+                ["name", table_name] => {
+                    name = Some(table_name.to_string());
+                    continue;
+                }
+                [state, neighbors, to] => (state, neighbors, to),
+                _ => {
+                    return Err(format!(
+                        "line {}: expected STATE NEIGHBORS NEXT, got \"{line}\"",
+                        number + 1
+                    ))
+                }
+            };
+
+            let states = parse_pattern(state, 1)
+                .ok_or_else(|| format!("line {}: \"{state}\" is not a state", number + 1))?;
+            let masks = parse_pattern(neighbors, 8).ok_or_else(|| {
+                format!(
+                    "line {}: \"{neighbors}\" is not eight neighbor states",
+                    number + 1
+                )
+            })?;
+            let to = match to {
+                "0" => false,
+                "1" => true,
+                _ => {
+                    return Err(format!(
+                        "line {}: \"{to}\" is not a state to turn into",
+                        number + 1
+                    ))
+                }
+            };
+            for state in states {
+                for &mask in &masks {
+                    if !matched[state][mask] {
+                        matched[state][mask] = true;
+                        next[state][mask] = to;
+                    }
+                }
+            }
+            transitions += 1;
+        }
+
+        if transitions == 0 {
+            return Err(String::from("the table has no transitions"));
+        }
+        if next[0][0] {
+            return Err(String::from(
+                "a birth with no live neighbors would fill the empty background",
+            ));
+        }
+        Ok(TransitionTable {
+            name,
+            transitions,
+            next,
+        })
+    }
+
+    /// Whether the cell at `y`, `x` of `board` is alive in the next generation. Cells off the
+    /// board count as dead.
+    pub fn verdict(&self, board: &[Vec<bool>], y: usize, x: usize) -> bool {
+        let mut mask = 0;
+        for (dy, dx) in NEIGHBORS {
+            let alive = y
+                .checked_add_signed(dy)
+                .zip(x.checked_add_signed(dx))
+                .and_then(|(ny, nx)| board.get(ny)?.get(nx))
+                .copied()
+                .unwrap_or(false);
+            mask = mask << 1 | usize::from(alive);
+        }
+        self.next[usize::from(board[y][x])][mask]
+    }
+}
+
+/// Every value a pattern of `len` `0`, `1` and `*` characters matches, read as binary.
+fn parse_pattern(pattern: &str, len: usize) -> Option<Vec<usize>> {
+    if pattern.len() != len {
+        return None;
+    }
+    let mut values = vec![0];
+    for ch in pattern.chars() {
+        let bits: &[usize] = match ch {
+            '0' => &[0],
+            '1' => &[1],
+            '*' => &[0, 1],
+            _ => return None,
+        };
+        values = values
+            .iter()
+            .flat_map(|value| bits.iter().map(move |bit| value << 1 | bit))
+            .collect();
+    }
+    Some(values)
+}
+
+impl fmt::Display for TransitionTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "Table {name} ({} transitions)", self.transitions),
+            None => write!(f, "Table ({} transitions)", self.transitions),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_step() {
+        let table = TransitionTable::parse(
+            "name DiagonalLife\n0 10000001 1 # NW and SE\n1 *1****** 1\n1 ******** 0\n",
+        )
+        .unwrap();
+        assert_eq!(table.to_string(), "Table DiagonalLife (3 transitions)");
+
+        let board = vec![
+            vec![true, false, false, true],
+            vec![false, false, false, true],
+            vec![false, false, true, false],
+        ];
+        assert!(table.verdict(&board, 1, 1));
+        // lines are tried in order, so a live N neighbor keeps (1, 3) alive and (0, 3) dies
+        assert!(table.verdict(&board, 1, 3));
+        assert!(!table.verdict(&board, 0, 3));
+        // unmatched dead cells stay dead
+        assert!(!table.verdict(&board, 2, 1));
+
+        assert!(TransitionTable::parse("0 00000000 1\n").is_err());
+        assert!(TransitionTable::parse("0 0000000 1\n").is_err());
+        assert!(TransitionTable::parse("2 00000000 1\n").is_err());
+        assert!(TransitionTable::parse("0 10000001\n").is_err());
+        assert!(TransitionTable::parse("# nothing\n").is_err());
+    }
+}
@@ -0,0 +1,465 @@
+use std::env;
+
+use colors_transform::{Color, Hsl};
+#[cfg(feature = "tui")]
+use ratatui::style::{self, Modifier, Style};
+use unicode_width::UnicodeWidthStr;
+
+use crate::color::ColorDepth;
+
+/// Glyphs for live cells from youngest to oldest, used when [`CellStyle::shows_glyphs`].
+pub const AGE_RAMP: [&str; 5] = ["·", "∙", "•", "●", "█"];
+
+/// The [`AGE_RAMP`] in ASCII, for the [`ascii`](Theme::ascii) theme.
+pub const ASCII_RAMP: [&str; 5] = [".", ":", "o", "O", "#"];
+
+/// How a live cell's age is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CellStyle {
+    /// The theme's glyph, colored by age.
+    #[default]
+    Color,
+    /// A glyph from [`AGE_RAMP`] picked by age, in the terminal's default color, so the age
+    /// survives monochrome terminals and text captures.
+    Glyphs,
+    /// A glyph from [`AGE_RAMP`], colored by age.
+    Both,
+}
+
+impl CellStyle {
+    pub fn parse(input: &str) -> Result<CellStyle, String> {
+        match input.trim().to_lowercase().as_str() {
+            "color" | "colour" => Ok(CellStyle::Color),
+            "glyphs" => Ok(CellStyle::Glyphs),
+            "both" => Ok(CellStyle::Both),
+            _ => Err(format!(
+                "unknown cell style \"{input}\", expected color, glyphs or both"
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CellStyle::Color => "color",
+            CellStyle::Glyphs => "glyphs",
+            CellStyle::Both => "both",
+        }
+    }
+
+    pub fn shows_colors(&self) -> bool {
+        matches!(self, CellStyle::Color | CellStyle::Both)
+    }
+
+    pub fn shows_glyphs(&self) -> bool {
+        matches!(self, CellStyle::Glyphs | CellStyle::Both)
+    }
+}
+
+/// The background of dead cells that have been alive, under History rules.
+pub const HISTORY_RGB: (u8, u8, u8) = (0, 40, 120);
+
+/// Marked live cells, under History rules.
+pub const MARKED_ALIVE_RGB: (u8, u8, u8) = (255, 255, 255);
+
+/// The background of marked dead cells, under History rules.
+pub const MARKED_DEAD_RGB: (u8, u8, u8) = (140, 0, 0);
+
+/// What cells fade in from as they're born and out to as they die with `--fade`, the usual
+/// terminal background.
+pub const FADED_RGB: (u8, u8, u8) = (0, 0, 0);
+
+/// Glyphs standing in for the colors of a [`Theme::monochrome`] theme: cells alive in the
+/// previous generation while ghosts are on, dead cells that have been alive under History
+/// rules, and marked live and dead cells.
+pub const GHOST_GLYPH: &str = "○";
+pub const HISTORY_GLYPH: &str = "░";
+pub const MARKED_ALIVE_GLYPH: &str = "◆";
+pub const MARKED_DEAD_GLYPH: &str = "×";
+
+/// Generations over which the ramp palettes run from their first color to their last.
+const PALETTE_SPAN: f32 = 180.0;
+
+/// The colors live cells take on as they age.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Palette {
+    /// A trip around the hue wheel. Its red and green stretches are hard to tell apart with
+    /// red-green color blindness.
+    #[default]
+    Rainbow,
+    /// Bright yellow fading to slate blue, after the cividis map, which was designed to look
+    /// the same to people with deuteranopia or protanopia as to everyone else.
+    Cividis,
+    /// Orange, yellow, sky blue and blue from the Okabe-Ito palette, which stays distinct
+    /// under deuteranopia and protanopia.
+    OkabeIto,
+}
+
+impl Palette {
+    pub fn parse(input: &str) -> Result<Palette, String> {
+        match input.trim().to_lowercase().as_str() {
+            "rainbow" => Ok(Palette::Rainbow),
+            "cividis" => Ok(Palette::Cividis),
+            "okabe-ito" | "okabeito" => Ok(Palette::OkabeIto),
+            _ => Err(format!(
+                "unknown palette \"{input}\", expected rainbow, cividis or okabe-ito"
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Rainbow => "rainbow",
+            Palette::Cividis => "cividis",
+            Palette::OkabeIto => "okabe-ito",
+        }
+    }
+
+    /// The color of a continuous automaton's cell in `state`, from 0 to 1, as red, green and
+    /// blue. The ramp palettes run from their last color up to their first; the rainbow runs
+    /// from dark blue to bright red.
+    pub fn intensity_rgb(&self, state: f32) -> (u8, u8, u8) {
+        let state = state.clamp(0.0, 1.0);
+        match self {
+            Palette::Rainbow => {
+                let rgb = Hsl::from(240.0 * (1.0 - state), 100.0, 15.0 + 40.0 * state).to_rgb();
+                (
+                    rgb.get_red() as u8,
+                    rgb.get_green() as u8,
+                    rgb.get_blue() as u8,
+                )
+            }
+            _ => self.rgb(((1.0 - state) * PALETTE_SPAN) as u32),
+        }
+    }
+
+    /// The color of a live cell of the given age, as red, green and blue.
+    pub fn rgb(&self, age: u32) -> (u8, u8, u8) {
+        let stops: &[(u8, u8, u8)] = match self {
+            Palette::Rainbow => return rainbow(age),
+            Palette::Cividis => &[
+                (255, 234, 70),
+                (188, 175, 111),
+                (124, 123, 120),
+                (65, 87, 127),
+            ],
+            Palette::OkabeIto => &[(230, 159, 0), (240, 228, 66), (86, 180, 233), (0, 114, 178)],
+        };
+
+        let position = (age as f32 / PALETTE_SPAN).min(1.0) * (stops.len() - 1) as f32;
+        let index = (position as usize).min(stops.len() - 2);
+        let t = position - index as f32;
+        let (from, to) = (stops[index], stops[index + 1]);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+    }
+}
+
+/// The original age coloring: the hue cycles every 180 generations while the color slowly
+/// darkens and loses saturation.
+fn rainbow(age: u32) -> (u8, u8, u8) {
+    let mut hue = age as f32;
+    hue *= 2.0;
+    hue %= 360.0;
+
+    let mut saturation = 100.0 - ((age as f32 / 360.0) * 25.0);
+    if saturation < 0.0 {
+        saturation = 0.0;
+    }
+
+    let light = 50.0 - ((age as f32 / 360.0) * 17.0);
+
+    let rgb = Hsl::from(hue, saturation, light).to_rgb();
+    (
+        rgb.get_red() as u8,
+        rgb.get_green() as u8,
+        rgb.get_blue() as u8,
+    )
+}
+
+/// A faint pattern on dead cells that shows where the board ends on terminals whose default
+/// background is the same as an empty cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Shading {
+    #[default]
+    None,
+    /// Every other dead cell gets a gray background.
+    Checkerboard,
+    /// Every dead cell gets a gray dot.
+    Grid,
+}
+
+impl Shading {
+    pub fn parse(input: &str) -> Result<Shading, String> {
+        match input.trim().to_lowercase().as_str() {
+            "none" => Ok(Shading::None),
+            "checkerboard" => Ok(Shading::Checkerboard),
+            "grid" => Ok(Shading::Grid),
+            _ => Err(format!(
+                "unknown shading \"{input}\", expected none, checkerboard or grid"
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shading::None => "none",
+            Shading::Checkerboard => "checkerboard",
+            Shading::Grid => "grid",
+        }
+    }
+}
+
+/// Parses a shading intensity, as a percentage of full brightness.
+pub fn parse_intensity(input: &str) -> Result<u8, String> {
+    match input.trim().trim_end_matches('%').parse::<u8>() {
+        Ok(intensity) if intensity <= 100 => Ok(intensity),
+        _ => Err(format!(
+            "intensity must be between 0 and 100, got \"{input}\""
+        )),
+    }
+}
+
+/// Controls how cells are drawn. Glyphs may be one or two terminal columns wide; every cell
+/// takes up [`Theme::cell_width`] columns so the board stays aligned either way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub alive_glyph: String,
+    pub dead_glyph: String,
+    pub cell_style: CellStyle,
+    pub palette: Palette,
+    /// What the terminal can show; palette colors are approximated to fit.
+    pub color_depth: ColorDepth,
+    pub shading: Shading,
+    /// Brightness of the shading's gray, as a percentage.
+    pub shading_intensity: u8,
+    /// Whether everything is drawn in the terminal's default colors, with glyphs telling
+    /// cells apart, for terminals and people without color.
+    pub monochrome: bool,
+    /// Whether ages are shown with the [`ASCII_RAMP`], for fonts without block glyphs such as
+    /// the legacy Windows console's.
+    pub ascii: bool,
+}
+
+impl Theme {
+    /// Looks up a built-in theme by name, case-insensitively. `cividis` and `okabe-ito` are the
+    /// classic theme with a color-blind friendly [`Palette`], `mono` is
+    /// [`monochrome`](Theme::monochrome), showing age with the [`AGE_RAMP`], and `ascii` draws
+    /// cells with `#` and shows age with the [`ASCII_RAMP`].
+    pub fn named(name: &str) -> Option<Theme> {
+        let name = name.to_lowercase();
+        if name == "ascii" {
+            let mut theme = Theme::with_glyph("#").unwrap();
+            theme.ascii = true;
+            return Some(theme);
+        }
+        if matches!(&name[..], "mono" | "monochrome") {
+            let mut theme = Theme::with_glyph("█").unwrap();
+            theme.cell_style = CellStyle::Glyphs;
+            theme.monochrome = true;
+            return Some(theme);
+        }
+        let (alive_glyph, palette) = match &name[..] {
+            "classic" => ("█", Palette::Rainbow),
+            "square" => ("██", Palette::Rainbow),
+            "dots" => ("●", Palette::Rainbow),
+            "emoji" => ("🟩", Palette::Rainbow),
+            "cividis" => ("█", Palette::Cividis),
+            "okabe-ito" | "okabeito" => ("█", Palette::OkabeIto),
+            _ => return None,
+        };
+
+        let mut theme = Theme::with_glyph(alive_glyph).unwrap();
+        theme.palette = palette;
+        Some(theme)
+    }
+
+    /// Builds a theme drawing live cells with `glyph`. Fails if the glyph isn't one or two
+    /// columns wide.
+    pub fn with_glyph(glyph: &str) -> Result<Theme, String> {
+        let width = glyph.width();
+        if !(1..=2).contains(&width) {
+            return Err(format!("glyph must be 1 or 2 columns wide, not {width}"));
+        }
+
+        Ok(Theme {
+            alive_glyph: String::from(glyph),
+            dead_glyph: " ".repeat(width),
+            cell_style: CellStyle::default(),
+            palette: Palette::default(),
+            color_depth: ColorDepth::default(),
+            shading: Shading::default(),
+            shading_intensity: 15,
+            monochrome: false,
+            ascii: false,
+        })
+    }
+
+    /// The theme used without `--theme`: `ascii` in the legacy Windows console, whose fonts
+    /// often lack the block glyphs, told apart from Windows Terminal by `$WT_SESSION`, and
+    /// `classic` everywhere else.
+    pub fn for_terminal() -> Theme {
+        if cfg!(windows) && env::var_os("WT_SESSION").is_none() {
+            Theme::named("ascii").unwrap()
+        } else {
+            Theme::default()
+        }
+    }
+
+    /// The glyphs ages are shown with, youngest first.
+    fn ramp(&self) -> &[&'static str; 5] {
+        if self.ascii {
+            &ASCII_RAMP
+        } else {
+            &AGE_RAMP
+        }
+    }
+
+    /// The glyph for a live cell that has survived `age` generations. Steps along the ramp get
+    /// twice as long each time, so old and very old cells can still be told apart.
+    pub fn glyph_for_age(&self, age: u32) -> &str {
+        if !self.shows_glyphs() {
+            return &self.alive_glyph;
+        }
+
+        let step = (age + 1).ilog2() as usize;
+        self.ramp()[step.min(AGE_RAMP.len() - 1)]
+    }
+
+    /// The glyph for a continuous automaton's cell in `state`, from 0 to 1: a step along
+    /// [`AGE_RAMP`] by state when [`CellStyle::shows_glyphs`].
+    pub fn glyph_for_intensity(&self, state: f32) -> &str {
+        if !self.shows_glyphs() {
+            return &self.alive_glyph;
+        }
+
+        let step = (state.clamp(0.0, 1.0) * (AGE_RAMP.len() - 1) as f32).round() as usize;
+        self.ramp()[step]
+    }
+
+    /// Whether live cells are colored by age or state.
+    pub fn shows_colors(&self) -> bool {
+        self.cell_style.shows_colors() && !self.monochrome
+    }
+
+    /// Whether live cells take a glyph from the [`AGE_RAMP`], which a monochrome theme always
+    /// does since nothing else shows age.
+    pub fn shows_glyphs(&self) -> bool {
+        self.cell_style.shows_glyphs() || self.monochrome
+    }
+
+    /// `color`, or the default color in a monochrome theme.
+    #[cfg(feature = "tui")]
+    pub fn tint(&self, color: style::Color) -> style::Color {
+        if self.monochrome {
+            style::Color::Reset
+        } else {
+            color
+        }
+    }
+
+    /// A background of `color` picking something out, or reversed default colors in a
+    /// monochrome theme.
+    #[cfg(feature = "tui")]
+    pub fn highlight(&self, color: style::Color) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().bg(color)
+        }
+    }
+
+    /// The gray used for shading dead cells.
+    pub fn shading_gray(&self) -> (u8, u8, u8) {
+        let level = (self.shading_intensity.min(100) as u16 * 255 / 100) as u8;
+        (level, level, level)
+    }
+
+    /// Number of terminal columns a single cell occupies.
+    pub fn cell_width(&self) -> u16 {
+        self.alive_glyph.width().max(self.dead_glyph.width()).max(1) as u16
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::named("classic").unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_width() {
+        assert_eq!(Theme::default().cell_width(), 1);
+        assert_eq!(Theme::named("Square").unwrap().cell_width(), 2);
+        assert_eq!(Theme::named("emoji").unwrap().cell_width(), 2);
+        assert!(Theme::with_glyph("███").is_err());
+        assert_eq!(Theme::named("ascii").unwrap().cell_width(), 1);
+        assert!(Theme::with_glyph("").is_err());
+    }
+
+    #[test]
+    fn palettes() {
+        assert_eq!(Palette::Cividis.rgb(0), (255, 234, 70));
+        assert_eq!(Palette::Cividis.rgb(1000), (65, 87, 127));
+        assert_eq!(Palette::OkabeIto.rgb(60), (240, 228, 66));
+        assert_eq!(Palette::OkabeIto.rgb(30), (235, 194, 33));
+        assert_eq!(Palette::Cividis.intensity_rgb(1.0), (255, 234, 70));
+        assert_eq!(Palette::Cividis.intensity_rgb(0.0), (65, 87, 127));
+        assert_eq!(
+            Theme::named("okabe-ito").unwrap().palette,
+            Palette::OkabeIto
+        );
+    }
+
+    #[test]
+    fn shading() {
+        assert_eq!(Shading::parse("Grid"), Ok(Shading::Grid));
+        assert!(Shading::parse("plaid").is_err());
+        assert_eq!(parse_intensity("40%"), Ok(40));
+        assert!(parse_intensity("101").is_err());
+
+        let theme = Theme {
+            shading_intensity: 20,
+            ..Theme::default()
+        };
+        assert_eq!(theme.shading_gray(), (51, 51, 51));
+    }
+
+    #[test]
+    fn monochrome() {
+        let theme = Theme::named("Mono").unwrap();
+        assert!(theme.monochrome && !theme.shows_colors());
+        assert_eq!(theme.glyph_for_age(0), "·");
+        assert_eq!(theme.glyph_for_age(1000), "█");
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn monochrome_colors() {
+        let theme = Theme::named("Mono").unwrap();
+        assert_eq!(theme.tint(style::Color::Red), style::Color::Reset);
+        assert_eq!(theme.highlight(style::Color::Blue).bg, None);
+        assert_eq!(
+            Theme::default().highlight(style::Color::Blue).bg,
+            Some(style::Color::Blue)
+        );
+    }
+
+    #[test]
+    fn age_ramp() {
+        let mut theme = Theme::default();
+        assert_eq!(theme.glyph_for_age(100), "█");
+
+        theme.cell_style = CellStyle::Glyphs;
+        let glyphs: Vec<_> = [0, 1, 2, 3, 7, 15, 1000]
+            .into_iter()
+            .map(|age| theme.glyph_for_age(age))
+            .collect();
+        assert_eq!(glyphs, ["·", "∙", "∙", "•", "●", "█", "█"]);
+        assert_eq!(theme.glyph_for_intensity(0.1), "·");
+        assert_eq!(theme.glyph_for_intensity(1.0), "█");
+    }
+}
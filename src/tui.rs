@@ -1,17 +1,113 @@
-use std::io::{self, stdout, Stdout};
+use std::{
+    io::{self, stdout, Stdout},
+    time::{Duration, Instant},
+};
 
-use ratatui::{crossterm::{cursor::{self, Show}, execute, terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen}}, prelude::CrosstermBackend, Terminal};
+use ratatui::{
+    crossterm::{
+        cursor,
+        event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
+        execute,
+        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    prelude::{Backend, CrosstermBackend},
+    Terminal,
+};
 
 pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
 pub fn init() -> io::Result<Tui> {
-    execute!(stdout(), cursor::Hide, EnterAlternateScreen)?;
-    enable_raw_mode()?;
+    enter()?;
     Terminal::new(CrosstermBackend::new(stdout()))
 }
 
+/// Puts the terminal into the alternate screen and raw mode the app runs in.
+fn enter() -> io::Result<()> {
+    execute!(
+        stdout(),
+        cursor::Hide,
+        EnterAlternateScreen,
+        EnableFocusChange,
+        EnableMouseCapture
+    )?;
+    enable_raw_mode()
+}
+
 pub fn restore() -> io::Result<()> {
-    execute!(stdout(), cursor::Show, LeaveAlternateScreen)?;
+    execute!(
+        stdout(),
+        cursor::Show,
+        LeaveAlternateScreen,
+        DisableFocusChange,
+        DisableMouseCapture
+    )?;
     disable_raw_mode()?;
     Ok(())
 }
+
+/// Sets the terminal up from scratch and clears `terminal` so the next draw repaints
+/// everything, to recover from output by other processes or a glitched resize.
+pub fn reinit<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
+    restore()?;
+    enter()?;
+    terminal.clear()
+}
+
+/// Stops the process the way Ctrl-Z does outside raw mode, handing a working terminal back to
+/// the shell first. Once the process is continued the terminal is set up again and `terminal`
+/// is cleared so the next draw repaints everything.
+#[cfg(unix)]
+pub fn suspend<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
+    restore()?;
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+    enter()?;
+    terminal.clear()
+}
+
+/// Caps how often the screen is redrawn, independently of how fast the simulation ticks.
+#[derive(Debug, Default)]
+pub struct FrameLimiter {
+    interval: Option<Duration>,
+    last: Option<Instant>,
+}
+
+impl FrameLimiter {
+    /// A limiter allowing one frame per `interval`, or every frame if there's no interval.
+    pub fn new(interval: Option<Duration>) -> FrameLimiter {
+        FrameLimiter {
+            interval,
+            last: None,
+        }
+    }
+
+    /// Whether a frame may be drawn at `now`. Returning `true` counts as drawing one.
+    pub fn due(&mut self, now: Instant) -> bool {
+        let due = match (self.interval, self.last) {
+            (Some(interval), Some(last)) => now - last >= interval,
+            _ => true,
+        };
+        if due {
+            self.last = Some(now);
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_frames() {
+        let start = Instant::now();
+        let mut limiter = FrameLimiter::new(Some(Duration::from_millis(100)));
+        let frames = (0..10)
+            .filter(|i| limiter.due(start + Duration::from_millis(30 * i)))
+            .count();
+        // drawn at 0, 120 and 240ms
+        assert_eq!(frames, 3);
+
+        let mut unlimited = FrameLimiter::new(None);
+        assert!((0..10).all(|_| unlimited.due(start)));
+    }
+}
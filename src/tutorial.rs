@@ -0,0 +1,111 @@
+/// Columns taken by the tutorial's side panel, borders included.
+pub const PANEL_WIDTH: u16 = 34;
+
+/// Something the tutorial asks the user to do, once, to move on to the next step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lesson {
+    /// Moving the cursor while editing.
+    Move,
+    /// Bringing a cell to life or killing it while editing.
+    Draw,
+    /// Leaving editing so the board runs.
+    Run,
+    /// Going back to editing from a running board.
+    Pause,
+    /// Mutating the rule.
+    Mutate,
+    /// Saving the board to a quick-save slot.
+    Save,
+}
+
+/// A step of the tutorial: a prompt shown until its lesson is done. Prompts name keys by their
+/// action, as in `{mutate}`, so they follow `--bind`.
+#[derive(Debug)]
+pub struct Step {
+    pub lesson: Lesson,
+    pub title: &'static str,
+    pub prompt: &'static str,
+}
+
+pub const STEPS: [Step; 6] = [
+    Step {
+        lesson: Lesson::Move,
+        title: "Move around",
+        prompt: "You're editing the board. Move the cursor with W, A, S and D; hold Shift to jump further.",
+    },
+    Step {
+        lesson: Lesson::Draw,
+        title: "Draw some cells",
+        prompt: "Press {toggle-cell} to bring the cell under the cursor to life, or click and drag to draw. Try three in a row.",
+    },
+    Step {
+        lesson: Lesson::Run,
+        title: "Run the board",
+        prompt: "Press {toggle-editing} to stop editing and watch the board evolve, one generation per tick. {faster} and {slower} change the speed.",
+    },
+    Step {
+        lesson: Lesson::Pause,
+        title: "Go back to editing",
+        prompt: "Press {toggle-editing} again to pause and go back to editing, where you can change the board.",
+    },
+    Step {
+        lesson: Lesson::Mutate,
+        title: "Change the rule",
+        prompt: "The title shows the rule, B3/S23 for Life: cells are born with 3 neighbors and survive with 2 or 3. Press {mutate} to change a digit of it.",
+    },
+    Step {
+        lesson: Lesson::Save,
+        title: "Save your work",
+        prompt: "Press F5 to save the board to the quick-save slot shown at the bottom right. F9 loads it back, and 1-9 pick another slot.",
+    },
+];
+
+/// Walks a new user through the app with `tutorial`, one [`Step`] at a time.
+#[derive(Debug, Default)]
+pub struct Tutorial {
+    /// Steps done so far.
+    done: usize,
+}
+
+impl Tutorial {
+    /// Moves on to the next step if `lesson` is what the current one asks for.
+    pub fn learn(&mut self, lesson: Lesson) {
+        if STEPS
+            .get(self.done)
+            .is_some_and(|step| step.lesson == lesson)
+        {
+            self.done += 1;
+        }
+    }
+
+    /// How many steps have been done, up to `STEPS.len()` once the tutorial is over.
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done == STEPS.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_in_order() {
+        let mut tutorial = Tutorial::default();
+        tutorial.learn(Lesson::Draw);
+        assert_eq!(tutorial.done(), 0);
+        tutorial.learn(Lesson::Move);
+        tutorial.learn(Lesson::Move);
+        assert_eq!(tutorial.done(), 1);
+        for step in &STEPS[1..] {
+            assert!(!tutorial.is_finished());
+            tutorial.learn(step.lesson);
+        }
+        assert!(tutorial.is_finished());
+        tutorial.learn(Lesson::Save);
+        assert_eq!(tutorial.done(), STEPS.len());
+    }
+}
@@ -1,132 +1,1154 @@
-use colors_transform::Hsl;
+use std::{cmp::Ordering, time::SystemTime};
+
+use unicode_width::UnicodeWidthStr;
+
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, WidgetRef},
+    widgets::{
+        block::Title, Axis, Block, Borders, Chart, Clear, Dataset, Gauge, GraphType, List,
+        ListItem, Paragraph, Widget, WidgetRef, Wrap,
+    },
     Frame,
 };
 
-use crate::app::{Coords, Model, State};
+use crate::{
+    app::{Coords, Inspection, Model, PickerKind, Preset, PromptField, Screen, State},
+    checkpoints::Checkpoint,
+    color::blend,
+    explorer,
+    history::Recent,
+    input::{Action, KeyHelp, Keymap},
+    overlay,
+    scenario::{Scenario, Step},
+    stats::Sample,
+    theme::{
+        Shading, Theme, FADED_RGB, GHOST_GLYPH, HISTORY_GLYPH, HISTORY_RGB, MARKED_ALIVE_GLYPH,
+        MARKED_ALIVE_RGB, MARKED_DEAD_GLYPH, MARKED_DEAD_RGB,
+    },
+    tutorial::{self, Tutorial},
+};
 
 pub fn view(f: &mut Frame, model: &mut Model) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(2),
-            Constraint::Length(3),
+    if model.is_zen() {
+        // the board keeps its size, so center it in the space the chrome left behind
+        let (columns, rows) = model.size();
+        let boards = if model.comparison().is_some() { 2 } else { 1 };
+        let width = (columns as u16 * model.theme().cell_width() + 1) * boards - 1;
+        let area = f.size();
+        let board_area = Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + area.height.saturating_sub(rows as u16) / 2,
+            width: width.min(area.width),
+            height: (rows as u16).min(area.height),
+        };
+        render_boards(f, model, board_area);
+        return;
+    }
+
+    let areas = model.layout().areas(f.size());
+    // the screen-reader friendly mode's status line sits under the board
+    let [body, status_area] = Layout::vertical([
+        Constraint::Min(2),
+        Constraint::Length(if model.is_accessible() { 2 } else { 0 }),
+    ])
+    .areas(areas.board);
+    // the tutorial's or lesson's panel sits to the right, leaving the rest of the middle to
+    // the board
+    let body = if model.tutorial().is_some() || model.scenario().is_some() {
+        let [body, panel] = Layout::horizontal([
+            Constraint::Min(0),
+            Constraint::Length(tutorial::PANEL_WIDTH),
         ])
-        .split(f.size());
+        .areas(body);
+        match (model.tutorial(), model.scenario()) {
+            (Some(tutorial), _) => {
+                render_tutorial(f, tutorial, model.keymap(), model.theme(), panel)
+            }
+            (None, Some(scenario)) => render_scenario(f, scenario, model, panel),
+            (None, None) => {}
+        }
+        body
+    } else {
+        body
+    };
 
-    let title_block = Paragraph::new(Line::from(model.rulestring()))
-        .block(Block::default().borders(Borders::ALL).title("Rulestring"))
+    let mut generation = format!("gen {}", model.generation());
+    if model.is_inverted() {
+        generation.push_str(" (inverted phase)");
+    }
+    if let Some(stabilization) = model.stabilization() {
+        generation.push_str(&format!(
+            " / stable at gen {} (period {})",
+            stabilization.generation, stabilization.period
+        ));
+    }
+    if let Some(expected) = model.expected_stabilization() {
+        let verdict = match model.stabilization() {
+            Some(stabilization) if stabilization.generation == expected => " ✓",
+            Some(_) => " ✗",
+            None => "",
+        };
+        generation.push_str(&format!(" / expected {expected}{verdict}"));
+    }
+    generation.push_str(&format!(" / tick {:?}", model.tickrate()));
+    let effective = model.effective_tickrate();
+    if *model.state() == State::Running && effective > model.tickrate() {
+        generation.push_str(&format!(" (effective {effective:.1?})"));
+    }
+    if model.time_lapse() > 1 {
+        generation.push_str(&format!(" / time-lapse {}x", model.time_lapse()));
+    }
+    if model.zoom() > 1 {
+        generation.push_str(&format!(" / zoom {}x", model.zoom()));
+    }
+    let mut title_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Rulestring")
+        .title(Title::from(generation).alignment(Alignment::Center));
+    if let Some(seed) = model.rng().seed() {
+        title_block =
+            title_block.title(Title::from(format!("seed {seed}")).alignment(Alignment::Right));
+    }
+    let mut rulestring = match (
+        model.continuous(),
+        model.mnca(),
+        model.table(),
+        model.kernel(),
+    ) {
+        (Some(continuous), ..) => continuous.to_string(),
+        (None, Some(mnca), ..) => mnca.to_string(),
+        (None, None, Some(table), _) => table.to_string(),
+        (None, None, None, Some(kernel)) => kernel.to_string(),
+        (None, None, None, None) => match model.known_rule() {
+            Some(known) => format!(
+                "{} {}: {}",
+                model.rulestring(),
+                known.name,
+                known.description
+            ),
+            None => model.rulestring(),
+        },
+    };
+    let lineage = model.explorer().lineage();
+    if !lineage.is_empty() {
+        let shown = lineage.iter().rev().take(explorer::SHOWN_LINEAGE);
+        let mut from = shown.map(String::as_str).collect::<Vec<_>>().join(" ← ");
+        if lineage.len() > explorer::SHOWN_LINEAGE {
+            from.push_str(" ← …");
+        }
+        rulestring = format!("{rulestring} ← {from}");
+    }
+    if let Some(comparison) = model.comparison() {
+        rulestring = format!("{rulestring}  |  {}", comparison.rulestring());
+    }
+    let title_block = Paragraph::new(Line::from(rulestring))
+        .block(title_block)
         .centered();
 
-    f.render_widget(title_block, chunks[0]);
+    if let Some(header) = areas.header {
+        f.render_widget(title_block, header);
+    }
 
-    f.render_widget(&*model, chunks[1]);
+    // with a pane of their own, the stats no longer take turns with the board
+    match (areas.stats, model.screen()) {
+        (Some(stats), _) => {
+            render_boards(f, model, body);
+            render_stats(f, model, stats);
+        }
+        (None, Screen::Board) => render_boards(f, model, body),
+        (None, Screen::Stats) => render_stats(f, model, body),
+    }
+
+    if let Some(fast_forward) = model.fast_forward() {
+        let (done, total) = (
+            model.generation() - fast_forward.from,
+            fast_forward.to - fast_forward.from,
+        );
+        let width = body.width.min(50);
+        let height = body.height.min(3);
+        let progress_area = Rect {
+            x: body.x + (body.width - width) / 2,
+            y: body.y + (body.height - height) / 2,
+            width,
+            height,
+        };
+        let gauge =
+            Gauge::default()
+                .block(Block::default().borders(Borders::ALL).title(
+                    match fast_forward.until_stable {
+                        true => "Running until stable (Esc to stop)",
+                        false => "Fast-forwarding (Esc to stop)",
+                    },
+                ))
+                .gauge_style(Style::default().fg(model.theme().tint(Color::Green)))
+                .ratio(done as f64 / total as f64)
+                .label(format!("gen {} of {}", model.generation(), fast_forward.to));
+        f.render_widget(Clear, progress_area);
+        f.render_widget(gauge, progress_area);
+    }
+
+    if model.is_debug_overlay() {
+        let metrics = model.metrics();
+        let lines = vec![
+            Line::from(format!("engine  {}", model.engine())),
+            Line::from(format!("tick    {:?}", metrics.tick)),
+            Line::from(format!("cells   {}", metrics.cells_evaluated)),
+            Line::from(format!("allocs  {}", metrics.allocations)),
+            Line::from(format!("queue   {}", metrics.queue_depth)),
+        ];
+        let width = 26.min(body.width);
+        let overlay_area = Rect {
+            x: body.right() - width,
+            width,
+            height: (lines.len() as u16 + 2).min(body.height),
+            ..body
+        };
+        let overlay = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(titled(model, "Debug", Action::Debug, "")),
+        );
+        f.render_widget(Clear, overlay_area);
+        f.render_widget(overlay, overlay_area);
+    }
+
+    // dim and without a border, so it's there in a recording without getting in the way
+    if let Some(corner) = model.overlay() {
+        let line = overlay::line(model, SystemTime::now());
+        let overlay_area = Rect::from(corner.area(body.into(), line.width() as u16));
+        let overlay = Paragraph::new(line).style(
+            Style::default()
+                .fg(model.theme().tint(Color::Gray))
+                .add_modifier(Modifier::DIM),
+        );
+        f.render_widget(overlay, overlay_area);
+    }
+
+    // the banner is drawn over the top of the board so that it doesn't change the board's size
+    if !model.config_errors().is_empty() {
+        let banner_area = Rect {
+            height: (model.config_errors().len() as u16 + 2).min(body.height),
+            ..body
+        };
+        let lines: Vec<Line> = model
+            .config_errors()
+            .iter()
+            .map(|error| Line::from(error.to_string()))
+            .collect();
+        let banner = Paragraph::new(lines)
+            .style(Style::default().fg(model.theme().tint(Color::Red)))
+            .block(Block::default().borders(Borders::ALL).title(titled(
+                model,
+                "Configuration",
+                Action::Correct,
+                " to correct",
+            )));
+        f.render_widget(Clear, banner_area);
+        f.render_widget(banner, banner_area);
+    }
+
+    // the newest unhandled error sits at the bottom of the board until dismissed
+    if let Some(error) = model.errors().last() {
+        let height = (error.chain.len() as u16 + 3).min(body.height);
+        let panel_area = Rect {
+            y: body.bottom() - height,
+            height,
+            ..body
+        };
+        let mut lines =
+            vec![Line::from(error.context.clone())
+                .style(Style::default().add_modifier(Modifier::BOLD))];
+        lines.extend(
+            error
+                .chain
+                .iter()
+                .map(|cause| Line::from(format!("  caused by: {cause}"))),
+        );
+        let count = model.errors().len();
+        let title = if count > 1 {
+            titled(
+                model,
+                &format!("Error (1 of {count})"),
+                Action::Dismiss,
+                " to dismiss",
+            )
+        } else {
+            titled(model, "Error", Action::Dismiss, " to dismiss")
+        };
+        let panel = Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .style(Style::default().fg(model.theme().tint(Color::Red)))
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(Clear, panel_area);
+        f.render_widget(panel, panel_area);
+    }
+
+    if model.is_help() {
+        render_help(f, model, body);
+    }
+
+    if let Some(picker) = model.picker() {
+        let width = body.width.min(72);
+        let height = body.height.min(14);
+        let picker_area = Rect {
+            x: body.x + (body.width - width) / 2,
+            y: body.y + (body.height - height) / 2,
+            width,
+            height,
+        };
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(28), Constraint::Min(0)])
+            .split(picker_area);
+
+        let items: Vec<ListItem> = picker
+            .matches()
+            .enumerate()
+            .map(|(i, name)| {
+                let style = if i == picker.selected_index() {
+                    model.theme().highlight(Color::Blue)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(name).style(style)
+            })
+            .collect();
+        let label = match model.picker_kind() {
+            PickerKind::Patterns => "Pattern",
+            PickerKind::Recent => "Recent",
+            PickerKind::Checkpoints => "Checkpoint",
+            PickerKind::Boards => "Board",
+        };
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{label}: {}█", picker.query)),
+        );
+
+        let details = match model.picker_kind() {
+            PickerKind::Patterns => picker
+                .selected()
+                .and_then(|name| model.library().resolve(name))
+                .map(|preset| pattern_details(&preset)),
+            PickerKind::Recent => picker
+                .selected()
+                .and_then(|label| model.recent(label))
+                .map(|recent| recent_details(model, recent)),
+            PickerKind::Checkpoints => picker
+                .selected()
+                .and_then(|label| model.checkpoint(label))
+                .map(checkpoint_details),
+            PickerKind::Boards => picker
+                .selected()
+                .and_then(|name| model.workspace().board(name))
+                .map(board_details),
+        };
+        let details = Paragraph::new(details.unwrap_or_default())
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title("Details"));
+
+        f.render_widget(Clear, picker_area);
+        f.render_widget(list, panes[0]);
+        f.render_widget(details, panes[1]);
+    }
 
     let current_keys_hint = {
         match model.state() {
             State::Editing => Span::styled(
-                "(Space) to toggle cell / (WASD) to move / (e) to exit editing mode",
-                Style::default().fg(Color::Yellow),
+                model.keymap().hints(true),
+                Style::default().fg(model.theme().tint(Color::Yellow)),
             ),
             State::Running => Span::styled(
-                "(e) to enter editing mode",
-                Style::default().fg(Color::Yellow),
+                model.keymap().hints(false),
+                Style::default().fg(model.theme().tint(Color::Yellow)),
+            ),
+            State::Prompting { .. } => {
+                let prompt = model.prompt().expect("prompting without a prompt");
+                let label = match prompt.field {
+                    PromptField::Rulestring => "Rulestring",
+                    PromptField::Preset => "Preset",
+                    PromptField::Theme => "Theme",
+                    PromptField::Size => "Board size (WxH, then center or top-left)",
+                };
+                Span::styled(
+                    format!(
+                        "{label}: {}█  (Enter) to apply / (Esc) to cancel",
+                        prompt.buffer
+                    ),
+                    Style::default().fg(model.theme().tint(Color::Yellow)),
+                )
+            }
+            State::Picking { .. } => Span::styled(
+                "Type to search / (Up/Down) to select / (Enter) to place at cursor / (Esc) to cancel",
+                Style::default().fg(model.theme().tint(Color::Yellow)),
+            ),
+            State::FastForwarding { .. } => Span::styled(
+                "(Esc) stop fast-forwarding / (q) quit",
+                Style::default().fg(model.theme().tint(Color::Yellow)),
             ),
             State::Done => Span::styled("", Style::default()),
         }
     };
 
-    let key_notes_footer =
-        Paragraph::new(Line::from(current_keys_hint)).block(Block::default().borders(Borders::ALL));
+    let mut footer_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Title::from(format!("slot {}", model.slot())).alignment(Alignment::Right));
+    if let Some(notice) = model.notice() {
+        footer_block = footer_block.title(notice.to_string());
+    }
+    let key_notes_footer = Paragraph::new(Line::from(current_keys_hint)).block(footer_block);
+
+    if let Some(footer) = areas.footer {
+        f.render_widget(key_notes_footer, footer);
+    }
+
+    if model.is_accessible() {
+        let status = Paragraph::new(model.status_line()).wrap(Wrap { trim: true });
+        f.render_widget(status, status_area);
+    }
+}
+
+/// `name`, followed by the keys bound to `action` and `what` they do, if it has any.
+fn titled(model: &Model, name: &str, action: Action, what: &str) -> String {
+    match model.keymap().keys(action) {
+        Some(keys) => format!("{name} ({keys}){what}"),
+        None => String::from(name),
+    }
+}
+
+/// Renders the key cheat sheet in the middle of `area`, with the keys that work in the current
+/// state.
+fn render_help(f: &mut Frame, model: &Model, area: Rect) {
+    let editing = *model.state() == State::Editing;
+    let help: Vec<KeyHelp> = model
+        .keymap()
+        .help()
+        .into_iter()
+        .filter(|line| if editing { line.editing } else { line.running })
+        .collect();
+    let keys_width = help
+        .iter()
+        .map(|line| line.keys.width())
+        .max()
+        .unwrap_or_default();
+    let lines: Vec<Line> = help
+        .iter()
+        .map(|line| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:keys_width$}  ", line.keys),
+                    Style::default().fg(model.theme().tint(Color::Yellow)),
+                ),
+                Span::raw(line.description.clone()),
+            ])
+        })
+        .collect();
+
+    let width = area.width.min(60);
+    let height = area.height.min(lines.len() as u16 + 2);
+    let help_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let mode = if editing { "editing" } else { "running" };
+    let panel = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(titled(
+        model,
+        &format!("Keys while {mode}"),
+        Action::Help,
+        " to close",
+    )));
+    f.render_widget(Clear, help_area);
+    f.render_widget(panel, help_area);
+}
+
+/// Renders the tutorial's steps, ticking off those done and spelling out the current one.
+fn render_tutorial(f: &mut Frame, tutorial: &Tutorial, keymap: &Keymap, theme: &Theme, area: Rect) {
+    let mut lines = vec![];
+    for (i, step) in tutorial::STEPS.iter().enumerate() {
+        match i.cmp(&tutorial.done()) {
+            Ordering::Less => lines.push(Line::styled(
+                format!("✓ {}", step.title),
+                Style::default().fg(theme.tint(Color::Green)),
+            )),
+            Ordering::Equal => {
+                lines.push(Line::styled(
+                    format!("▶ {}", step.title),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+                lines.push(Line::from(keymap.fill(step.prompt)));
+                lines.push(Line::from(""));
+            }
+            Ordering::Greater => lines.push(Line::styled(
+                format!("  {}", step.title),
+                Style::default().fg(theme.tint(Color::DarkGray)),
+            )),
+        }
+    }
+    if tutorial.is_finished() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(keymap.fill(
+            "That's the basics! Keep playing, try a pattern with {pick}, or press {quit} to quit.",
+        )));
+    }
+    let title = format!("Tutorial ({}/{})", tutorial.done(), tutorial::STEPS.len());
+    let panel = Paragraph::new(lines)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(panel, area);
+}
+
+/// Renders the lesson's current step: its text, or its question and, once answered, whether
+/// the answer was right and why.
+fn render_scenario(f: &mut Frame, scenario: &Scenario, model: &Model, area: Rect) {
+    let theme = model.theme();
+    let go_on = Line::styled(
+        "(Enter) to go on",
+        Style::default().fg(theme.tint(Color::Yellow)),
+    );
+    let lines = match (scenario.current(), scenario.answer()) {
+        (Some(Step::Say(text)), _) => vec![Line::from(text.clone()), Line::from(""), go_on],
+        (Some(Step::Predict(at)), None) => vec![
+            Line::from(format!(
+                "Will the cell at {},{}, under the cursor, be alive next generation?",
+                at.x, at.y
+            )),
+            Line::from(""),
+            Line::styled(
+                "(y) yes / (n) no",
+                Style::default().fg(theme.tint(Color::Yellow)),
+            ),
+        ],
+        (Some(Step::Predict(_)), Some(answer)) => {
+            let verdict = match answer.actual {
+                Some(actual) if actual == answer.predicted => {
+                    Line::styled("Right!", Style::default().fg(theme.tint(Color::Green)))
+                }
+                Some(_) => Line::styled("Not quite.", Style::default().fg(theme.tint(Color::Red))),
+                None => Line::from("This rule can't be predicted cell by cell."),
+            };
+            vec![
+                verdict,
+                Line::from(answer.reason.clone()),
+                Line::from(""),
+                go_on,
+            ]
+        }
+        _ => vec![Line::from(model.keymap().fill(
+            "That's the end of the lesson. Keep playing, or press {quit} to quit.",
+        ))],
+    };
+    let title = match &scenario.title {
+        Some(title) => format!("{title} ({}/{})", scenario.done(), scenario.steps.len()),
+        None => format!("Lesson ({}/{})", scenario.done(), scenario.steps.len()),
+    };
+    let panel = Paragraph::new(lines)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(panel, area);
+}
+
+/// Renders the board, and the comparison board beside it if there is one.
+fn render_boards(f: &mut Frame, model: &mut Model, area: Rect) {
+    let board_area = match model.comparison() {
+        Some(_) => Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)])
+            .spacing(1)
+            .split(area)[0],
+        None => area,
+    };
+    model.fit_viewport(board_area.into());
+
+    let model = &*model;
+    f.render_widget(model, board_area);
+    if let Some(comparison) = model.comparison() {
+        let comparison_area = Rect {
+            x: board_area.right() + 1,
+            width: area.right().saturating_sub(board_area.right() + 1),
+            ..board_area
+        };
+        f.render_widget(comparison, comparison_area);
+    }
+
+    // the inset sits in the board's bottom-right corner, unless decoration is left out
+    if let Some(center) = model.inset_center().filter(|_| !model.is_accessible()) {
+        let width = (board_area.width / 2).min(11 * INSET_SCALE.0 + 2);
+        let height = (board_area.height / 2).min(7 * INSET_SCALE.1 + 2);
+        let inset_area = Rect::new(
+            board_area.right() - width,
+            board_area.bottom() - height,
+            width,
+            height,
+        );
+        f.render_widget(Clear, inset_area);
+        f.render_widget(ZoomInset { model, center }, inset_area);
+    }
+
+    // the inspector sits in the top-right corner, out of the inset's way
+    if let Some(inspection) = model.inspect().filter(|_| model.is_inspector()) {
+        let width = board_area.width.min(INSPECTOR_WIDTH);
+        let height = board_area.height.min(6);
+        let inspector_area = Rect::new(board_area.right() - width, board_area.y, width, height);
+        f.render_widget(Clear, inspector_area);
+        f.render_widget(inspector(&inspection), inspector_area);
+    }
+}
+
+/// Columns taken by the neighborhood inspector, borders included.
+const INSPECTOR_WIDTH: u16 = 40;
+
+/// The neighborhood inspector's panel: the cell's state, its live neighbors and what happens
+/// to it next.
+fn inspector(inspection: &Inspection) -> Paragraph<'static> {
+    let Coords { x, y } = inspection.at;
+    let state = match inspection.alive {
+        true => format!("Alive, age {}", inspection.age),
+        false => String::from("Dead"),
+    };
+    let neighbors = match inspection.neighbors {
+        1 => String::from("1 live neighbor"),
+        count => format!("{count} live neighbors"),
+    };
+    let next = match inspection.next {
+        Some(true) => "alive",
+        Some(false) => "dead",
+        None => "unknown",
+    };
+    let lines = vec![
+        Line::from(state),
+        Line::from(format!("{neighbors}, next tick {next}")),
+        Line::from(inspection.reason.clone()),
+    ];
+    Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Cell {x},{y}")),
+    )
+}
+
+/// Renders the statistics screen: a summary line and charts of the recorded history.
+fn render_stats(f: &mut Frame, model: &Model, area: Rect) {
+    let rows = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+    ])
+    .split(area);
+    let stats = model.stats();
+
+    let (columns, board_rows) = model.size();
+    let latest = stats.latest().copied().unwrap_or(Sample {
+        generation: model.generation(),
+        population: 0,
+        births: 0,
+        deaths: 0,
+    });
+    let density = latest.population as f64 / (columns * board_rows).max(1) as f64;
+    let period = match model.stabilization() {
+        Some(stabilization) => format!(
+            "period {} since gen {}",
+            stabilization.period, stabilization.generation
+        ),
+        None => String::from("no period detected"),
+    };
+    let summary = Paragraph::new(Line::from(format!(
+        "population {} / density {:.1}% / births {} / deaths {} / {period}",
+        latest.population,
+        density * 100.0,
+        latest.births,
+        latest.deaths,
+    )))
+    .block(Block::default().borders(Borders::ALL).title("Statistics"));
+    f.render_widget(summary, rows[0]);
+
+    let series = |value: fn(&Sample) -> usize| -> Vec<(f64, f64)> {
+        stats
+            .samples()
+            .map(|sample| (sample.generation as f64, value(sample) as f64))
+            .collect()
+    };
+    let population = series(|sample| sample.population);
+    let births = series(|sample| sample.births);
+    let deaths = series(|sample| sample.deaths);
+
+    f.render_widget(
+        history_chart(
+            "Population",
+            &[("population", model.theme().tint(Color::Green), &population)],
+        ),
+        rows[1],
+    );
+    f.render_widget(
+        history_chart(
+            "Births and deaths",
+            &[
+                ("births", model.theme().tint(Color::Cyan), &births),
+                ("deaths", model.theme().tint(Color::Red), &deaths),
+            ],
+        ),
+        rows[2],
+    );
+}
+
+/// A named, colored line of `(generation, value)` points.
+type Series<'a> = (&'a str, Color, &'a [(f64, f64)]);
+
+/// A line chart over generations of each series, with axes fitted to the data.
+fn history_chart<'a>(title: &'a str, series: &[Series<'a>]) -> Chart<'a> {
+    let points = || series.iter().flat_map(|(_, _, data)| data.iter());
+    let first = points().map(|(x, _)| *x).fold(f64::INFINITY, f64::min);
+    let last = points().map(|(x, _)| *x).fold(f64::NEG_INFINITY, f64::max);
+    let (first, last) = if first <= last {
+        (first, last)
+    } else {
+        (0.0, 0.0)
+    };
+    let max = points().map(|(_, y)| *y).fold(1.0, f64::max);
+
+    let datasets = series
+        .iter()
+        .map(|(name, color, data)| {
+            Dataset::default()
+                .name(*name)
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(data)
+        })
+        .collect();
+
+    Chart::new(datasets)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .x_axis(
+            Axis::default()
+                .bounds([first, last.max(first + 1.0)])
+                .labels(vec![
+                    Span::raw(format!("{first}")),
+                    Span::raw(format!("{last}")),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max])
+                .labels(vec![Span::raw("0"), Span::raw(format!("{max}"))]),
+        )
+}
+
+/// The lines of the picker's detail pane for `preset`.
+fn pattern_details(preset: &Preset) -> Vec<Line<'static>> {
+    let Some(pattern) = preset.pattern() else {
+        return vec![
+            Line::from(preset.name().to_string()),
+            Line::from("Generated to fit the board."),
+        ];
+    };
+
+    let (width, height) = pattern.size();
+    let metadata = pattern.metadata;
+    let mut lines = vec![
+        Line::from(metadata.name.unwrap_or_else(|| preset.name().to_string())),
+        Line::from(format!("{width}x{height}")),
+    ];
+    if let Some(author) = metadata.author {
+        lines.push(Line::from(format!("by {author}")));
+    }
+    if let Some(year) = metadata.year {
+        lines.push(Line::from(format!("year {year}")));
+    }
+    lines.push(Line::from(""));
+    lines.extend(metadata.comments.into_iter().map(Line::from));
+    lines
+}
+
+/// The lines of the picker's detail pane for a recent run: its rule and seed, then the details
+/// of its pattern.
+fn recent_details(model: &Model, recent: &Recent) -> Vec<Line<'static>> {
+    let mut lines = vec![Line::from(format!("rule {}", recent.rule))];
+    if let Some(seed) = recent.seed {
+        lines.push(Line::from(format!("seed {seed}")));
+    }
+    lines.push(Line::from(""));
+    if let Some(preset) = model.library().resolve(&recent.preset) {
+        lines.extend(pattern_details(&preset));
+    }
+    lines
+}
 
-    f.render_widget(key_notes_footer, chunks[2]);
+/// The lines of the picker's detail pane for a workspace board: its rule, then its details as
+/// a pattern, notes included.
+fn board_details(board: &Preset) -> Vec<Line<'static>> {
+    let rule = board.pattern().and_then(|pattern| pattern.rule);
+    let mut lines = vec![Line::from(format!(
+        "rule {}",
+        rule.as_deref().unwrap_or("unchanged")
+    ))];
+    lines.extend(pattern_details(board));
+    lines
+}
+
+/// What the picker shows about a checkpoint to roll back to.
+fn checkpoint_details(checkpoint: &Checkpoint) -> Vec<Line<'static>> {
+    vec![
+        Line::from(format!("generation {}", checkpoint.generation)),
+        Line::from(format!("population {}", checkpoint.population)),
+        Line::from(""),
+        Line::from("Checkpoints after this one are dropped."),
+    ]
+}
+
+/// The color of a live cell of the given age, as close to the theme's palette as the terminal
+/// allows.
+fn age_color(theme: &Theme, age: u32) -> Color {
+    theme.color_depth.approximate(theme.palette.rgb(age))
+}
+
+/// Columns and rows each cell takes up in the magnified inset.
+const INSET_SCALE: (u16, u16) = (4, 2);
+
+/// A magnified view of the cells around `center`, drawn as solid blocks.
+struct ZoomInset<'a> {
+    model: &'a Model,
+    center: Coords,
+}
+
+impl Widget for ZoomInset<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = if self.model.is_inset_pinned() {
+            format!("Zoom (pinned at {},{})", self.center.x, self.center.y)
+        } else {
+            String::from("Zoom")
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let (columns, rows) = (inner.width / INSET_SCALE.0, inner.height / INSET_SCALE.1);
+        let left = self.center.x as isize - columns as isize / 2;
+        let top = self.center.y as isize - rows as isize / 2;
+        for row in 0..rows {
+            for column in 0..columns {
+                let (x, y) = (left + column as isize, top + row as isize);
+                let zoomed = Rect::new(
+                    inner.x + column * INSET_SCALE.0,
+                    inner.y + row * INSET_SCALE.1,
+                    INSET_SCALE.0,
+                    INSET_SCALE.1,
+                );
+                let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) else {
+                    continue;
+                };
+                let (Some(cell), Some(alive)) = (self.model.cell(x, y), self.model.is_alive(x, y))
+                else {
+                    continue;
+                };
+
+                let theme = self.model.theme();
+                let style = if alive {
+                    theme.highlight(age_color(theme, cell.age))
+                } else if self.model.was_alive(x, y) && !theme.monochrome {
+                    Style::default().bg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                buf.set_style(zoomed, style);
+                if *self.model.state() == State::Editing
+                    && (x as i16, y as i16)
+                        == (self.model.current_coords().x, self.model.current_coords().y)
+                {
+                    buf.set_style(zoomed, Style::default().fg(theme.tint(Color::Blue)));
+                    buf.set_string(zoomed.x, zoomed.y, "┌", Style::default());
+                    buf.set_string(
+                        zoomed.right() - 1,
+                        zoomed.bottom() - 1,
+                        "┘",
+                        Style::default(),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Pads `glyph` with spaces to `width` columns, so narrow glyphs fill double width cells.
+fn pad(glyph: &str, width: u16) -> String {
+    let padding = (width as usize).saturating_sub(glyph.width());
+    format!("{glyph}{}", " ".repeat(padding))
 }
 
 impl WidgetRef for Model {
+    /// Draws the part of the board in the viewport, each cell as a block [`Model::zoom`] cells
+    /// tall and wide.
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
-        let mut relative_x = 0;
-        for x in area.left()..area.right() {
-            let mut relative_y = 0;
-            for y in area.top()..area.bottom() {
-                let mut hue = self.cells()[relative_y][relative_x].age as f32;
-                hue *= 2.0;
-                hue %= 360.0;
-
-                let mut saturation =
-                    100.0 - ((self.cells()[relative_y][relative_x].age as f32 / 360.0) * 25.0);
-                if saturation < 0.0 {
-                    saturation = 0.0;
-                }
+        let theme = self.theme();
+        let cell_width = theme.cell_width();
+        let zoom = self.zoom();
+        let (block_width, block_height) = (cell_width * zoom, zoom);
+        let columns = area.width / block_width;
+        let rows = area.height / block_height;
+        let Coords { x: left, y: top } = *self.viewport();
+        // ghosts, history, shading and glyphs by age only decorate, and screen readers would
+        // read them out
+        let decorate = !self.is_accessible();
 
-                let light =
-                    50.0 - ((self.cells()[relative_y][relative_x].age as f32 / 360.0) * 17.0);
-
-                let hsl = Hsl::from(hue, saturation, light);
-                let rgb = colors_transform::Color::to_rgb(&hsl);
-                if self.cells()[relative_y][relative_x].is_alive {
-                    buf.get_mut(x, y).set_char('█').set_fg(Color::Rgb(
-                        colors_transform::Color::get_red(&rgb) as u8,
-                        colors_transform::Color::get_green(&rgb) as u8,
-                        colors_transform::Color::get_blue(&rgb) as u8,
-                    ));
+        for column in 0..columns {
+            let x = area.left() + column * block_width;
+            for row in 0..rows {
+                let y = area.top() + row * block_height;
+                let (cell_x, cell_y) =
+                    (left as usize + column as usize, top as usize + row as usize);
+                let (Some(cell), Some(alive)) =
+                    (self.cell(cell_x, cell_y), self.is_alive(cell_x, cell_y))
+                else {
+                    continue;
+                };
+
+                let (glyph, style) = if let Some(state) = self.intensity(cell_x, cell_y) {
+                    intensity_cell(theme, state)
+                } else if alive && cell.marked && theme.monochrome {
+                    (pad(MARKED_ALIVE_GLYPH, cell_width), Style::default())
+                } else if alive && cell.marked {
+                    let style =
+                        Style::default().fg(theme.color_depth.approximate(MARKED_ALIVE_RGB));
+                    (pad(&theme.alive_glyph, cell_width), style)
+                } else if alive && !decorate {
+                    (pad(&theme.alive_glyph, cell_width), Style::default())
+                } else if alive {
+                    let style = if theme.shows_colors() {
+                        let rgb = theme.palette.rgb(cell.age);
+                        let rgb = match self.fading(cell_x, cell_y) {
+                            Some(progress) => blend(FADED_RGB, rgb, progress),
+                            None => rgb,
+                        };
+                        Style::default().fg(theme.color_depth.approximate(rgb))
+                    } else {
+                        Style::default()
+                    };
+                    (pad(theme.glyph_for_age(cell.age), cell_width), style)
+                } else if cell.marked && theme.monochrome {
+                    (pad(MARKED_DEAD_GLYPH, cell_width), Style::default())
+                } else if !decorate {
+                    let style = match cell.marked {
+                        true => Style::default().bg(theme.color_depth.approximate(MARKED_DEAD_RGB)),
+                        false => Style::default(),
+                    };
+                    (theme.dead_glyph.clone(), style)
+                } else if let Some(progress) =
+                    self.fading(cell_x, cell_y).filter(|_| theme.shows_colors())
+                {
+                    let rgb = blend(theme.palette.rgb(cell.age), FADED_RGB, progress);
+                    let style = Style::default().fg(theme.color_depth.approximate(rgb));
+                    (pad(&theme.alive_glyph, cell_width), style)
+                } else if self.was_alive(cell_x, cell_y) && theme.monochrome {
+                    (pad(GHOST_GLYPH, cell_width), Style::default())
+                } else if self.was_alive(cell_x, cell_y) {
+                    let style = Style::default().fg(Color::DarkGray);
+                    (pad(&theme.alive_glyph, cell_width), style)
+                } else if cell.ever_alive && theme.monochrome {
+                    (pad(HISTORY_GLYPH, cell_width), Style::default())
+                } else if cell.marked || cell.ever_alive {
+                    let rgb = if cell.marked {
+                        MARKED_DEAD_RGB
+                    } else {
+                        HISTORY_RGB
+                    };
+                    let style = Style::default().bg(theme.color_depth.approximate(rgb));
+                    (theme.dead_glyph.clone(), style)
                 } else {
-                    buf.get_mut(x, y).set_char(' ');
+                    let gray = theme.color_depth.approximate(theme.shading_gray());
+                    match theme.shading {
+                        // a monochrome theme has no gray for the checkerboard
+                        Shading::Checkerboard
+                            if (cell_x + cell_y).is_multiple_of(2) && !theme.monochrome =>
+                        {
+                            (theme.dead_glyph.clone(), Style::default().bg(gray))
+                        }
+                        Shading::Grid => (pad("·", cell_width), Style::default().fg(gray)),
+                        _ => (theme.dead_glyph.clone(), Style::default()),
+                    }
+                };
+
+                let line = glyph.repeat(zoom as usize);
+                for dy in 0..block_height {
+                    buf.set_stringn(x, y + dy, &line, block_width as usize, style);
                 }
-                relative_y += 1;
             }
-            relative_x += 1;
         }
         if *self.state() == State::Editing {
             let Coords {
-                x: mut current_x,
-                y: mut current_y,
+                x: current_x,
+                y: current_y,
             } = *self.current_coords();
-            current_x += area.left() as i16;
-            current_y += area.top() as i16;
-            buf.get_mut(current_x as u16, current_y as u16)
-                .set_bg(Color::Blue);
+            let (Ok(column), Ok(row)) = (
+                u16::try_from(current_x - left),
+                u16::try_from(current_y - top),
+            ) else {
+                return;
+            };
+            let cursor = Rect::new(
+                area.left()
+                    .saturating_add(column.saturating_mul(block_width)),
+                area.top().saturating_add(row.saturating_mul(block_height)),
+                block_width,
+                block_height,
+            );
+            if area.contains(cursor.as_position()) {
+                buf.set_style(cursor.intersection(area), theme.highlight(Color::Blue));
+            }
         }
     }
 }
 
+/// The glyph and style of a continuous automaton's cell in `state`: dead cells are blank, and
+/// the rest take a glyph and color by how alive they are.
+fn intensity_cell(theme: &Theme, state: f32) -> (String, Style) {
+    let cell_width = theme.cell_width();
+    if state < 1.0 / 255.0 {
+        return (theme.dead_glyph.clone(), Style::default());
+    }
+    let style = if theme.shows_colors() {
+        Style::default().fg(theme
+            .color_depth
+            .approximate(theme.palette.intensity_rgb(state)))
+    } else {
+        Style::default()
+    };
+    (pad(theme.glyph_for_intensity(state), cell_width), style)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::app::{Message, Preset};
+    use crate::app::{self, Area, Message};
+
+    use std::time::Duration;
 
     use super::*;
 
+    fn symbols(buf: &Buffer) -> Vec<String> {
+        let width = buf.area.width as usize;
+        buf.content
+            .chunks(width)
+            .map(|line| line.iter().map(|cell| cell.symbol()).collect())
+            .collect()
+    }
+
     #[test]
     fn render_blinker() {
-        let mut model = Model::new(5, 5, vec![3], vec![2, 3], 50);
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
         let mut buf = Buffer::empty(Rect::new(0, 0, 6, 6));
         model.load_preset(Preset::Blinker);
         model.render_ref(buf.area, &mut buf);
 
-        let expected = Buffer::with_lines(vec![
-            "      ",
-            "███   ",
-            "      ",
-            "      ",
-            "      ",
-            "      ",
-        ]);
+        let expected = vec!["      ", "███   ", "      ", "      ", "      ", "      "];
+
+        assert_eq!(symbols(&buf), expected);
+        assert_eq!(buf.get(0, 0).bg, Color::Blue);
+
+        model.update(Message::ToggleEditing);
+        model.update(Message::Idle);
+        model.render_ref(buf.area, &mut buf);
+
+        let expected = vec![" █    ", " █    ", " █    ", "      ", "      ", "      "];
 
-        assert_eq!(buf, expected);
+        assert_eq!(symbols(&buf), expected);
+    }
 
+    #[test]
+    fn render_monochrome() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_theme(Theme::named("mono").unwrap());
+        model.set_ghost(true);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 6));
+        model.load_preset(Preset::Blinker);
         model.update(Message::ToggleEditing);
         model.update(Message::Idle);
         model.render_ref(buf.area, &mut buf);
 
-        let expected = Buffer::with_lines(vec![
-            " █    ", " █    ", " █    ", "      ", "      ", "      ",
-        ]);
+        // newborn cells are the lightest step of the ramp, the survivor the next one up, and
+        // the cells that just died ghosts
+        let expected = vec![" ·    ", "○∙○   ", " ·    ", "      ", "      ", "      "];
+        assert_eq!(symbols(&buf), expected);
+        assert!(buf
+            .content
+            .iter()
+            .all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset));
+
+        model.update(Message::ToggleEditing);
+        model.render_ref(buf.area, &mut buf);
+        assert!(buf.get(0, 0).modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn details_from_metadata() {
+        let lines: Vec<String> = pattern_details(&Preset::Acorn)
+            .iter()
+            .map(Line::to_string)
+            .collect();
+        assert_eq!(lines[0], "Acorn");
+        assert_eq!(lines[1], "7x3");
+        assert_eq!(lines[2], "by Charles Corderman");
+        assert_eq!(lines[3], "year 1971");
+    }
+
+    #[test]
+    fn render_zoom_inset() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 14, 8));
+        let center = Coords { x: 1, y: 1 };
+        ZoomInset {
+            model: &model,
+            center,
+        }
+        .render(buf.area, &mut buf);
+
+        // three cells across and three down, centered on the blinker's middle cell
+        for x in 1..13 {
+            assert_ne!(buf.get(x, 3).bg, Color::Reset);
+            assert_ne!(buf.get(x, 4).bg, Color::Reset);
+        }
+        assert_eq!(buf.get(1, 1).bg, Color::Reset);
+        assert_eq!(buf.get(5, 5).bg, Color::Reset);
+    }
+
+    #[test]
+    fn render_zoomed() {
+        let mut model = Model::new(5, 5, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.load_preset(Preset::Blinker);
+        model.update(Message::Zoom(1));
+        model.update(Message::Move(app::Direction::Down, 3));
+        model.fit_viewport(Area::new(0, 0, 6, 4));
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 4));
+        model.render_ref(buf.area, &mut buf);
+
+        // the viewport scrolled down to keep the cursor on screen
+        assert_eq!(*model.viewport(), Coords { x: 0, y: 2 });
+        assert_eq!(symbols(&buf), vec!["      "; 4]);
+        assert_eq!(buf.get(0, 2).bg, Color::Blue);
+        assert_eq!(buf.get(1, 3).bg, Color::Blue);
+
+        model.update(Message::Move(app::Direction::Up, 2));
+        model.fit_viewport(Area::new(0, 0, 6, 4));
+        model.render_ref(buf.area, &mut buf);
+        assert_eq!(*model.viewport(), Coords { x: 0, y: 1 });
+        assert_eq!(symbols(&buf), vec!["██████", "██████", "      ", "      "]);
+    }
+
+    #[test]
+    fn render_double_width() {
+        let mut model = Model::new(2, 2, vec![3], vec![2, 3], Duration::from_millis(50));
+        model.set_theme(Theme::named("square").unwrap());
+        model.update(Message::Move(app::Direction::Right, 1));
+        model.update(Message::ToggleCellState);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 2));
+        model.render_ref(buf.area, &mut buf);
 
-        assert_eq!(buf, expected);
+        assert_eq!(symbols(&buf), vec!["  ██  ", "      "]);
+        assert_eq!(buf.get(2, 0).bg, Color::Blue);
+        assert_eq!(buf.get(3, 0).bg, Color::Blue);
+        assert_eq!(buf.get(4, 0).bg, Color::Reset);
     }
 }
@@ -0,0 +1,316 @@
+use std::{
+    fs, io,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use tracing::{info, warn};
+
+use crate::{
+    app::{Message, Model, State},
+    headless::population,
+    status,
+};
+
+/// The color of dead cells and of the margin the board doesn't cover.
+const BACKGROUND_RGB: (u8, u8, u8) = (0, 0, 0);
+
+/// The deflate level images are compressed at, the quickest: boards are mostly runs of the
+/// background, which even that shrinks to a small part of their size.
+const COMPRESSION_LEVEL: u8 = 1;
+
+/// How often the daemon wakes up while waiting, to notice a signal to stop.
+const WAKE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An image size in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Resolution {
+    /// Parses a resolution such as `1920x1080`.
+    pub fn parse(input: &str) -> Result<Resolution, String> {
+        let error = || format!("expected a resolution such as 1920x1080, got \"{input}\"");
+        let (width, height) = input.trim().split_once(['x', 'X']).ok_or_else(error)?;
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|n| (1..=16_384).contains(n))
+        };
+        Ok(Resolution {
+            width: parse(width).ok_or_else(error)?,
+            height: parse(height).ok_or_else(error)?,
+        })
+    }
+
+    /// How many cells `cell_size` pixels wide fit across and down, counting those cut off at
+    /// the right and bottom edges. Boards are at least 2 cells a side, so in an image smaller
+    /// than that the second cell is cut off entirely.
+    pub fn cells(&self, cell_size: u16) -> (u16, u16) {
+        let cells = |pixels: u32| pixels.div_ceil(u32::from(cell_size)).clamp(2, 4096) as u16;
+        (cells(self.width), cells(self.height))
+    }
+}
+
+/// How the daemon draws the board and how often.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub resolution: Resolution,
+    /// Pixels per cell across and down.
+    pub cell_size: u16,
+    /// The shortest time between images.
+    pub interval: Duration,
+    /// Whether to start over from a random soup once the board dies out or repeats.
+    pub reseed: bool,
+}
+
+/// Steps `model` at its tickrate without a terminal, replacing the image at `path` with the
+/// board at most once every [`Settings::interval`], until `shutdown` is set.
+pub fn run(
+    model: &mut Model,
+    settings: &Settings,
+    path: &Path,
+    shutdown: &AtomicBool,
+) -> io::Result<()> {
+    info!(path = %path.display(), ?settings, "wallpaper daemon started");
+    let mut written: Option<Instant> = None;
+    while !shutdown.load(Ordering::Relaxed) {
+        status::publish(model);
+        #[cfg(feature = "prometheus")]
+        crate::prometheus::record(model);
+        if written.is_none_or(|at| at.elapsed() >= settings.interval) {
+            write(path, &png(model, settings))?;
+            written = Some(Instant::now());
+        }
+        sleep(model.tickrate(), shutdown);
+
+        if settings.reseed && (population(model) == 0 || model.stabilization().is_some()) {
+            info!(generation = model.generation(), "reseeding the wallpaper");
+            model.update(Message::Randomize);
+        }
+        if *model.state() == State::Editing {
+            model.update(Message::ToggleEditing);
+        }
+        model.update(Message::Idle);
+    }
+    info!("wallpaper daemon stopped");
+    Ok(())
+}
+
+/// Sleeps for `duration`, or until `shutdown` is set.
+fn sleep(duration: Duration, shutdown: &AtomicBool) {
+    let until = Instant::now() + duration;
+    while !shutdown.load(Ordering::Relaxed) {
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        thread::sleep(left.min(WAKE_INTERVAL));
+    }
+}
+
+/// Replaces the image at `path` with `bytes` all at once, so a wallpaper setter reading it
+/// never sees half an image.
+fn write(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    fs::write(&partial, bytes)?;
+    fs::rename(&partial, path).inspect_err(|error| {
+        warn!(%error, path = %path.display(), "couldn't replace the wallpaper");
+    })
+}
+
+/// The color of each cell on the board, a row at a time: the theme's color for its age, or
+/// for its state under a continuous automaton.
+pub fn colors(model: &Model) -> Vec<(u8, u8, u8)> {
+    let (width, height) = model.size();
+    let palette = &model.theme().palette;
+    let mut colors = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            colors.push(match (model.intensity(x, y), model.cell(x, y)) {
+                (Some(state), _) => palette.intensity_rgb(state),
+                (None, Some(cell)) if cell.is_alive => palette.rgb(cell.age),
+                _ => BACKGROUND_RGB,
+            });
+        }
+    }
+    colors
+}
+
+/// The pixels of a `resolution` image of a board `columns` cells across, given the
+/// [`colors`] of its cells, a row at a time: each cell a square of `cell_size` pixels, with
+/// the background wherever the image runs past the board.
+pub fn pixels(
+    colors: &[(u8, u8, u8)],
+    columns: usize,
+    resolution: Resolution,
+    cell_size: u16,
+) -> Vec<(u8, u8, u8)> {
+    let Resolution { width, height } = resolution;
+    let cell_size = u32::from(cell_size);
+    let mut pixels = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height {
+        let row = (y / cell_size) as usize * columns;
+        for x in 0..width {
+            let column = (x / cell_size) as usize;
+            let rgb = (column < columns)
+                .then(|| colors.get(row + column))
+                .flatten();
+            pixels.push(rgb.copied().unwrap_or(BACKGROUND_RGB));
+        }
+    }
+    pixels
+}
+
+/// The board as a PNG image.
+fn png(model: &Model, settings: &Settings) -> Vec<u8> {
+    let Resolution { width, height } = settings.resolution;
+    let pixels = pixels(
+        &colors(model),
+        model.size().0,
+        settings.resolution,
+        settings.cell_size,
+    );
+    encode(width, height, &pixels)
+}
+
+/// Encodes `width` by `height` RGB pixels as a PNG, without filtering rows before compressing
+/// them.
+pub fn encode(width: u32, height: u32, pixels: &[(u8, u8, u8)]) -> Vec<u8> {
+    // each row starts with a filter type, 0 for none
+    let mut raw = Vec::with_capacity(pixels.len() * 3 + height as usize);
+    for row in pixels.chunks(width as usize) {
+        raw.push(0);
+        raw.extend(row.iter().flat_map(|&(r, g, b)| [r, g, b]));
+    }
+
+    let mut header = vec![];
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    // 8 bits per channel, RGB, deflate, standard filters, not interlaced
+    header.extend([8, 2, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(
+        &mut png,
+        b"IDAT",
+        &compress_to_vec_zlib(&raw, COMPRESSION_LEVEL),
+    );
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+    png.extend(crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xedb8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_resolutions() {
+        let resolution = Resolution::parse("1920x1080").unwrap();
+        assert_eq!(
+            resolution,
+            Resolution {
+                width: 1920,
+                height: 1080
+            }
+        );
+        assert_eq!(resolution.cells(8), (240, 135));
+        assert_eq!(resolution.cells(7), (275, 155));
+        assert_eq!(Resolution::parse("8x8").unwrap().cells(8), (2, 2));
+        assert_eq!(Resolution::parse("1x100").unwrap().cells(8), (2, 13));
+        assert!(Resolution::parse("1920").is_err());
+        assert!(Resolution::parse("0x10").is_err());
+    }
+
+    #[test]
+    fn scales_cells_to_pixels() {
+        let (a, b, c, d) = ((1, 1, 1), (2, 2, 2), (3, 3, 3), (4, 4, 4));
+        let resolution = Resolution {
+            width: 5,
+            height: 3,
+        };
+        let x = BACKGROUND_RGB;
+        assert_eq!(
+            pixels(&[a, b, c, d], 2, resolution, 2),
+            [a, a, b, b, x, a, a, b, b, x, c, c, d, d, x]
+        );
+    }
+
+    /// The chunks of `png` by kind, checking each one's CRC.
+    fn chunks(png: &[u8]) -> Vec<([u8; 4], &[u8])> {
+        let mut chunks = vec![];
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let (kind, data) = (&rest[4..8], &rest[8..8 + length]);
+            let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+            assert_eq!(crc, crc32(kind.iter().chain(data)));
+            chunks.push((kind.try_into().unwrap(), data));
+            rest = &rest[12 + length..];
+        }
+        chunks
+    }
+
+    #[test]
+    fn encodes_png() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+
+        let png = encode(2, 1, &[(255, 0, 0), (0, 0, 255)]);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let chunks = chunks(&png);
+        let kinds = chunks.iter().map(|(kind, _)| kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 2, 0, 0, 0, 1, 8, 2, 0, 0, 0]);
+        // a filter byte and two pixels
+        let raw = miniz_oxide::inflate::decompress_to_vec_zlib(chunks[1].1).unwrap();
+        assert_eq!(raw, [0, 255, 0, 0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn compresses_png() {
+        let (width, height) = (1920, 1080);
+        let mut pixels = vec![BACKGROUND_RGB; width * height];
+        pixels[width * 500..width * 510].fill((0, 200, 100));
+        let png = encode(width as u32, height as u32, &pixels);
+        assert!(png.len() < 100_000, "{} bytes", png.len());
+
+        let raw = miniz_oxide::inflate::decompress_to_vec_zlib(chunks(&png)[1].1).unwrap();
+        let rows = raw.chunks(width * 3 + 1).collect::<Vec<_>>();
+        assert_eq!(rows.len(), height);
+        assert!(rows.iter().all(|row| row[0] == 0));
+        assert_eq!(rows[505][1..4], [0, 200, 100]);
+        assert_eq!(rows[510][1..4], [0, 0, 0]);
+    }
+}
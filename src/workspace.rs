@@ -0,0 +1,101 @@
+use std::{fs, io, path::Path};
+
+use tracing::info;
+
+use crate::{app::Preset, pattern::Pattern};
+
+/// Several boards shipped as one file, such as the examples for a lesson. A workspace file is
+/// a run of RLE patterns one after another, each ending with its `!`. A board's `#N` line
+/// names it, its header gives its rule, and its `#C` lines are notes shown beside it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Workspace {
+    boards: Vec<Preset>,
+}
+
+impl Workspace {
+    pub fn load(path: &Path) -> io::Result<Workspace> {
+        info!(path = %path.display(), "opening workspace");
+        let text = fs::read_to_string(path)?;
+        Workspace::parse(&text).map_err(|reason| io::Error::new(io::ErrorKind::InvalidData, reason))
+    }
+
+    /// Parses a workspace, naming unnamed boards by their position. Fails if any board can't
+    /// be read or there are none.
+    pub fn parse(text: &str) -> Result<Workspace, String> {
+        let mut boards = vec![];
+        let mut rle = String::new();
+        for line in text.lines() {
+            rle.push_str(line);
+            rle.push('\n');
+            if line.trim_start().starts_with('#') || !line.contains('!') {
+                continue;
+            }
+
+            let number = boards.len() + 1;
+            let pattern =
+                Pattern::from_rle(&rle).map_err(|reason| format!("board {number}: {reason}"))?;
+            let name = pattern
+                .metadata
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("Board {number}"));
+            boards.push(Preset::User { name, pattern });
+            rle.clear();
+        }
+
+        if boards.is_empty() {
+            return Err(String::from("no boards"));
+        }
+        Ok(Workspace { boards })
+    }
+
+    pub fn boards(&self) -> &[Preset] {
+        &self.boards
+    }
+
+    /// The board called `name`.
+    pub fn board(&self, name: &str) -> Option<&Preset> {
+        self.boards.iter().find(|board| board.name() == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_boards() {
+        let text = "\
+#N Blinker
+#C Period 2! The simplest oscillator.
+x = 3, y = 1, rule = B3/S23
+3o!
+
+x = 3, y = 3, rule = B36/S23
+bo$2bo$3o!
+";
+        let workspace = Workspace::parse(text).unwrap();
+        let names: Vec<&str> = workspace.boards().iter().map(Preset::name).collect();
+        assert_eq!(names, ["Blinker", "Board 2"]);
+
+        let Some(Preset::User { pattern, .. }) = workspace.board("Board 2") else {
+            panic!("no second board");
+        };
+        assert_eq!(pattern.rule.as_deref(), Some("B36/S23"));
+        let Some(Preset::User { pattern, .. }) = workspace.board("Blinker") else {
+            panic!("no first board");
+        };
+        assert_eq!(
+            pattern.metadata.comments,
+            ["Period 2! The simplest oscillator."]
+        );
+
+        assert!(Workspace::parse("").is_err());
+        assert_eq!(
+            Workspace::parse("3o!\nx = 1, y = 1\n3o!\n"),
+            Err(String::from(
+                "board 2: pattern is larger than its 1x1 header"
+            ))
+        );
+    }
+}